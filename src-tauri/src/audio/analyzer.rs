@@ -0,0 +1,187 @@
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::Arc;
+use realfft::num_complex::Complex;
+use realfft::{RealFftPlanner, RealToComplex};
+use serde::Serialize;
+
+// 스펙트럼 분석 FFT 길이 (프레임)
+pub const SPECTRUM_FFT_SIZE: usize = 2048;
+// 로그 간격 밴드 수 / 주파수 범위
+pub const SPECTRUM_BAND_COUNT: usize = 32;
+const SPECTRUM_MIN_HZ: f32 = 20.0;
+const SPECTRUM_MAX_HZ: f32 = 20000.0;
+// dB 하한 (무음)
+pub const SPECTRUM_FLOOR_DB: f32 = -100.0;
+// 콜백 -> 분석 스레드 링 버퍼 크기 (프레임, 2의 거듭제곱)
+const TAP_CAPACITY: usize = 8192;
+
+// RT 콜백이 실제로 출력한 LR 샘플을 분석 스레드와 공유하는 lock-free 링 버퍼
+// 쓰는 쪽(콜백)은 하나뿐이고, 읽는 쪽은 최신 구간만 복사함 (덮어쓰기 중인 구간이 섞여도 시각화에는 문제없음)
+pub struct AnalysisTap {
+    samples: Box<[AtomicU32]>, // LR 인터리브 (f32 bits)
+    write_pos: AtomicU64,      // 지금까지 쓴 누적 프레임 수
+}
+
+impl Default for AnalysisTap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AnalysisTap {
+    pub fn new() -> Self {
+        Self {
+            samples: (0..TAP_CAPACITY * 2).map(|_| AtomicU32::new(0)).collect(),
+            write_pos: AtomicU64::new(0),
+        }
+    }
+
+    // 콜백에서 호출: 출력한 프레임을 순서대로 기록 (할당 / 잠금 없음)
+    pub fn push(&self, pos: u64, l: f32, r: f32) {
+        let index = (pos as usize & (TAP_CAPACITY - 1)) * 2;
+        self.samples[index].store(l.to_bits(), Ordering::Relaxed);
+        self.samples[index + 1].store(r.to_bits(), Ordering::Relaxed);
+    }
+
+    pub fn write_pos(&self) -> u64 {
+        self.write_pos.load(Ordering::Acquire)
+    }
+
+    // 콜백 끝에서 호출: push한 프레임을 읽는 쪽에 공개
+    pub fn publish(&self, pos: u64) {
+        self.write_pos.store(pos, Ordering::Release);
+    }
+
+    // 최근 frames 프레임을 LR 인터리브로 복사 (아직 쓴 적 없는 부분은 0)
+    pub fn read_latest(&self, frames: usize, out: &mut Vec<f32>) {
+        let frames = frames.min(TAP_CAPACITY);
+        let end = self.write_pos();
+        out.clear();
+        out.resize(frames * 2, 0.0);
+        let available = (end as usize).min(frames);
+        let start = end - available as u64;
+        let offset = frames - available;
+        for i in 0..available {
+            let index = ((start + i as u64) as usize & (TAP_CAPACITY - 1)) * 2;
+            out[(offset + i) * 2] = f32::from_bits(self.samples[index].load(Ordering::Relaxed));
+            out[(offset + i) * 2 + 1] = f32::from_bits(self.samples[index + 1].load(Ordering::Relaxed));
+        }
+    }
+}
+
+// 비주얼라이저용 분석 결과 (dBFS)
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpectrumFrame {
+    pub bands: Vec<f32>,      // 로그 간격 밴드별 크기 (낮은 주파수부터)
+    pub band_hz: Vec<f32>,    // 밴드 중심 주파수
+    pub peak_db: [f32; 2],    // LR 피크 (마지막 분석 구간)
+    pub rms_db: [f32; 2],     // LR RMS (마지막 분석 구간)
+}
+
+fn to_db(amplitude: f32) -> f32 {
+    if amplitude > 0.0 {
+        (20.0 * amplitude.log10()).max(SPECTRUM_FLOOR_DB)
+    } else {
+        SPECTRUM_FLOOR_DB
+    }
+}
+
+// 분석 스레드에서 사용하는 FFT 스펙트럼 + 레벨 미터
+pub struct SpectrumAnalyzer {
+    fft: Arc<dyn RealToComplex<f32>>,
+    window: Vec<f32>,
+    window_gain: f32,
+    input: Vec<f32>,
+    spectrum: Vec<Complex<f32>>,
+    samples: Vec<f32>,
+    band_bins: Vec<(usize, usize)>, // 밴드별 FFT bin 범위 [start, end)
+    band_hz: Vec<f32>,
+}
+
+impl SpectrumAnalyzer {
+    pub fn new(sample_rate: u32) -> Self {
+        let fft = RealFftPlanner::<f32>::new().plan_fft_forward(SPECTRUM_FFT_SIZE);
+        // Hann 창
+        let window: Vec<f32> = (0..SPECTRUM_FFT_SIZE)
+            .map(|i| 0.5 - 0.5 * (2.0 * std::f32::consts::PI * i as f32 / SPECTRUM_FFT_SIZE as f32).cos())
+            .collect();
+        let window_gain = window.iter().sum::<f32>() / 2.0;
+
+        let bin_hz = sample_rate as f32 / SPECTRUM_FFT_SIZE as f32;
+        let max_hz = SPECTRUM_MAX_HZ.min(sample_rate as f32 / 2.0);
+        let ratio = (max_hz / SPECTRUM_MIN_HZ).powf(1.0 / SPECTRUM_BAND_COUNT as f32);
+        let bin_count = SPECTRUM_FFT_SIZE / 2 + 1;
+        let mut band_bins = Vec::with_capacity(SPECTRUM_BAND_COUNT);
+        let mut band_hz = Vec::with_capacity(SPECTRUM_BAND_COUNT);
+        for band in 0..SPECTRUM_BAND_COUNT {
+            let low = SPECTRUM_MIN_HZ * ratio.powi(band as i32);
+            let high = low * ratio;
+            // 저역 밴드는 bin 하나보다 좁으므로 최소 1개 bin은 포함
+            let start = ((low / bin_hz).round() as usize).clamp(1, bin_count - 1);
+            let end = ((high / bin_hz).round() as usize).clamp(start + 1, bin_count);
+            band_bins.push((start, end));
+            band_hz.push((low * high).sqrt());
+        }
+
+        Self {
+            input: fft.make_input_vec(),
+            spectrum: fft.make_output_vec(),
+            fft,
+            window,
+            window_gain,
+            samples: Vec::with_capacity(SPECTRUM_FFT_SIZE * 2),
+            band_bins,
+            band_hz,
+        }
+    }
+
+    // 최근 SPECTRUM_FFT_SIZE 프레임의 스펙트럼 + 최근 level_frames 프레임의 피크 / RMS
+    pub fn analyze(&mut self, tap: &AnalysisTap, level_frames: usize) -> SpectrumFrame {
+        tap.read_latest(SPECTRUM_FFT_SIZE, &mut self.samples);
+
+        let level_frames = level_frames.clamp(1, SPECTRUM_FFT_SIZE);
+        let mut peak = [0.0f32; 2];
+        let mut sum_sq = [0.0f64; 2];
+        for frame in self.samples[(SPECTRUM_FFT_SIZE - level_frames) * 2..].chunks_exact(2) {
+            for ch in 0..2 {
+                peak[ch] = peak[ch].max(frame[ch].abs());
+                sum_sq[ch] += (frame[ch] as f64) * (frame[ch] as f64);
+            }
+        }
+        let rms = sum_sq.map(|sum| (sum / level_frames as f64).sqrt() as f32);
+
+        // 모노 합으로 스펙트럼 계산
+        for (i, frame) in self.samples.chunks_exact(2).enumerate() {
+            self.input[i] = (frame[0] + frame[1]) * 0.5 * self.window[i];
+        }
+        let bands = if self.fft.process(&mut self.input, &mut self.spectrum).is_ok() {
+            self.band_bins
+                .iter()
+                .map(|&(start, end)| {
+                    let magnitude = self.spectrum[start..end].iter().map(|c| c.norm()).fold(0.0f32, f32::max);
+                    to_db(magnitude / self.window_gain)
+                })
+                .collect()
+        } else {
+            vec![SPECTRUM_FLOOR_DB; SPECTRUM_BAND_COUNT]
+        };
+
+        SpectrumFrame {
+            bands,
+            band_hz: self.band_hz.clone(),
+            peak_db: peak.map(to_db),
+            rms_db: rms.map(to_db),
+        }
+    }
+
+    // 출력이 멈췄을 때(일시정지 등) 보내는 무음 프레임
+    pub fn silent_frame(&self) -> SpectrumFrame {
+        SpectrumFrame {
+            bands: vec![SPECTRUM_FLOOR_DB; SPECTRUM_BAND_COUNT],
+            band_hz: self.band_hz.clone(),
+            peak_db: [SPECTRUM_FLOOR_DB; 2],
+            rms_db: [SPECTRUM_FLOOR_DB; 2],
+        }
+    }
+}
@@ -0,0 +1,266 @@
+use serde::{Deserialize, Serialize};
+use symphonia::core::audio::Channels;
+
+// -3dB (ITU-R BS.775 다운믹스 계수)
+const MINUS_3DB: f32 = std::f32::consts::FRAC_1_SQRT_2;
+
+// 멀티채널 원본 -> LR 다운믹스 계수 (ITU-R BS.775, 입력 채널 순서대로 [L 계수, R 계수])
+// Lo = L + 0.707 C + 0.707 Ls, Ro = R + 0.707 C + 0.707 Rs (LFE는 규격대로 제외)
+// 합쳐진 신호가 클리핑되지 않도록 한쪽 계수 합이 1이 되게 정규화
+pub fn stereo_downmix_matrix(channels: Channels) -> Vec<[f32; 2]> {
+    let mut matrix: Vec<[f32; 2]> = channels
+        .iter()
+        .map(|channel| match channel {
+            Channels::FRONT_LEFT
+            | Channels::FRONT_LEFT_CENTRE
+            | Channels::FRONT_LEFT_WIDE
+            | Channels::FRONT_LEFT_HIGH
+            | Channels::TOP_FRONT_LEFT => [1.0, 0.0],
+            Channels::FRONT_RIGHT
+            | Channels::FRONT_RIGHT_CENTRE
+            | Channels::FRONT_RIGHT_WIDE
+            | Channels::FRONT_RIGHT_HIGH
+            | Channels::TOP_FRONT_RIGHT => [0.0, 1.0],
+            Channels::FRONT_CENTRE
+            | Channels::FRONT_CENTRE_HIGH
+            | Channels::TOP_FRONT_CENTRE
+            | Channels::TOP_CENTRE => [MINUS_3DB, MINUS_3DB],
+            Channels::REAR_LEFT
+            | Channels::SIDE_LEFT
+            | Channels::REAR_LEFT_CENTRE
+            | Channels::TOP_REAR_LEFT => [MINUS_3DB, 0.0],
+            Channels::REAR_RIGHT
+            | Channels::SIDE_RIGHT
+            | Channels::REAR_RIGHT_CENTRE
+            | Channels::TOP_REAR_RIGHT => [0.0, MINUS_3DB],
+            Channels::REAR_CENTRE | Channels::TOP_REAR_CENTRE => [0.5, 0.5],
+            _ => [0.0, 0.0], // LFE1 / LFE2
+        })
+        .collect();
+
+    let sum_l: f32 = matrix.iter().map(|c| c[0]).sum();
+    let sum_r: f32 = matrix.iter().map(|c| c[1]).sum();
+    let norm = sum_l.max(sum_r);
+    if norm > 1.0 {
+        for coefficients in &mut matrix {
+            coefficients[0] /= norm;
+            coefficients[1] /= norm;
+        }
+    }
+    matrix
+}
+
+// 출력 장치 채널의 스피커 위치
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutputChannelRole {
+    Mono,
+    FrontLeft,
+    FrontRight,
+    Center,
+    Lfe,
+    SurroundLeft,
+    SurroundRight,
+    SideLeft,
+    SideRight,
+    Other,
+}
+
+// 장치 채널 수에 따른 표준 채널 순서
+// Windows(WASAPI) / macOS는 WAVE 순서 (FL FR FC LFE BL BR SL SR)
+#[cfg(not(target_os = "linux"))]
+pub fn output_channel_roles(channels: usize) -> Vec<OutputChannelRole> {
+    use OutputChannelRole::*;
+    let layout: &[OutputChannelRole] = match channels {
+        0 => &[],
+        1 => &[Mono],
+        2 => &[FrontLeft, FrontRight],
+        3 => &[FrontLeft, FrontRight, Center],
+        4 => &[FrontLeft, FrontRight, SurroundLeft, SurroundRight],
+        5 => &[FrontLeft, FrontRight, Center, SurroundLeft, SurroundRight],
+        6 => &[FrontLeft, FrontRight, Center, Lfe, SurroundLeft, SurroundRight],
+        7 => &[FrontLeft, FrontRight, Center, Lfe, Other, SurroundLeft, SurroundRight],
+        _ => &[FrontLeft, FrontRight, Center, Lfe, SurroundLeft, SurroundRight, SideLeft, SideRight],
+    };
+    pad_roles(layout, channels)
+}
+
+// ALSA 기본 순서 (FL FR RL RR FC LFE SL SR)
+#[cfg(target_os = "linux")]
+pub fn output_channel_roles(channels: usize) -> Vec<OutputChannelRole> {
+    use OutputChannelRole::*;
+    let layout: &[OutputChannelRole] = match channels {
+        0 => &[],
+        1 => &[Mono],
+        2 => &[FrontLeft, FrontRight],
+        3 => &[FrontLeft, FrontRight, Lfe],
+        4 => &[FrontLeft, FrontRight, SurroundLeft, SurroundRight],
+        5 => &[FrontLeft, FrontRight, SurroundLeft, SurroundRight, Center],
+        6 | 7 => &[FrontLeft, FrontRight, SurroundLeft, SurroundRight, Center, Lfe],
+        _ => &[FrontLeft, FrontRight, SurroundLeft, SurroundRight, Center, Lfe, SideLeft, SideRight],
+    };
+    pad_roles(layout, channels)
+}
+
+fn pad_roles(layout: &[OutputChannelRole], channels: usize) -> Vec<OutputChannelRole> {
+    let mut roles = layout.to_vec();
+    roles.resize(channels, OutputChannelRole::Other);
+    roles
+}
+
+// 원본 채널을 내보낼 수 있는 스피커 위치 (앞쪽이 우선, 비어 있으면 따로 내보낼 수 없는 채널)
+// 5.1 원본의 서라운드는 장치에 따라 REAR / SIDE로 표기가 달라서 서로 대신할 수 있게 함
+fn channel_role_candidates(channel: Channels) -> &'static [OutputChannelRole] {
+    use OutputChannelRole::*;
+    match channel {
+        Channels::FRONT_LEFT => &[FrontLeft],
+        Channels::FRONT_RIGHT => &[FrontRight],
+        Channels::FRONT_CENTRE => &[Center],
+        Channels::LFE1 => &[Lfe],
+        Channels::REAR_LEFT => &[SurroundLeft, SideLeft],
+        Channels::REAR_RIGHT => &[SurroundRight, SideRight],
+        Channels::SIDE_LEFT => &[SideLeft, SurroundLeft],
+        Channels::SIDE_RIGHT => &[SideRight, SurroundRight],
+        _ => &[],
+    }
+}
+
+// 원본 채널마다 서로 다른 roles 위치를 하나씩 배정 (입력 채널 순서대로 roles 인덱스)
+// 하나라도 배정할 수 없으면 None
+fn place_channels(channels: Channels, roles: &[OutputChannelRole]) -> Option<Vec<usize>> {
+    let mut used = vec![false; roles.len()];
+    channels
+        .iter()
+        .map(|channel| {
+            let index = channel_role_candidates(channel)
+                .iter()
+                .find_map(|candidate| (0..roles.len()).find(|&i| roles[i] == *candidate && !used[i]))?;
+            used[index] = true;
+            Some(index)
+        })
+        .collect()
+}
+
+// 디코딩 파이프라인이 다룰 채널 구성 (앞의 두 채널은 항상 FrontLeft / FrontRight)
+// 멀티채널 원본의 모든 채널을 출력 장치 스피커에 따로 내보낼 수 있으면 원본 채널 구성을 그대로 유지하고,
+// 그렇지 않으면 LR로 다운믹스
+pub fn pipeline_channel_layout(channels: Option<Channels>, output_channels: usize) -> Vec<OutputChannelRole> {
+    let mut layout = vec![OutputChannelRole::FrontLeft, OutputChannelRole::FrontRight];
+    let Some(channels) = channels.filter(|c| c.count() > 2) else {
+        return layout;
+    };
+    let device_roles = output_channel_roles(output_channels);
+    let Some(indices) = place_channels(channels, &device_roles) else {
+        return layout;
+    };
+    for role in indices.into_iter().map(|i| device_roles[i]) {
+        if !layout.contains(&role) {
+            layout.push(role);
+        }
+    }
+    layout
+}
+
+// 디코딩한 원본 채널 -> 파이프라인 채널 계수 (입력 채널 순서대로, 각각 layout 길이)
+// 원본 채널을 layout 위치에 모두 배정할 수 있으면 그대로 옮기고, 아니면 앞쪽 L/R로 다운믹스
+// (모노는 양쪽에 같은 신호, 스테레오는 그대로, 그 이상은 stereo_downmix_matrix)
+pub fn channel_routing_matrix(channels: Channels, layout: &[OutputChannelRole]) -> Vec<Vec<f32>> {
+    let width = layout.len();
+    let one_hot = |index: usize| {
+        let mut coefficients = vec![0.0; width];
+        coefficients[index] = 1.0;
+        coefficients
+    };
+    let count = channels.count();
+    if count > 2 && width > 2 {
+        if let Some(indices) = place_channels(channels, layout) {
+            return indices.into_iter().map(one_hot).collect();
+        }
+    }
+    match count {
+        0 => Vec::new(),
+        1 => {
+            let mut coefficients = vec![0.0; width];
+            coefficients[0] = 1.0;
+            coefficients[1] = 1.0;
+            vec![coefficients]
+        }
+        2 => vec![one_hot(0), one_hot(1)],
+        _ => stereo_downmix_matrix(channels)
+            .into_iter()
+            .map(|[l, r]| {
+                let mut coefficients = vec![0.0; width];
+                coefficients[0] = l;
+                coefficients[1] = r;
+                coefficients
+            })
+            .collect(),
+    }
+}
+
+// 파이프라인 채널 -> 출력 장치 채널 계수 (장치 채널 순서대로, 각각 layout 길이)
+// 멀티채널 layout이면 같은 스피커 위치로 그대로 내보냄 (원본에 없는 스피커는 무음, upmix 무시)
+// LR layout에서 upmix가 꺼져 있으면 앞쪽 L/R에만 내보내고 나머지 스피커는 무음
+// upmix가 켜져 있으면 센터에 L+R(-3dB), 서라운드에 L/R(-3dB)을 복제 (LFE는 베이스 매니지먼트가 없으므로 무음)
+pub fn output_channel_matrix(layout: &[OutputChannelRole], channels: usize, upmix: bool) -> Vec<Vec<f32>> {
+    let width = layout.len();
+    output_channel_roles(channels)
+        .into_iter()
+        .map(|role| {
+            if width > 2 {
+                let mut coefficients = vec![0.0; width];
+                if let Some(index) = layout.iter().position(|&r| r == role) {
+                    coefficients[index] = 1.0;
+                }
+                return coefficients;
+            }
+            let lr = match role {
+                OutputChannelRole::Mono => [0.5, 0.5],
+                OutputChannelRole::FrontLeft => [1.0, 0.0],
+                OutputChannelRole::FrontRight => [0.0, 1.0],
+                OutputChannelRole::Center if upmix => [0.5 * MINUS_3DB, 0.5 * MINUS_3DB],
+                OutputChannelRole::SurroundLeft | OutputChannelRole::SideLeft if upmix => [MINUS_3DB, 0.0],
+                OutputChannelRole::SurroundRight | OutputChannelRole::SideRight if upmix => [0.0, MINUS_3DB],
+                _ => [0.0, 0.0],
+            };
+            lr.to_vec()
+        })
+        .collect()
+}
+
+// 출력 LR 조정 (한쪽 청력 손실 / 배선이 잘못된 모니터 대응)
+// 적용 순서: 좌우 바꾸기 -> 모노 합산 -> 밸런스
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelOptions {
+    pub balance: f32, // -1.0 (왼쪽만) ~ 1.0 (오른쪽만), 0.0이면 가운데
+    pub mono: bool,   // L+R 합산을 양쪽에 출력
+    pub swap_channels: bool,
+}
+
+impl Default for ChannelOptions {
+    fn default() -> Self {
+        Self {
+            balance: 0.0,
+            mono: false,
+            swap_channels: false,
+        }
+    }
+}
+
+impl ChannelOptions {
+    pub fn clamped(self) -> Self {
+        Self {
+            balance: if self.balance.is_finite() { self.balance.clamp(-1.0, 1.0) } else { 0.0 },
+            ..self
+        }
+    }
+}
+
+// 밸런스 -> (L gain, R gain): 반대쪽만 줄이고 가운데에서는 둘 다 1 (음량이 커지지 않도록)
+pub fn balance_gains(balance: f32) -> (f32, f32) {
+    if balance < 0.0 {
+        (1.0, 1.0 + balance)
+    } else {
+        (1.0 - balance, 1.0)
+    }
+}
@@ -0,0 +1,292 @@
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+// moov 박스 최대 크기 (이보다 크면 손상된 파일로 보고 챕터를 읽지 않음)
+const MP4_MOOV_MAX_BYTES: u64 = 64 * 1024 * 1024;
+// Nero 챕터(chpl) 시간 단위 (100ns)
+const CHPL_TIMESCALE: f64 = 10_000_000.0;
+// QuickTime 챕터 트랙에서 읽는 최대 챕터 수 (손상된 stts 방어)
+const MP4_MAX_CHAPTERS: usize = 10_000;
+
+// 오디오북 / 팟캐스트 챕터 (초)
+#[derive(Debug, Clone)]
+pub struct Chapter {
+    pub title: String,
+    pub start: f64,
+    pub end: Option<f64>, // 없으면 다음 챕터 시작 (마지막 챕터는 파일 끝)
+}
+
+// 파일에 들어 있는 챕터 목록 (MP3: ID3 CHAP/CTOC, MP4/M4B: Nero chpl 또는 QuickTime 챕터 트랙)
+pub fn read_chapters(file_path: &str) -> Vec<Chapter> {
+    let extension = Path::new(file_path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|s| s.to_lowercase());
+
+    let mut chapters = match extension.as_deref() {
+        Some("mp3") => read_id3_chapters(file_path),
+        Some("m4a" | "m4b" | "mp4" | "aac") => read_mp4_chapters(file_path).unwrap_or_default(),
+        _ => Vec::new(),
+    };
+
+    chapters.retain(|chapter| chapter.start.is_finite() && chapter.start >= 0.0);
+    chapters.sort_by(|a, b| a.start.total_cmp(&b.start));
+    chapters.dedup_by(|b, a| (a.start - b.start).abs() < 0.001);
+    for i in 0..chapters.len() {
+        let next_start = chapters.get(i + 1).map(|next| next.start);
+        let chapter = &mut chapters[i];
+        if chapter.end.is_none_or(|end| end <= chapter.start) {
+            chapter.end = next_start;
+        }
+        if chapter.title.trim().is_empty() {
+            chapter.title = format!("Chapter {}", i + 1);
+        }
+    }
+    chapters
+}
+
+fn read_id3_chapters(file_path: &str) -> Vec<Chapter> {
+    let Ok(tag) = id3::Tag::read_from_path(file_path) else {
+        return Vec::new();
+    };
+
+    let to_chapter = |chap: &id3::frame::Chapter| Chapter {
+        title: chap
+            .frames
+            .iter()
+            .find(|frame| frame.id() == "TIT2")
+            .and_then(|frame| frame.content().text())
+            .unwrap_or_default()
+            .to_string(),
+        start: chap.start_time as f64 / 1000.0,
+        end: Some(chap.end_time as f64 / 1000.0),
+    };
+
+    // 최상위 CTOC가 있으면 그 순서의 챕터만 (없으면 CHAP 전체)
+    let chapters: Vec<&id3::frame::Chapter> = tag.chapters().collect();
+    let toc = tag
+        .tables_of_contents()
+        .find(|toc| toc.top_level)
+        .or_else(|| tag.tables_of_contents().next());
+    match toc {
+        Some(toc) => toc
+            .elements
+            .iter()
+            .filter_map(|id| chapters.iter().find(|chap| &chap.element_id == id))
+            .map(|chap| to_chapter(chap))
+            .collect(),
+        None => chapters.into_iter().map(to_chapter).collect(),
+    }
+}
+
+fn be_u32(data: &[u8], pos: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(data.get(pos..pos + 4)?.try_into().ok()?))
+}
+
+fn be_u64(data: &[u8], pos: usize) -> Option<u64> {
+    Some(u64::from_be_bytes(data.get(pos..pos + 8)?.try_into().ok()?))
+}
+
+// 메모리에 읽은 박스 내용의 자식 박스 목록 (type, payload)
+fn mp4_children(data: &[u8]) -> Vec<([u8; 4], &[u8])> {
+    let mut boxes = Vec::new();
+    let mut pos = 0usize;
+    while pos + 8 <= data.len() {
+        let Some(size32) = be_u32(data, pos) else { break };
+        let kind: [u8; 4] = data[pos + 4..pos + 8].try_into().unwrap_or_default();
+        let (header, size) = match size32 {
+            0 => (8, (data.len() - pos) as u64),
+            1 => match be_u64(data, pos + 8) {
+                Some(size) => (16, size),
+                None => break,
+            },
+            size => (8, size as u64),
+        };
+        if size < header as u64 || pos as u64 + size > data.len() as u64 {
+            break;
+        }
+        boxes.push((kind, &data[pos + header..pos + size as usize]));
+        pos += size as usize;
+    }
+    boxes
+}
+
+fn mp4_child<'a>(data: &'a [u8], kind: &[u8; 4]) -> Option<&'a [u8]> {
+    mp4_children(data).into_iter().find(|(k, _)| k == kind).map(|(_, payload)| payload)
+}
+
+// 파일 최상위 박스를 훑어 moov만 읽음 (오디오 데이터는 읽지 않음)
+fn read_moov(file: &mut File) -> Option<Vec<u8>> {
+    let file_len = file.metadata().ok()?.len();
+    let mut pos = 0u64;
+    while pos + 8 <= file_len {
+        file.seek(SeekFrom::Start(pos)).ok()?;
+        let mut header = [0u8; 16];
+        file.read_exact(&mut header[..8]).ok()?;
+        let size32 = u32::from_be_bytes(header[..4].try_into().ok()?);
+        let (header_len, size) = match size32 {
+            0 => (8, file_len - pos),
+            1 => {
+                file.read_exact(&mut header[8..16]).ok()?;
+                (16, u64::from_be_bytes(header[8..16].try_into().ok()?))
+            }
+            size => (8, size as u64),
+        };
+        if size < header_len {
+            return None;
+        }
+        if &header[4..8] == b"moov" {
+            let payload_len = size - header_len;
+            if payload_len > MP4_MOOV_MAX_BYTES {
+                return None;
+            }
+            let mut payload = vec![0u8; payload_len as usize];
+            file.read_exact(&mut payload).ok()?;
+            return Some(payload);
+        }
+        pos += size;
+    }
+    None
+}
+
+fn read_mp4_chapters(file_path: &str) -> Option<Vec<Chapter>> {
+    let mut file = File::open(file_path).ok()?;
+    let moov = read_moov(&mut file)?;
+
+    // QuickTime 챕터 트랙 (iTunes / 대부분의 M4B) 우선, 없으면 Nero chpl
+    if let Some(chapters) = read_quicktime_chapters(&mut file, &moov).filter(|c| !c.is_empty()) {
+        return Some(chapters);
+    }
+    let chpl = mp4_child(mp4_child(&moov, b"udta")?, b"chpl")?;
+    parse_chpl(chpl)
+}
+
+// Nero 챕터: version(1) flags(3) [reserved(4) if version 1] count(1) { start(8, 100ns) len(1) title }
+fn parse_chpl(data: &[u8]) -> Option<Vec<Chapter>> {
+    let version = *data.first()?;
+    let mut pos = if version >= 1 { 8 } else { 4 };
+    let count = *data.get(pos)? as usize;
+    pos += 1;
+    let mut chapters = Vec::with_capacity(count);
+    for _ in 0..count {
+        let start = be_u64(data, pos)?;
+        let len = *data.get(pos + 8)? as usize;
+        let title = data.get(pos + 9..pos + 9 + len)?;
+        chapters.push(Chapter {
+            title: String::from_utf8_lossy(title).into_owned(),
+            start: start as f64 / CHPL_TIMESCALE,
+            end: None,
+        });
+        pos += 9 + len;
+    }
+    Some(chapters)
+}
+
+fn track_id(trak: &[u8]) -> Option<u32> {
+    let tkhd = mp4_child(trak, b"tkhd")?;
+    match tkhd.first()? {
+        1 => be_u32(tkhd, 4 + 16),
+        _ => be_u32(tkhd, 4 + 8),
+    }
+}
+
+// QuickTime 챕터: 오디오 트랙의 tref/chap이 가리키는 텍스트 트랙의 샘플 = 챕터 제목, 샘플 시간 = 챕터 시작
+fn read_quicktime_chapters(file: &mut File, moov: &[u8]) -> Option<Vec<Chapter>> {
+    let traks: Vec<&[u8]> = mp4_children(moov)
+        .into_iter()
+        .filter(|(kind, _)| kind == b"trak")
+        .map(|(_, payload)| payload)
+        .collect();
+    let chapter_track_id = traks.iter().find_map(|trak| {
+        let chap = mp4_child(mp4_child(trak, b"tref")?, b"chap")?;
+        be_u32(chap, 0)
+    })?;
+    let trak = traks.iter().find(|trak| track_id(trak) == Some(chapter_track_id))?;
+
+    let mdia = mp4_child(trak, b"mdia")?;
+    let mdhd = mp4_child(mdia, b"mdhd")?;
+    let timescale = match mdhd.first()? {
+        1 => be_u32(mdhd, 4 + 16)?,
+        _ => be_u32(mdhd, 4 + 8)?,
+    };
+    if timescale == 0 {
+        return None;
+    }
+    let stbl = mp4_child(mp4_child(mdia, b"minf")?, b"stbl")?;
+
+    // 샘플 시작 시간 (stts)
+    let stts = mp4_child(stbl, b"stts")?;
+    let mut starts = Vec::new();
+    let mut time = 0u64;
+    for i in 0..be_u32(stts, 4)? as usize {
+        let count = be_u32(stts, 8 + i * 8)?;
+        let delta = be_u32(stts, 12 + i * 8)? as u64;
+        for _ in 0..count {
+            if starts.len() >= MP4_MAX_CHAPTERS {
+                break;
+            }
+            starts.push(time);
+            time += delta;
+        }
+    }
+
+    // 샘플 크기 (stsz)
+    let stsz = mp4_child(stbl, b"stsz")?;
+    let uniform_size = be_u32(stsz, 4)?;
+    let sample_count = be_u32(stsz, 8)? as usize;
+    let sizes: Vec<u32> = (0..sample_count.min(starts.len()))
+        .map(|i| if uniform_size != 0 { Some(uniform_size) } else { be_u32(stsz, 12 + i * 4) })
+        .collect::<Option<_>>()?;
+
+    // 청크 오프셋 (stco / co64) + 청크별 샘플 수 (stsc) -> 샘플 오프셋
+    let chunk_offsets: Vec<u64> = if let Some(stco) = mp4_child(stbl, b"stco") {
+        (0..be_u32(stco, 4)? as usize).map(|i| be_u32(stco, 8 + i * 4).map(u64::from)).collect::<Option<_>>()?
+    } else {
+        let co64 = mp4_child(stbl, b"co64")?;
+        (0..be_u32(co64, 4)? as usize).map(|i| be_u64(co64, 8 + i * 8)).collect::<Option<_>>()?
+    };
+    let stsc = mp4_child(stbl, b"stsc")?;
+    let stsc_entries: Vec<(u32, u32)> = (0..be_u32(stsc, 4)? as usize)
+        .map(|i| Some((be_u32(stsc, 8 + i * 12)?, be_u32(stsc, 12 + i * 12)?)))
+        .collect::<Option<_>>()?;
+
+    let mut offsets = Vec::with_capacity(sizes.len());
+    for (chunk_index, chunk_offset) in chunk_offsets.iter().enumerate() {
+        let chunk_number = chunk_index as u32 + 1;
+        let samples_in_chunk = stsc_entries
+            .iter()
+            .rev()
+            .find(|(first_chunk, _)| *first_chunk <= chunk_number)
+            .map(|(_, samples)| *samples)
+            .unwrap_or(1);
+        let mut offset = *chunk_offset;
+        for _ in 0..samples_in_chunk {
+            let Some(size) = sizes.get(offsets.len()) else { break };
+            offsets.push(offset);
+            offset += *size as u64;
+        }
+    }
+
+    // 텍스트 샘플: 길이(2) + 제목 (UTF-8, BOM이 있으면 UTF-16)
+    let mut chapters = Vec::with_capacity(offsets.len());
+    for (i, offset) in offsets.iter().enumerate() {
+        let mut buf = vec![0u8; sizes[i] as usize];
+        file.seek(SeekFrom::Start(*offset)).ok()?;
+        file.read_exact(&mut buf).ok()?;
+        let len = buf.get(..2).map(|b| u16::from_be_bytes([b[0], b[1]]) as usize).unwrap_or(0);
+        let text = buf.get(2..2 + len).unwrap_or_default();
+        let title = if let Some(utf16) = text.strip_prefix(&[0xFE, 0xFF]) {
+            let units: Vec<u16> = utf16.chunks_exact(2).map(|c| u16::from_be_bytes([c[0], c[1]])).collect();
+            String::from_utf16_lossy(&units)
+        } else {
+            String::from_utf8_lossy(text).into_owned()
+        };
+        chapters.push(Chapter {
+            title,
+            start: starts[i] as f64 / timescale as f64,
+            end: None,
+        });
+    }
+    Some(chapters)
+}
@@ -0,0 +1,160 @@
+use std::collections::VecDeque;
+use serde::{Deserialize, Serialize};
+
+// 크로스페이드 최대 길이 (초)
+pub const CROSSFADE_MAX_SECONDS: f64 = 12.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CrossfadeCurve {
+    Linear,
+    EqualPower,
+}
+
+impl CrossfadeCurve {
+    pub fn from_setting(value: &str) -> Self {
+        match value {
+            "equal_power" => CrossfadeCurve::EqualPower,
+            _ => CrossfadeCurve::Linear,
+        }
+    }
+
+    pub fn as_setting(&self) -> &'static str {
+        match self {
+            CrossfadeCurve::Linear => "linear",
+            CrossfadeCurve::EqualPower => "equal_power",
+        }
+    }
+
+    // 진행률 t(0~1)에서 (나가는 곡 gain, 들어오는 곡 gain)
+    fn gains(&self, t: f32) -> (f32, f32) {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            CrossfadeCurve::Linear => (1.0 - t, t),
+            CrossfadeCurve::EqualPower => {
+                let angle = t * std::f32::consts::FRAC_PI_2;
+                (angle.cos(), angle.sin())
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrossfadeSettings {
+    pub duration_secs: f64,
+    pub curve: CrossfadeCurve,
+}
+
+impl Default for CrossfadeSettings {
+    fn default() -> Self {
+        Self {
+            duration_secs: 0.0,
+            curve: CrossfadeCurve::EqualPower,
+        }
+    }
+}
+
+impl CrossfadeSettings {
+    pub fn clamped(self) -> Self {
+        let duration_secs = if self.duration_secs.is_finite() {
+            self.duration_secs.clamp(0.0, CROSSFADE_MAX_SECONDS)
+        } else {
+            0.0
+        };
+        Self { duration_secs, curve: self.curve }
+    }
+
+    // 출력 샘플레이트 기준 크로스페이드 프레임 수
+    pub fn frames(&self, sample_rate: u32) -> usize {
+        (self.duration_secs * sample_rate as f64) as usize
+    }
+}
+
+// 현재 곡의 마지막 N프레임(채널 인터리브)을 붙잡아 두는 지연 버퍼
+// EOF 시점에 남아 있는 샘플이 곧 크로스페이드할 꼬리 구간
+pub struct TailBuffer {
+    frames: usize,
+    channels: usize,
+    samples: VecDeque<f32>,
+}
+
+impl TailBuffer {
+    pub fn new(frames: usize, channels: usize) -> Self {
+        Self {
+            frames,
+            channels,
+            samples: VecDeque::with_capacity(frames * channels),
+        }
+    }
+
+    // 붙잡아 둘 길이 변경 (줄어들면 넘치는 샘플은 다음 push에서 내보냄)
+    pub fn set_frames(&mut self, frames: usize) {
+        self.frames = frames;
+    }
+
+    // fresh 샘플을 꼬리에 넣고, 붙잡아 둘 길이를 넘는 오래된 샘플을 out으로 내보냄
+    pub fn push(&mut self, fresh: &mut Vec<f32>, out: &mut Vec<f32>) {
+        if self.frames == 0 && self.samples.is_empty() {
+            out.append(fresh);
+            return;
+        }
+        self.samples.extend(fresh.drain(..));
+        let limit = self.frames * self.channels;
+        if self.samples.len() > limit {
+            let overflow = self.samples.len() - limit;
+            out.extend(self.samples.drain(..overflow));
+        }
+    }
+
+    // 붙잡아 두고 있는 프레임 수
+    pub fn buffered_frames(&self) -> usize {
+        self.samples.len() / self.channels
+    }
+
+    // 붙잡아 둔 꼬리 전체를 꺼냄
+    pub fn take(&mut self) -> Vec<f32> {
+        self.samples.drain(..).collect()
+    }
+}
+
+// 이전 곡 꼬리를 다음 곡 머리에 섞는 믹서
+pub struct Crossfader {
+    tail: Vec<f32>,
+    pos: usize, // tail에서 다음에 섞을 샘플 인덱스 (채널 인터리브)
+    channels: usize,
+    curve: CrossfadeCurve,
+}
+
+impl Crossfader {
+    pub fn new(tail: Vec<f32>, curve: CrossfadeCurve, channels: usize) -> Self {
+        Self { tail, pos: 0, channels, curve }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.pos >= self.tail.len()
+    }
+
+    // 다음 곡 샘플(head)에 이전 곡 꼬리를 섞음
+    pub fn mix(&mut self, head: &mut [f32]) {
+        let total_frames = (self.tail.len() / self.channels).max(1) as f32;
+        for frame in head.chunks_exact_mut(self.channels) {
+            if self.is_done() {
+                break;
+            }
+            let t = (self.pos / self.channels) as f32 / total_frames;
+            let (out_gain, in_gain) = self.curve.gains(t);
+            for (sample, tail) in frame.iter_mut().zip(&self.tail[self.pos..]) {
+                *sample = *sample * in_gain + tail * out_gain;
+            }
+            self.pos += self.channels;
+        }
+    }
+
+    // 다음 곡이 크로스페이드 구간보다 먼저 끝났을 때: 남은 꼬리를 무음과 섞어서 내보냄
+    pub fn finish(&mut self, out: &mut Vec<f32>) {
+        let mut rest = vec![0.0f32; self.tail.len() - self.pos];
+        self.mix(&mut rest);
+        out.extend(rest);
+    }
+}
@@ -0,0 +1,196 @@
+use std::path::{Path, PathBuf};
+
+// CUE 시간 단위 (MM:SS:FF, 1초 = 75프레임)
+const CUE_FRAMES_PER_SECOND: f64 = 75.0;
+
+// CUE 시트 (한 파일에 앨범 전체를 담은 무손실 립용)
+#[derive(Debug, Clone, Default)]
+pub struct CueSheet {
+    pub title: Option<String>,     // 앨범 제목
+    pub performer: Option<String>, // 앨범 아티스트
+    pub genre: Option<String>,     // REM GENRE
+    pub date: Option<String>,      // REM DATE
+    pub files: Vec<CueFile>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CueFile {
+    pub name: String, // 시트 기준 상대 경로 (FILE "...")
+    pub tracks: Vec<CueTrack>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CueTrack {
+    pub number: u32,
+    pub title: Option<String>,
+    pub performer: Option<String>,
+    pub start: f64, // INDEX 01 위치 (초, 파일 기준)
+}
+
+impl CueSheet {
+    pub fn year(&self) -> Option<i32> {
+        self.date.as_deref()?.get(..4)?.parse().ok()
+    }
+}
+
+// 트랙 끝 위치 (같은 파일의 다음 트랙 시작, 마지막 트랙은 파일 끝이므로 None)
+pub fn cue_track_end(file: &CueFile, index: usize) -> Option<f64> {
+    file.tracks.get(index + 1).map(|next| next.start)
+}
+
+// 가상 트랙의 songs.file_path (원본 파일 경로 + 트랙 번호, 실제 파일은 없음)
+pub fn cue_track_path(audio_path: &str, number: u32) -> String {
+    format!("{}#track{:02}", audio_path, number)
+}
+
+// MM:SS:FF -> 초
+fn parse_cue_time(value: &str) -> Option<f64> {
+    let mut parts = value.split(':').map(|part| part.trim().parse::<u32>().ok());
+    let minutes = parts.next()??;
+    let seconds = parts.next()??;
+    let frames = parts.next()??;
+    if parts.next().is_some() {
+        return None;
+    }
+    Some(minutes as f64 * 60.0 + seconds as f64 + frames as f64 / CUE_FRAMES_PER_SECOND)
+}
+
+// 명령어 뒤의 값 (따옴표로 감싸져 있으면 안쪽만)
+fn parse_cue_value(rest: &str) -> String {
+    let rest = rest.trim();
+    if let Some(quoted) = rest.strip_prefix('"') {
+        match quoted.find('"') {
+            Some(end) => quoted[..end].to_string(),
+            None => quoted.to_string(),
+        }
+    } else {
+        rest.to_string()
+    }
+}
+
+// FILE "name" WAVE -> name (파일 형식은 무시)
+fn parse_cue_file_name(rest: &str) -> String {
+    let rest = rest.trim();
+    if rest.starts_with('"') {
+        parse_cue_value(rest)
+    } else {
+        match rest.rsplit_once(char::is_whitespace) {
+            Some((name, _format)) => name.trim().to_string(),
+            None => rest.to_string(),
+        }
+    }
+}
+
+pub fn parse_cue_sheet(text: &str) -> CueSheet {
+    let mut sheet = CueSheet::default();
+    let mut track: Option<CueTrack> = None;
+    let mut track_has_start = false;
+
+    // 시작 위치(INDEX 01)가 있는 트랙만 현재 FILE에 추가
+    fn flush(sheet: &mut CueSheet, track: &mut Option<CueTrack>, has_start: bool) {
+        if let Some(track) = track.take() {
+            if has_start {
+                if let Some(file) = sheet.files.last_mut() {
+                    file.tracks.push(track);
+                }
+            }
+        }
+    }
+
+    for line in text.lines() {
+        let line = line.trim();
+        let (command, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        match command.to_uppercase().as_str() {
+            "FILE" => {
+                flush(&mut sheet, &mut track, track_has_start);
+                sheet.files.push(CueFile {
+                    name: parse_cue_file_name(rest),
+                    tracks: Vec::new(),
+                });
+            }
+            "TRACK" => {
+                flush(&mut sheet, &mut track, track_has_start);
+                let number = rest.split_whitespace().next().and_then(|n| n.parse().ok()).unwrap_or(0);
+                track = Some(CueTrack {
+                    number,
+                    title: None,
+                    performer: None,
+                    start: 0.0,
+                });
+                track_has_start = false;
+            }
+            "TITLE" | "PERFORMER" => {
+                let value = Some(parse_cue_value(rest)).filter(|v| !v.is_empty());
+                let is_title = command.eq_ignore_ascii_case("TITLE");
+                match (track.as_mut(), is_title) {
+                    (Some(track), true) => track.title = value,
+                    (Some(track), false) => track.performer = value,
+                    (None, true) => sheet.title = value,
+                    (None, false) => sheet.performer = value,
+                }
+            }
+            "INDEX" => {
+                let mut parts = rest.split_whitespace();
+                let index = parts.next().and_then(|n| n.parse::<u32>().ok());
+                let time = parts.next().and_then(parse_cue_time);
+                if let (Some(1), Some(time), Some(track)) = (index, time, track.as_mut()) {
+                    track.start = time;
+                    track_has_start = true;
+                }
+            }
+            "REM" => {
+                let (key, value) = rest.trim().split_once(char::is_whitespace).unwrap_or((rest.trim(), ""));
+                let value = Some(parse_cue_value(value)).filter(|v| !v.is_empty());
+                match key.to_uppercase().as_str() {
+                    "GENRE" => sheet.genre = value,
+                    "DATE" => sheet.date = value,
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+    flush(&mut sheet, &mut track, track_has_start);
+
+    for file in &mut sheet.files {
+        file.tracks.sort_by(|a, b| a.start.total_cmp(&b.start));
+    }
+    sheet
+}
+
+// CUE 파일 텍스트 디코딩 (UTF-8이 아니면 CP949 -> Shift-JIS -> Windows-1252 순서로 시도)
+fn decode_cue_text(bytes: &[u8]) -> String {
+    if let Some(utf8) = bytes.strip_prefix(b"\xEF\xBB\xBF") {
+        return String::from_utf8_lossy(utf8).into_owned();
+    }
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        return text.to_string();
+    }
+    for encoding in [encoding_rs::EUC_KR, encoding_rs::SHIFT_JIS] {
+        let (text, _, had_errors) = encoding.decode(bytes);
+        if !had_errors {
+            return text.into_owned();
+        }
+    }
+    encoding_rs::WINDOWS_1252.decode(bytes).0.into_owned()
+}
+
+pub fn read_cue_sheet(cue_path: &Path) -> Result<CueSheet, String> {
+    let bytes = std::fs::read(cue_path)
+        .map_err(|e| format!("Failed to read cue sheet {}: {}", cue_path.display(), e))?;
+    Ok(parse_cue_sheet(&decode_cue_text(&bytes)))
+}
+
+// FILE이 가리키는 오디오 파일 찾기 (시트에는 .wav로 적혀 있지만 실제로는 .flac으로 변환된 경우 등)
+pub fn resolve_cue_audio_path(cue_path: &Path, file_name: &str, audio_extensions: &[&str]) -> Option<PathBuf> {
+    let dir = cue_path.parent()?;
+    let direct = dir.join(file_name);
+    if direct.is_file() {
+        return Some(direct);
+    }
+    let stem = Path::new(file_name).file_stem()?;
+    audio_extensions
+        .iter()
+        .map(|ext| dir.join(stem).with_extension(ext))
+        .find(|candidate| candidate.is_file())
+}
@@ -0,0 +1,158 @@
+use cpal::traits::{DeviceTrait, HostTrait};
+use cpal::{SampleFormat, SampleRate, SupportedStreamConfig};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutputConfigInfo {
+    pub channels: u16,
+    pub min_sample_rate: u32,
+    pub max_sample_rate: u32,
+    pub sample_format: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutputDeviceInfo {
+    pub name: String,
+    pub is_default: bool,
+    pub default_sample_rate: Option<u32>,
+    pub default_channels: Option<u16>,
+    pub supported_configs: Vec<OutputConfigInfo>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutputHostInfo {
+    pub name: String,
+    pub is_default: bool,
+    pub devices: Vec<OutputDeviceInfo>,
+}
+
+// 사용자가 고른 출력 장치 (None이면 시스템 기본값)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutputDeviceSelection {
+    pub host: Option<String>,
+    pub device: Option<String>,
+}
+
+impl OutputDeviceSelection {
+    pub fn is_default(&self) -> bool {
+        self.host.is_none() && self.device.is_none()
+    }
+}
+
+fn describe_device(device: &cpal::Device, default_name: Option<&str>) -> Option<OutputDeviceInfo> {
+    let name = device.name().ok()?;
+    let default_config = device.default_output_config().ok();
+    let supported_configs = device
+        .supported_output_configs()
+        .map(|configs| {
+            configs
+                .map(|c| OutputConfigInfo {
+                    channels: c.channels(),
+                    min_sample_rate: c.min_sample_rate().0,
+                    max_sample_rate: c.max_sample_rate().0,
+                    sample_format: format!("{:?}", c.sample_format()),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    Some(OutputDeviceInfo {
+        is_default: default_name == Some(name.as_str()),
+        name,
+        default_sample_rate: default_config.as_ref().map(|c| c.sample_rate().0),
+        default_channels: default_config.as_ref().map(|c| c.channels()),
+        supported_configs,
+    })
+}
+
+// 사용 가능한 오디오 호스트(WASAPI/ASIO/ALSA 등)와 각 호스트의 출력 장치 목록
+pub fn list_output_hosts() -> Vec<OutputHostInfo> {
+    let default_host_id = cpal::default_host().id();
+    cpal::available_hosts()
+        .into_iter()
+        .filter_map(|host_id| {
+            let host = cpal::host_from_id(host_id).ok()?;
+            let default_name = host.default_output_device().and_then(|d| d.name().ok());
+            let devices = host
+                .output_devices()
+                .map(|devices| {
+                    devices
+                        .filter_map(|d| describe_device(&d, default_name.as_deref()))
+                        .collect()
+                })
+                .unwrap_or_default();
+            Some(OutputHostInfo {
+                name: host_id.name().to_string(),
+                is_default: host_id == default_host_id,
+                devices,
+            })
+        })
+        .collect()
+}
+
+fn find_host(name: Option<&str>) -> Option<cpal::Host> {
+    match name {
+        None => Some(cpal::default_host()),
+        Some(name) => cpal::available_hosts()
+            .into_iter()
+            .find(|id| id.name() == name)
+            .and_then(|id| cpal::host_from_id(id).ok()),
+    }
+}
+
+// 선택한 장치 찾기 (없으면 None)
+pub fn find_output_device(selection: &OutputDeviceSelection) -> Option<cpal::Device> {
+    let host = find_host(selection.host.as_deref())?;
+    match selection.device.as_deref() {
+        None => host.default_output_device(),
+        Some(name) => host
+            .output_devices()
+            .ok()?
+            .find(|d| d.name().map(|n| n == name).unwrap_or(false)),
+    }
+}
+
+// 선택한 장치를 열고, 없으면 기본 장치로 대체 (두 번째 값: 대체했는지 여부)
+pub fn open_output_device(selection: &OutputDeviceSelection) -> Result<(cpal::Device, bool), String> {
+    if let Some(device) = find_output_device(selection) {
+        return Ok((device, false));
+    }
+    let device = cpal::default_host()
+        .default_output_device()
+        .ok_or_else(|| "No output device available".to_string())?;
+    Ok((device, !selection.is_default()))
+}
+
+// 비트퍼펙트 출력용 스트림 설정: 원본 샘플레이트 그대로, 스테레오(LR), 원본 샘플이 손실 없이 들어가는 포맷
+// (모노/스테레오 원본만 지원, 장치가 지원하지 않으면 None)
+pub fn find_bit_perfect_config(
+    device: &cpal::Device,
+    sample_rate: u32,
+    channels: usize,
+    bits_per_sample: Option<u32>,
+) -> Option<SupportedStreamConfig> {
+    if channels == 0 || channels > 2 {
+        return None;
+    }
+    // 정수 PCM은 같은 크기 이상의 정수 포맷 또는 f32(24비트까지 정확)로 손실 없이 전달됨
+    let formats: &[SampleFormat] = match bits_per_sample {
+        Some(bits) if bits <= 16 => &[SampleFormat::I16, SampleFormat::I32, SampleFormat::F32],
+        Some(bits) if bits <= 24 => &[SampleFormat::I32, SampleFormat::F32],
+        _ => &[SampleFormat::F32],
+    };
+    let ranges: Vec<_> = device.supported_output_configs().ok()?.collect();
+    formats.iter().find_map(|format| {
+        ranges
+            .iter()
+            .find(|range| {
+                range.channels() == 2
+                    && range.sample_format() == *format
+                    && range.min_sample_rate().0 <= sample_rate
+                    && sample_rate <= range.max_sample_rate().0
+            })
+            .map(|range| range.with_sample_rate(SampleRate(sample_rate)))
+    })
+}
@@ -0,0 +1,235 @@
+use std::collections::VecDeque;
+use serde::{Deserialize, Serialize};
+
+// 리미터 look-ahead 길이 (이만큼 출력이 늦어지는 대신 피크 전에 미리 gain을 낮춤)
+const LIMITER_LOOKAHEAD_MS: f64 = 5.0;
+const LIMITER_RELEASE_MS: f64 = 80.0;
+// 리미터 출력 상한 (-0.3 dBFS, 이후 단계에서 클리핑 나지 않도록 약간 여유)
+const LIMITER_CEILING: f32 = 0.966_051;
+const COMPRESSOR_ATTACK_MS: f64 = 10.0;
+// 이보다 작은 레벨은 무음으로 보고 dB 계산 생략
+const SILENCE_DB: f64 = -120.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DynamicsPreset {
+    Off,    // 컴프레서 끔 (리미터만)
+    Light,  // 살짝 (큰 소리만 조금 줄임)
+    Night,  // 야간 모드 (작은 소리는 키우고 큰 소리는 줄임)
+    Heavy,  // 아주 작은 볼륨으로 들을 때
+    Custom, // 사용자가 직접 조정
+}
+
+impl DynamicsPreset {
+    pub fn from_setting(value: &str) -> Self {
+        match value {
+            "light" => DynamicsPreset::Light,
+            "night" => DynamicsPreset::Night,
+            "heavy" => DynamicsPreset::Heavy,
+            "custom" => DynamicsPreset::Custom,
+            _ => DynamicsPreset::Off,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DynamicsSettings {
+    pub preset: DynamicsPreset,
+    pub compressor_enabled: bool,
+    pub threshold_db: f64, // 이 레벨을 넘는 부분만 압축 (dBFS)
+    pub ratio: f64,        // 압축 비율 (4.0이면 threshold 위로 4dB 커질 때 1dB만 커짐)
+    pub release_ms: f64,   // 큰 소리가 지나간 뒤 gain이 돌아오는 시간
+    pub makeup_db: f64,    // 압축 후 전체를 올리는 양 (작은 소리가 커지는 효과)
+    pub limiter_enabled: bool, // EQ / ReplayGain 이후 클리핑 방지 (기본 켬)
+}
+
+impl Default for DynamicsSettings {
+    fn default() -> Self {
+        Self::from_preset(DynamicsPreset::Off)
+    }
+}
+
+impl DynamicsSettings {
+    // 프리셋 값 (Custom은 Off 값을 바탕으로 사용자가 조정)
+    pub fn from_preset(preset: DynamicsPreset) -> Self {
+        let (compressor_enabled, threshold_db, ratio, release_ms, makeup_db) = match preset {
+            DynamicsPreset::Off | DynamicsPreset::Custom => (false, -24.0, 3.0, 250.0, 0.0),
+            DynamicsPreset::Light => (true, -18.0, 2.0, 200.0, 3.0),
+            DynamicsPreset::Night => (true, -30.0, 4.0, 300.0, 10.0),
+            DynamicsPreset::Heavy => (true, -40.0, 8.0, 500.0, 16.0),
+        };
+        Self {
+            preset,
+            compressor_enabled,
+            threshold_db,
+            ratio,
+            release_ms,
+            makeup_db,
+            limiter_enabled: true,
+        }
+    }
+
+    pub fn clamped(self) -> Self {
+        let finite_or = |v: f64, d: f64| if v.is_finite() { v } else { d };
+        Self {
+            threshold_db: finite_or(self.threshold_db, -24.0).clamp(-60.0, 0.0),
+            ratio: finite_or(self.ratio, 3.0).clamp(1.0, 20.0),
+            release_ms: finite_or(self.release_ms, 250.0).clamp(20.0, 2000.0),
+            makeup_db: finite_or(self.makeup_db, 0.0).clamp(0.0, 24.0),
+            ..self
+        }
+    }
+}
+
+// 시간 상수 -> 한 프레임당 1차 smoothing 계수
+fn smoothing_coef(ms: f64, sample_rate: u32) -> f64 {
+    let frames = ms / 1000.0 * sample_rate as f64;
+    if frames <= 0.0 {
+        1.0
+    } else {
+        1.0 - (-1.0 / frames).exp()
+    }
+}
+
+// 디코딩 스레드에서 EQ 다음에 채널 인터리브 샘플에 적용하는 컴프레서 + look-ahead 리미터
+// 리미터가 켜져 있으면 출력이 LIMITER_LOOKAHEAD_MS만큼 늦어짐 (샘플 수는 그대로)
+pub struct DynamicsProcessor {
+    sample_rate: u32,
+    channels: usize,
+    settings: DynamicsSettings,
+    bypass: bool,
+    // 컴프레서
+    envelope_db: f64,
+    attack_coef: f64,
+    release_coef: f64,
+    makeup: f64,
+    // 리미터
+    lookahead_frames: usize,
+    delay: VecDeque<f32>, // 채널 인터리브
+    required: VecDeque<(u64, f32)>, // (프레임 번호, 필요한 gain) - look-ahead 구간 최솟값 계산용 (단조 증가 유지)
+    frame_index: u64,
+    limiter_gain: f32,
+    limiter_attack_coef: f32,
+    limiter_release_coef: f32,
+}
+
+impl DynamicsProcessor {
+    pub fn new(sample_rate: u32, channels: usize) -> Self {
+        let lookahead_frames = ((LIMITER_LOOKAHEAD_MS / 1000.0 * sample_rate as f64) as usize).max(1);
+        let mut processor = Self {
+            sample_rate,
+            channels,
+            settings: DynamicsSettings::default(),
+            bypass: true,
+            envelope_db: SILENCE_DB,
+            attack_coef: smoothing_coef(COMPRESSOR_ATTACK_MS, sample_rate),
+            release_coef: 0.0,
+            makeup: 1.0,
+            lookahead_frames,
+            delay: VecDeque::with_capacity((lookahead_frames + 1) * channels),
+            required: VecDeque::with_capacity(lookahead_frames + 1),
+            frame_index: 0,
+            limiter_gain: 1.0,
+            // 피크가 출력되기 전 look-ahead 구간 안에 gain이 대부분 내려가도록
+            limiter_attack_coef: 1.0 - (-4.0 / lookahead_frames as f64).exp() as f32,
+            limiter_release_coef: smoothing_coef(LIMITER_RELEASE_MS, sample_rate) as f32,
+        };
+        processor.set(&DynamicsSettings::default());
+        processor.reset();
+        processor
+    }
+
+    // 설정 변경 (컴프레서 상태는 유지, 리미터를 켜고 끌 때만 지연 버퍼 초기화)
+    pub fn set(&mut self, settings: &DynamicsSettings) {
+        let limiter_changed = settings.limiter_enabled != self.settings.limiter_enabled;
+        self.settings = *settings;
+        self.bypass = !settings.compressor_enabled && !settings.limiter_enabled;
+        self.release_coef = smoothing_coef(settings.release_ms, self.sample_rate);
+        self.makeup = 10f64.powf(settings.makeup_db / 20.0);
+        if limiter_changed {
+            self.reset();
+        }
+    }
+
+    // seek 등으로 샘플이 불연속해질 때 상태 초기화
+    pub fn reset(&mut self) {
+        self.envelope_db = SILENCE_DB;
+        self.delay.clear();
+        self.delay.extend(std::iter::repeat_n(0.0, self.lookahead_frames * self.channels));
+        self.required.clear();
+        self.frame_index = 0;
+        self.limiter_gain = 1.0;
+    }
+
+    pub fn process(&mut self, samples: &mut [f32]) {
+        if self.bypass {
+            return;
+        }
+        for frame in samples.chunks_exact_mut(self.channels) {
+            if self.settings.compressor_enabled {
+                let gain = self.compressor_gain(frame_peak(frame));
+                frame.iter_mut().for_each(|sample| *sample *= gain);
+            }
+            if self.settings.limiter_enabled {
+                self.limit(frame);
+            }
+        }
+    }
+
+    // 마지막 곡 끝: 지연 버퍼에 남은 프레임을 out 뒤에 붙여 내보냄
+    pub fn drain(&mut self, out: &mut Vec<f32>) {
+        if self.bypass || !self.settings.limiter_enabled {
+            return;
+        }
+        let mut frame = vec![0.0; self.channels];
+        for _ in 0..self.lookahead_frames {
+            frame.fill(0.0);
+            self.limit(&mut frame);
+            out.extend_from_slice(&frame);
+        }
+        self.reset();
+    }
+
+    // 전체 채널 링크 피드포워드 컴프레서 (dB 영역 envelope)
+    fn compressor_gain(&mut self, peak: f32) -> f32 {
+        let level_db = if peak > 0.0 { (20.0 * (peak as f64).log10()).max(SILENCE_DB) } else { SILENCE_DB };
+        let coef = if level_db > self.envelope_db { self.attack_coef } else { self.release_coef };
+        self.envelope_db += (level_db - self.envelope_db) * coef;
+
+        let over_db = self.envelope_db - self.settings.threshold_db;
+        let reduction_db = if over_db > 0.0 { over_db * (1.0 - 1.0 / self.settings.ratio) } else { 0.0 };
+        (10f64.powf(-reduction_db / 20.0) * self.makeup) as f32
+    }
+
+    // look-ahead 리미터: 들어온 프레임은 지연 버퍼에 넣고, look-ahead 구간 전체에서 필요한 최소 gain으로 지연된 프레임을 내보냄
+    // (frame 자리에 지연된 프레임을 덮어씀)
+    fn limit(&mut self, frame: &mut [f32]) {
+        let peak = frame_peak(frame);
+        let required = if peak > LIMITER_CEILING { LIMITER_CEILING / peak } else { 1.0 };
+        while self.required.back().is_some_and(|&(_, gain)| gain >= required) {
+            self.required.pop_back();
+        }
+        self.required.push_back((self.frame_index, required));
+        let window_start = self.frame_index.saturating_sub(self.lookahead_frames as u64);
+        while self.required.front().is_some_and(|&(index, _)| index < window_start) {
+            self.required.pop_front();
+        }
+        self.frame_index += 1;
+        let target = self.required.front().map(|&(_, gain)| gain).unwrap_or(1.0);
+
+        let coef = if target < self.limiter_gain { self.limiter_attack_coef } else { self.limiter_release_coef };
+        self.limiter_gain += (target - self.limiter_gain) * coef;
+
+        // smoothing으로 다 못 내려간 부분은 상한에서 자름 (출력이 절대 상한을 넘지 않도록)
+        for sample in frame.iter_mut() {
+            self.delay.push_back(*sample);
+            let delayed = self.delay.pop_front().unwrap_or(0.0);
+            *sample = (delayed * self.limiter_gain).clamp(-LIMITER_CEILING, LIMITER_CEILING);
+        }
+    }
+}
+
+fn frame_peak(frame: &[f32]) -> f32 {
+    frame.iter().fold(0.0f32, |peak, sample| peak.max(sample.abs()))
+}
@@ -0,0 +1,224 @@
+use serde::{Deserialize, Serialize};
+
+pub const EQ_BAND_COUNT: usize = 10;
+pub const EQ_GAIN_MAX_DB: f64 = 24.0;
+// 10밴드 기본 중심 주파수 (Hz)
+const DEFAULT_FREQUENCIES: [f64; EQ_BAND_COUNT] = [
+    31.0, 62.0, 125.0, 250.0, 500.0, 1000.0, 2000.0, 4000.0, 8000.0, 16000.0,
+];
+const DEFAULT_Q: f64 = std::f64::consts::SQRT_2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EqBandType {
+    Peaking,
+    LowShelf,
+    HighShelf,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EqBand {
+    pub band_type: EqBandType,
+    pub frequency: f64,
+    pub gain_db: f64,
+    pub q: f64,
+}
+
+impl EqBand {
+    fn clamped(self) -> Self {
+        let finite_or = |v: f64, d: f64| if v.is_finite() { v } else { d };
+        Self {
+            band_type: self.band_type,
+            frequency: finite_or(self.frequency, 1000.0).clamp(20.0, 20000.0),
+            gain_db: finite_or(self.gain_db, 0.0).clamp(-EQ_GAIN_MAX_DB, EQ_GAIN_MAX_DB),
+            q: finite_or(self.q, DEFAULT_Q).clamp(0.1, 10.0),
+        }
+    }
+}
+
+pub fn default_eq_bands() -> Vec<EqBand> {
+    DEFAULT_FREQUENCIES
+        .iter()
+        .enumerate()
+        .map(|(i, &frequency)| EqBand {
+            band_type: match i {
+                0 => EqBandType::LowShelf,
+                i if i == EQ_BAND_COUNT - 1 => EqBandType::HighShelf,
+                _ => EqBandType::Peaking,
+            },
+            frequency,
+            gain_db: 0.0,
+            q: DEFAULT_Q,
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EqSettings {
+    pub enabled: bool,
+    pub preamp_db: f64,
+    pub bands: Vec<EqBand>,
+}
+
+impl Default for EqSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            preamp_db: 0.0,
+            bands: default_eq_bands(),
+        }
+    }
+}
+
+impl EqSettings {
+    // 밴드 수를 EQ_BAND_COUNT로 맞추고 값 범위 제한
+    pub fn clamped(self) -> Self {
+        let defaults = default_eq_bands();
+        let bands = (0..EQ_BAND_COUNT)
+            .map(|i| self.bands.get(i).copied().unwrap_or(defaults[i]).clamped())
+            .collect();
+        let preamp_db = if self.preamp_db.is_finite() {
+            self.preamp_db.clamp(-EQ_GAIN_MAX_DB, EQ_GAIN_MAX_DB)
+        } else {
+            0.0
+        };
+        Self { enabled: self.enabled, preamp_db, bands }
+    }
+}
+
+// RBJ Audio EQ Cookbook 2차 필터 (Direct Form II transposed, LR 상태 별도)
+#[derive(Clone)]
+struct EqFilter {
+    b0: f64,
+    b1: f64,
+    b2: f64,
+    a1: f64,
+    a2: f64,
+    z: Vec<[f64; 2]>, // [채널][z1, z2]
+    bypass: bool,
+}
+
+impl EqFilter {
+    fn new(band: &EqBand, sample_rate: u32, channels: usize) -> Self {
+        let fs = sample_rate as f64;
+        // 나이퀴스트 근처 주파수는 필터가 불안정해지므로 제한
+        let frequency = band.frequency.min(fs * 0.45);
+        let a = 10f64.powf(band.gain_db / 40.0);
+        let w0 = 2.0 * std::f64::consts::PI * frequency / fs;
+        let (sin, cos) = w0.sin_cos();
+        let alpha = sin / (2.0 * band.q);
+
+        let (b0, b1, b2, a0, a1, a2) = match band.band_type {
+            EqBandType::Peaking => (
+                1.0 + alpha * a,
+                -2.0 * cos,
+                1.0 - alpha * a,
+                1.0 + alpha / a,
+                -2.0 * cos,
+                1.0 - alpha / a,
+            ),
+            EqBandType::LowShelf => {
+                let sq = 2.0 * a.sqrt() * alpha;
+                (
+                    a * ((a + 1.0) - (a - 1.0) * cos + sq),
+                    2.0 * a * ((a - 1.0) - (a + 1.0) * cos),
+                    a * ((a + 1.0) - (a - 1.0) * cos - sq),
+                    (a + 1.0) + (a - 1.0) * cos + sq,
+                    -2.0 * ((a - 1.0) + (a + 1.0) * cos),
+                    (a + 1.0) + (a - 1.0) * cos - sq,
+                )
+            }
+            EqBandType::HighShelf => {
+                let sq = 2.0 * a.sqrt() * alpha;
+                (
+                    a * ((a + 1.0) + (a - 1.0) * cos + sq),
+                    -2.0 * a * ((a - 1.0) + (a + 1.0) * cos),
+                    a * ((a + 1.0) + (a - 1.0) * cos - sq),
+                    (a + 1.0) - (a - 1.0) * cos + sq,
+                    2.0 * ((a - 1.0) - (a + 1.0) * cos),
+                    (a + 1.0) - (a - 1.0) * cos - sq,
+                )
+            }
+        };
+
+        Self {
+            b0: b0 / a0,
+            b1: b1 / a0,
+            b2: b2 / a0,
+            a1: a1 / a0,
+            a2: a2 / a0,
+            z: vec![[0.0; 2]; channels],
+            bypass: band.gain_db.abs() < 0.01,
+        }
+    }
+
+    fn process(&mut self, ch: usize, x: f64) -> f64 {
+        let z = &mut self.z[ch];
+        let y = self.b0 * x + z[0];
+        z[0] = self.b1 * x - self.a1 * y + z[1];
+        z[1] = self.b2 * x - self.a2 * y;
+        y
+    }
+}
+
+// 디코딩 스레드에서 채널 인터리브 샘플에 적용하는 파라메트릭 EQ
+pub struct Equalizer {
+    sample_rate: u32,
+    channels: usize,
+    enabled: bool,
+    preamp: f64,
+    filters: Vec<EqFilter>,
+}
+
+impl Equalizer {
+    pub fn new(sample_rate: u32, channels: usize) -> Self {
+        Self {
+            sample_rate,
+            channels,
+            enabled: false,
+            preamp: 1.0,
+            filters: Vec::new(),
+        }
+    }
+
+    // 설정 변경 (필터 상태는 유지해서 재생 중 변경해도 끊김 없음)
+    pub fn set(&mut self, settings: &EqSettings) {
+        self.enabled = settings.enabled;
+        self.preamp = 10f64.powf(settings.preamp_db / 20.0);
+        let mut filters: Vec<EqFilter> = settings
+            .bands
+            .iter()
+            .map(|band| EqFilter::new(band, self.sample_rate, self.channels))
+            .collect();
+        for (new, old) in filters.iter_mut().zip(self.filters.iter_mut()) {
+            new.z = std::mem::take(&mut old.z);
+        }
+        self.filters = filters;
+    }
+
+    // seek 등으로 샘플이 불연속해질 때 필터 상태 초기화
+    pub fn reset(&mut self) {
+        for filter in &mut self.filters {
+            filter.z.fill([0.0; 2]);
+        }
+    }
+
+    pub fn process(&mut self, samples: &mut [f32]) {
+        if !self.enabled {
+            return;
+        }
+        for frame in samples.chunks_exact_mut(self.channels) {
+            for (ch, sample) in frame.iter_mut().enumerate() {
+                let mut v = *sample as f64 * self.preamp;
+                for filter in &mut self.filters {
+                    if !filter.bypass {
+                        v = filter.process(ch, v);
+                    }
+                }
+                *sample = v as f32;
+            }
+        }
+    }
+}
@@ -0,0 +1,292 @@
+use serde::{Deserialize, Serialize};
+
+// ReplayGain 2.0 기준 라우드니스 (LUFS)
+pub const REPLAYGAIN_REFERENCE_LUFS: f64 = -18.0;
+
+const ABSOLUTE_GATE_LUFS: f64 = -70.0;
+const RELATIVE_GATE_LU: f64 = -10.0;
+// true peak 측정용 4배 오버샘플링 보간 필터 탭 수 (위상당)
+const TRUE_PEAK_TAPS: usize = 12;
+const TRUE_PEAK_PHASES: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReplayGainMode {
+    Off,
+    Track,
+    Album,
+}
+
+impl ReplayGainMode {
+    pub fn from_setting(value: &str) -> Self {
+        match value {
+            "track" => ReplayGainMode::Track,
+            "album" => ReplayGainMode::Album,
+            _ => ReplayGainMode::Off,
+        }
+    }
+
+    pub fn as_setting(&self) -> &'static str {
+        match self {
+            ReplayGainMode::Off => "off",
+            ReplayGainMode::Track => "track",
+            ReplayGainMode::Album => "album",
+        }
+    }
+
+    pub fn to_u8(self) -> u8 {
+        match self {
+            ReplayGainMode::Off => 0,
+            ReplayGainMode::Track => 1,
+            ReplayGainMode::Album => 2,
+        }
+    }
+
+    pub fn from_u8(value: u8) -> Self {
+        match value {
+            1 => ReplayGainMode::Track,
+            2 => ReplayGainMode::Album,
+            _ => ReplayGainMode::Off,
+        }
+    }
+}
+
+// 프리앰프 범위 (dB)
+pub const REPLAYGAIN_PREAMP_MAX_DB: f64 = 15.0;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayGainSettings {
+    pub mode: ReplayGainMode,
+    pub preamp_db: f64,
+    pub write_tags: bool, // 분석 결과를 REPLAYGAIN_* 태그로 파일에 기록
+}
+
+impl Default for ReplayGainSettings {
+    fn default() -> Self {
+        Self {
+            mode: ReplayGainMode::Off,
+            preamp_db: 0.0,
+            write_tags: false,
+        }
+    }
+}
+
+impl ReplayGainSettings {
+    pub fn clamped(self) -> Self {
+        let preamp_db = if self.preamp_db.is_finite() {
+            self.preamp_db.clamp(-REPLAYGAIN_PREAMP_MAX_DB, REPLAYGAIN_PREAMP_MAX_DB)
+        } else {
+            0.0
+        };
+        Self { preamp_db, ..self }
+    }
+}
+
+// 곡 하나의 분석 결과 (DB songs 테이블 loudness_* 컬럼)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayGainInfo {
+    pub track_lufs: f64,
+    pub track_peak: f64, // true peak (선형, 1.0 = 0 dBTP)
+    pub album_lufs: Option<f64>,
+    pub album_peak: Option<f64>,
+}
+
+impl ReplayGainInfo {
+    pub fn track_gain_db(&self) -> f64 {
+        REPLAYGAIN_REFERENCE_LUFS - self.track_lufs
+    }
+
+    pub fn album_gain_db(&self) -> Option<f64> {
+        self.album_lufs.map(|lufs| REPLAYGAIN_REFERENCE_LUFS - lufs)
+    }
+
+    // 모드 + 프리앰프를 적용한 선형 gain (피크가 0 dBTP를 넘지 않도록 제한)
+    pub fn linear_gain(&self, mode: ReplayGainMode, preamp_db: f64) -> f32 {
+        let (gain_db, peak) = match mode {
+            ReplayGainMode::Off => return 1.0,
+            ReplayGainMode::Track => (self.track_gain_db(), self.track_peak),
+            ReplayGainMode::Album => match (self.album_gain_db(), self.album_peak) {
+                (Some(gain), Some(peak)) => (gain, peak),
+                _ => (self.track_gain_db(), self.track_peak),
+            },
+        };
+        let mut gain = 10f64.powf((gain_db + preamp_db) / 20.0);
+        // ✅ 클리핑 방지: gain 적용 후 true peak가 1.0을 넘지 않게
+        if peak > 0.0 && gain * peak > 1.0 {
+            gain = 1.0 / peak;
+        }
+        gain as f32
+    }
+}
+
+// 2차 IIR 필터 (Direct Form II transposed)
+#[derive(Clone, Copy)]
+struct Biquad {
+    b0: f64,
+    b1: f64,
+    b2: f64,
+    a1: f64,
+    a2: f64,
+    z1: f64,
+    z2: f64,
+}
+
+impl Biquad {
+    fn process(&mut self, x: f64) -> f64 {
+        let y = self.b0 * x + self.z1;
+        self.z1 = self.b1 * x - self.a1 * y + self.z2;
+        self.z2 = self.b2 * x - self.a2 * y;
+        y
+    }
+}
+
+// ITU-R BS.1770 K-weighting 필터 (high shelf + high pass), 임의 샘플레이트용 계수
+fn k_weighting(sample_rate: u32) -> [Biquad; 2] {
+    let fs = sample_rate as f64;
+
+    let f0 = 1681.974450955533;
+    let g = 3.999843853973347;
+    let q = 0.7071752369554196;
+    let k = (std::f64::consts::PI * f0 / fs).tan();
+    let vh = 10f64.powf(g / 20.0);
+    let vb = vh.powf(0.4996667741545416);
+    let a0 = 1.0 + k / q + k * k;
+    let shelf = Biquad {
+        b0: (vh + vb * k / q + k * k) / a0,
+        b1: 2.0 * (k * k - vh) / a0,
+        b2: (vh - vb * k / q + k * k) / a0,
+        a1: 2.0 * (k * k - 1.0) / a0,
+        a2: (1.0 - k / q + k * k) / a0,
+        z1: 0.0,
+        z2: 0.0,
+    };
+
+    let f0 = 38.13547087602444;
+    let q = 0.5003270373238773;
+    let k = (std::f64::consts::PI * f0 / fs).tan();
+    let a0 = 1.0 + k / q + k * k;
+    let highpass = Biquad {
+        b0: 1.0,
+        b1: -2.0,
+        b2: 1.0,
+        a1: 2.0 * (k * k - 1.0) / a0,
+        a2: (1.0 - k / q + k * k) / a0,
+        z1: 0.0,
+        z2: 0.0,
+    };
+
+    [shelf, highpass]
+}
+
+fn energy_to_lufs(energy: f64) -> f64 {
+    -0.691 + 10.0 * energy.log10()
+}
+
+// 게이팅된 블록 에너지로 integrated loudness 계산 (블록이 없으면 None)
+// 앨범 라우드니스는 앨범 내 모든 곡의 블록을 합쳐서 계산
+pub fn integrated_loudness(block_energies: &[f64]) -> Option<f64> {
+    let above_absolute: Vec<f64> = block_energies
+        .iter()
+        .copied()
+        .filter(|&e| e > 0.0 && energy_to_lufs(e) > ABSOLUTE_GATE_LUFS)
+        .collect();
+    if above_absolute.is_empty() {
+        return None;
+    }
+    let mean = above_absolute.iter().sum::<f64>() / above_absolute.len() as f64;
+    let relative_gate = energy_to_lufs(mean) + RELATIVE_GATE_LU;
+
+    let gated: Vec<f64> = above_absolute
+        .into_iter()
+        .filter(|&e| energy_to_lufs(e) > relative_gate)
+        .collect();
+    if gated.is_empty() {
+        return None;
+    }
+    Some(energy_to_lufs(gated.iter().sum::<f64>() / gated.len() as f64))
+}
+
+// EBU R128 라우드니스 + true peak 측정기 (LR 2채널 입력)
+pub struct LoudnessMeter {
+    filters: [[Biquad; 2]; 2],
+    sub_block_frames: usize, // 100ms
+    sub_block_pos: usize,
+    sub_block_sum: f64,
+    sub_blocks: Vec<f64>, // 100ms 단위 평균 에너지
+    peak_history: [[f32; TRUE_PEAK_TAPS]; 2],
+    peak_coeffs: [[f32; TRUE_PEAK_TAPS]; TRUE_PEAK_PHASES],
+    true_peak: f32,
+}
+
+impl LoudnessMeter {
+    pub fn new(sample_rate: u32) -> Self {
+        let filters = k_weighting(sample_rate);
+
+        // 4배 오버샘플링 보간 계수 (Hann 창 windowed-sinc)
+        let center = (TRUE_PEAK_TAPS / 2) as f32;
+        let mut peak_coeffs = [[0.0f32; TRUE_PEAK_TAPS]; TRUE_PEAK_PHASES];
+        for (phase, coeffs) in peak_coeffs.iter_mut().enumerate() {
+            let frac = phase as f32 / TRUE_PEAK_PHASES as f32;
+            for (k, c) in coeffs.iter_mut().enumerate() {
+                let x = k as f32 - center + frac;
+                let sinc = if x.abs() < 1e-6 {
+                    1.0
+                } else {
+                    (std::f32::consts::PI * x).sin() / (std::f32::consts::PI * x)
+                };
+                let window = 0.5 * (1.0 + (std::f32::consts::PI * x / (center + 1.0)).cos());
+                *c = sinc * window;
+            }
+        }
+
+        Self {
+            filters: [filters, filters],
+            sub_block_frames: (sample_rate as usize / 10).max(1),
+            sub_block_pos: 0,
+            sub_block_sum: 0.0,
+            sub_blocks: Vec::new(),
+            peak_history: [[0.0; TRUE_PEAK_TAPS]; 2],
+            peak_coeffs,
+            true_peak: 0.0,
+        }
+    }
+
+    pub fn process(&mut self, l: f32, r: f32) {
+        let mut energy = 0.0;
+        for (ch, sample) in [l, r].into_iter().enumerate() {
+            let [shelf, highpass] = &mut self.filters[ch];
+            let y = highpass.process(shelf.process(sample as f64));
+            energy += y * y;
+
+            let history = &mut self.peak_history[ch];
+            history.copy_within(0..TRUE_PEAK_TAPS - 1, 1);
+            history[0] = sample;
+            for coeffs in &self.peak_coeffs {
+                let v: f32 = coeffs.iter().zip(history.iter()).map(|(c, x)| c * x).sum();
+                self.true_peak = self.true_peak.max(v.abs());
+            }
+        }
+
+        self.sub_block_sum += energy;
+        self.sub_block_pos += 1;
+        if self.sub_block_pos >= self.sub_block_frames {
+            self.sub_blocks.push(self.sub_block_sum / self.sub_block_frames as f64);
+            self.sub_block_sum = 0.0;
+            self.sub_block_pos = 0;
+        }
+    }
+
+    // 400ms 블록(100ms 간격, 75% 겹침) 에너지 목록
+    pub fn block_energies(&self) -> Vec<f64> {
+        self.sub_blocks
+            .windows(4)
+            .map(|w| w.iter().sum::<f64>() / 4.0)
+            .collect()
+    }
+
+    pub fn true_peak(&self) -> f64 {
+        self.true_peak as f64
+    }
+}
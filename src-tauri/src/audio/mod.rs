@@ -0,0 +1,29 @@
+pub mod source;
+pub mod resample;
+pub mod crossfade;
+pub mod loudness;
+pub mod device;
+pub mod eq;
+pub mod stretch;
+pub mod channels;
+pub mod analyzer;
+pub mod sink;
+pub mod cue;
+pub mod chapters;
+pub mod dynamics;
+pub mod silence;
+
+pub use source::*;
+pub use resample::*;
+pub use crossfade::*;
+pub use loudness::*;
+pub use device::*;
+pub use eq::*;
+pub use stretch::*;
+pub use channels::*;
+pub use analyzer::*;
+pub use sink::*;
+pub use cue::*;
+pub use chapters::*;
+pub use dynamics::*;
+pub use silence::*;
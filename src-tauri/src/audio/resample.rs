@@ -0,0 +1,153 @@
+use std::collections::VecDeque;
+use std::ops::Range;
+use rubato::{Resampler, SincFixedIn, SincInterpolationType, SincInterpolationParameters, WindowFunction};
+
+// ✅ 리샘플러 고정 입력 프레임 크기
+pub const RS_IN_FRAMES: usize = 8192;
+
+// 채널별 pending 버퍼용 리샘플러 (소스 샘플레이트 -> 출력 장치 샘플레이트)
+// pending 버퍼에서 RS_IN_FRAMES 단위로 꺼내 처리하고 결과는 인터리브로 batch에 추가
+pub struct FrameResampler {
+    channels: usize,
+    needs_resampling: bool,
+    ratio: f64,
+    resampler: Option<SincFixedIn<f32>>,
+    input_frames: u64,   // 리샘플러에 넣은 소스 프레임 수 (0-padding 제외)
+    output_frames: u64,  // 내보낸 프레임 수 (리샘플러 지연 구간 제외)
+    delay_frames: usize, // 아직 버리지 않은 리샘플러 지연 (출력 맨 앞의 sinc 필터 지연분)
+}
+
+impl FrameResampler {
+    pub fn new(source_sample_rate: u32, target_sample_rate: u32, channels: usize) -> Self {
+        let needs_resampling = source_sample_rate != target_sample_rate;
+        let ratio = if needs_resampling {
+            target_sample_rate as f64 / source_sample_rate as f64
+        } else {
+            1.0
+        };
+        Self {
+            channels,
+            needs_resampling,
+            ratio,
+            resampler: None,
+            input_frames: 0,
+            output_frames: 0,
+            delay_frames: 0,
+        }
+    }
+
+    // pending에 남은 소스 프레임이 출력에서 차지할 프레임 수 (트랙 경계 계산용)
+    pub fn output_frames_for(&self, source_frames: usize) -> u64 {
+        (source_frames as f64 * self.ratio).round() as u64
+    }
+
+    fn ensure_resampler(&mut self) {
+        if self.resampler.is_some() {
+            return;
+        }
+        // ✅ SincInterpolationType::Linear는 sinc 커널 계산 시 내부 보간 방식
+        // (선형 보간 리샘플러가 아님 - sinc 기반 고품질 리샘플러)
+        let params = SincInterpolationParameters {
+            sinc_len: 256,
+            f_cutoff: 0.95,
+            interpolation: SincInterpolationType::Linear, // sinc 내부 테이블 보간 방식
+            oversampling_factor: 256,
+            window: WindowFunction::BlackmanHarris2,
+        };
+        match SincFixedIn::<f32>::new(self.ratio, 2.0, params, RS_IN_FRAMES, self.channels) {
+            Ok(r) => {
+                self.delay_frames = r.output_delay();
+                self.resampler = Some(r);
+            }
+            Err(e) => {
+                eprintln!("[resample_init_err] {:?}", e);
+                // 초기화 실패 시 다음 블록에서 다시 시도
+            }
+        }
+    }
+
+    // ✅ pending이 RS_IN_FRAMES 이상 모이면 "딱 RS_IN_FRAMES만" 뽑아서 process
+    // 리샘플링이 필요 없으면 pending 전체를 그대로 batch로 옮김
+    pub fn process(&mut self, pending: &mut [VecDeque<f32>], batch_samples: &mut Vec<f32>) {
+        if !self.needs_resampling {
+            let frames = pending_frames(pending);
+            batch_samples.reserve(frames * pending.len());
+            for _ in 0..frames {
+                batch_samples.extend(pending.iter_mut().filter_map(VecDeque::pop_front));
+            }
+            return;
+        }
+
+        self.ensure_resampler();
+        while pending_frames(pending) >= RS_IN_FRAMES {
+            // 고정 길이 블록 만들기
+            let input_channels: Vec<Vec<f32>> = pending.iter_mut().map(|channel| channel.drain(..RS_IN_FRAMES).collect()).collect();
+            self.input_frames += RS_IN_FRAMES as u64;
+            self.process_block(input_channels, batch_samples, u64::MAX);
+        }
+    }
+
+    // ✅ EOF / 샘플레이트가 다른 곡으로 전환: pending 잔여 처리 + flush
+    // 출력 길이는 정확히 입력 길이 * 비율 (0-padding으로 생긴 뒤쪽 무음은 잘라냄)
+    pub fn drain(&mut self, pending: &mut [VecDeque<f32>], batch_samples: &mut Vec<f32>) {
+        self.process(pending, batch_samples);
+        if !self.needs_resampling {
+            return;
+        }
+
+        // 남은 pending + 리샘플러 안에 지연된 샘플이 모두 나올 때까지 0-padding 블록을 넣음
+        self.input_frames += pending_frames(pending) as u64;
+        let expected = (self.input_frames as f64 * self.ratio).round() as u64;
+        if self.output_frames < expected {
+            self.ensure_resampler();
+        }
+        while self.output_frames < expected && self.resampler.is_some() {
+            let input_channels: Vec<Vec<f32>> = pending
+                .iter_mut()
+                .map(|channel| (0..RS_IN_FRAMES).map(|_| channel.pop_front().unwrap_or(0.0)).collect())
+                .collect();
+            self.process_block(input_channels, batch_samples, expected);
+        }
+        pending.iter_mut().for_each(VecDeque::clear);
+        self.resampler = None;
+        self.input_frames = 0;
+        self.output_frames = 0;
+    }
+
+    // 출력 앞부분의 리샘플러 지연은 버리고, 누적 출력이 limit 프레임을 넘지 않게 추가
+    fn process_block(&mut self, input_channels: Vec<Vec<f32>>, batch_samples: &mut Vec<f32>, limit: u64) {
+        if let Some(ref mut rs) = self.resampler {
+            match rs.process(&input_channels, None) {
+                Ok(out) => {
+                    let frames = out.first().map_or(0, |channel| channel.len());
+                    let skip = self.delay_frames.min(frames);
+                    self.delay_frames -= skip;
+                    let take = ((frames - skip) as u64).min(limit.saturating_sub(self.output_frames)) as usize;
+                    self.output_frames += take as u64;
+                    push_interleaved(&out, skip..skip + take, batch_samples);
+                }
+                Err(e) => {
+                    // 리샘플러 에러: reset하고 계속
+                    eprintln!("[resample_err] {:?} (reset resampler)", e);
+                    self.resampler = None;
+                }
+            }
+        }
+    }
+}
+
+// 모든 채널에 다 들어 있는 프레임 수
+fn pending_frames(pending: &[VecDeque<f32>]) -> usize {
+    pending.iter().map(VecDeque::len).min().unwrap_or(0)
+}
+
+// 채널별 출력의 range 구간 -> batch_samples에 인터리브로 추가
+fn push_interleaved(out: &[Vec<f32>], range: Range<usize>, batch_samples: &mut Vec<f32>) {
+    if out.is_empty() || range.is_empty() {
+        return;
+    }
+    batch_samples.reserve(range.len() * out.len());
+    for i in range {
+        batch_samples.extend(out.iter().map(|channel| channel[i]));
+    }
+}
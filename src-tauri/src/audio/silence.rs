@@ -0,0 +1,118 @@
+use std::collections::VecDeque;
+use serde::{Deserialize, Serialize};
+use symphonia::core::formats::SeekMode;
+use super::source::{DecodeSource, DecodeStatus};
+
+// 앞/뒤 각각 이만큼까지만 검사 (곡 전체를 디코딩하지 않도록)
+const SILENCE_SCAN_MAX_SECONDS: f64 = 30.0;
+// 이보다 짧은 무음은 그대로 둠 (gapless 앨범의 곡 경계 / 짧은 여백 보호)
+const SILENCE_MIN_SECONDS: f64 = 0.5;
+
+// 무음 건너뛰기 구간 (초, 곡 기준)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SilenceTrim {
+    pub start: f64,       // 재생을 시작할 위치 (앞부분 무음 길이, 없으면 0)
+    pub end: Option<f64>, // 재생을 끝낼 위치 (뒷부분 무음 시작, 없으면 곡 끝까지)
+}
+
+impl SilenceTrim {
+    pub const NONE: SilenceTrim = SilenceTrim { start: 0.0, end: None };
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkipSilenceSettings {
+    pub enabled: bool,
+    pub threshold_db: f64, // 이 레벨 이하를 무음으로 봄 (dBFS)
+}
+
+impl Default for SkipSilenceSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            threshold_db: -60.0,
+        }
+    }
+}
+
+impl SkipSilenceSettings {
+    pub fn clamped(self) -> Self {
+        Self {
+            threshold_db: if self.threshold_db.is_finite() { self.threshold_db.clamp(-90.0, -20.0) } else { -60.0 },
+            ..self
+        }
+    }
+}
+
+// 곡 앞/뒤의 무음 길이 검사 (source는 처음 위치에서 열린 상태, 검사 후에는 다시 seek해서 사용)
+pub fn detect_silence(source: &mut DecodeSource, threshold_db: f64) -> Result<SilenceTrim, String> {
+    let threshold = 10f32.powf(threshold_db as f32 / 20.0);
+    let sample_rate = source.sample_rate as f64;
+    let scan_frames = (SILENCE_SCAN_MAX_SECONDS * sample_rate) as u64;
+    let mut pending: [VecDeque<f32>; 2] = Default::default();
+    let is_loud = |(l, r): (&f32, &f32)| l.abs().max(r.abs()) > threshold;
+
+    // 앞부분: 처음으로 threshold를 넘는 프레임
+    let mut first_loud: Option<u64> = None;
+    let mut reached_eof = false;
+    loop {
+        match source.decode_into(&mut pending) {
+            DecodeStatus::Decoded => {
+                let base = source.position_frames - pending[0].len() as u64;
+                if let Some(i) = pending[0].iter().zip(pending[1].iter()).position(is_loud) {
+                    first_loud = Some(base + i as u64);
+                    break;
+                }
+                pending.iter_mut().for_each(VecDeque::clear);
+                if source.position_frames >= scan_frames {
+                    break;
+                }
+            }
+            DecodeStatus::Skipped => {}
+            DecodeStatus::Eof => {
+                reached_eof = true;
+                break;
+            }
+        }
+    }
+    let start_frames = match first_loud {
+        Some(frames) => frames,
+        // 곡 전체가 무음이면 건너뛰지 않음
+        None if reached_eof => return Ok(SilenceTrim::NONE),
+        None => scan_frames,
+    };
+    let start = start_frames as f64 / sample_rate;
+    let start = if start >= SILENCE_MIN_SECONDS { start } else { 0.0 };
+
+    // 뒷부분: 마지막 SILENCE_SCAN_MAX_SECONDS 구간에서 threshold를 넘는 마지막 프레임 (길이를 모르면 검사 안 함)
+    let Some(duration) = source.duration else {
+        return Ok(SilenceTrim { start, end: None });
+    };
+    let window_start = (duration - SILENCE_SCAN_MAX_SECONDS).max(start_frames as f64 / sample_rate);
+    source
+        .seek(window_start, SeekMode::Accurate)
+        .or_else(|_| source.seek(window_start, SeekMode::Coarse))?;
+    pending.iter_mut().for_each(VecDeque::clear);
+    let mut last_loud: Option<u64> = None;
+    loop {
+        match source.decode_into(&mut pending) {
+            DecodeStatus::Decoded => {
+                let base = source.position_frames - pending[0].len() as u64;
+                if let Some(i) = pending[0].iter().zip(pending[1].iter()).rposition(is_loud) {
+                    last_loud = Some(base + i as u64);
+                }
+                pending.iter_mut().for_each(VecDeque::clear);
+            }
+            DecodeStatus::Skipped => {}
+            DecodeStatus::Eof => break,
+        }
+    }
+    let total = source.position_frames as f64 / sample_rate;
+    let end = match last_loud {
+        Some(frames) => (frames + 1) as f64 / sample_rate,
+        None => window_start,
+    };
+    let end = Some(end).filter(|&end| total - end >= SILENCE_MIN_SECONDS && end > start);
+    Ok(SilenceTrim { start, end })
+}
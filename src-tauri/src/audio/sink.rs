@@ -0,0 +1,415 @@
+use std::fs::File;
+use std::io::{BufWriter, Seek, SeekFrom, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use cpal::traits::{DeviceTrait, StreamTrait};
+use cpal::{FromSample, SampleFormat, SizedSample};
+use serde::{Deserialize, Serialize};
+
+// 출력 버퍼를 채우는 렌더 함수 (장치 채널 수에 맞춘 인터리브 f32, 재생 엔진이 만듦)
+// 반환값: 디코딩된 소리로 채운 앞쪽 프레임 수 (나머지는 일시정지 / 버퍼 부족으로 채운 무음, 시계 없는 출력이 버림)
+pub type RenderFn = Box<dyn FnMut(&mut [f32]) -> usize + Send + 'static>;
+// 출력 장치가 사라졌을 때 호출 (cpal만 해당)
+pub type DeviceLostFn = Box<dyn Fn() + Send + 'static>;
+
+// cpal 콜백 안에서 f32 -> 장치 포맷 변환용 버퍼 크기 (일반적인 콜백 크기에서는 재할당 없음)
+const CPAL_SCRATCH_CAPACITY: usize = 16384;
+// null / WAV 출력이 한 번에 렌더링하는 길이
+const RENDER_BLOCK: Duration = Duration::from_millis(10);
+// 시계 없는 출력(WAV)이 렌더링할 소리가 없을 때 다시 시도하기 전 대기 시간
+const UNCLOCKED_IDLE_WAIT: Duration = Duration::from_millis(1);
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AudioSinkKind {
+    Cpal, // 사운드 카드 (기본값)
+    Null, // 출력 없이 실시간 속도로 버림 (사운드 카드 없는 환경)
+    Wav,  // 디코딩이 허용하는 최대 속도로 WAV 파일에 기록 (파이프라인 검증용)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioSinkSettings {
+    pub kind: AudioSinkKind,
+    pub wav_path: Option<String>, // Wav 출력 파일 경로
+    pub sample_rate: u32,         // Null / Wav 출력 샘플레이트 (cpal은 장치 설정을 따름)
+}
+
+impl Default for AudioSinkSettings {
+    fn default() -> Self {
+        Self {
+            kind: AudioSinkKind::Cpal,
+            wav_path: None,
+            sample_rate: 48000,
+        }
+    }
+}
+
+impl AudioSinkSettings {
+    pub fn clamped(self) -> Self {
+        Self {
+            sample_rate: self.sample_rate.clamp(8000, 384000),
+            ..self
+        }
+    }
+}
+
+// 재생 엔진이 렌더링한 샘플을 내보내는 출력
+// start 이후 render를 주기적으로 호출하고, drop되면 출력을 멈춤
+pub trait AudioSink {
+    fn name(&self) -> String;
+    fn sample_rate(&self) -> u32;
+    fn channels(&self) -> usize;
+    fn start(&mut self, render: RenderFn, on_device_lost: DeviceLostFn) -> Result<(), String>;
+}
+
+// cpal 출력 장치
+pub struct CpalSink {
+    device: cpal::Device,
+    config: cpal::SupportedStreamConfig,
+    stream: Option<cpal::Stream>,
+}
+
+impl CpalSink {
+    pub fn new(device: cpal::Device, config: cpal::SupportedStreamConfig) -> Self {
+        Self { device, config, stream: None }
+    }
+}
+
+impl AudioSink for CpalSink {
+    fn name(&self) -> String {
+        self.device.name().unwrap_or_default()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.config.sample_rate().0
+    }
+
+    fn channels(&self) -> usize {
+        self.config.channels() as usize
+    }
+
+    fn start(&mut self, render: RenderFn, on_device_lost: DeviceLostFn) -> Result<(), String> {
+        let config = self.config.config();
+        let stream = match self.config.sample_format() {
+            SampleFormat::F32 => build_cpal_stream::<f32>(&self.device, &config, render, on_device_lost)?,
+            SampleFormat::I16 => build_cpal_stream::<i16>(&self.device, &config, render, on_device_lost)?,
+            SampleFormat::I32 => build_cpal_stream::<i32>(&self.device, &config, render, on_device_lost)?,
+            SampleFormat::I64 => build_cpal_stream::<i64>(&self.device, &config, render, on_device_lost)?,
+            SampleFormat::U16 => build_cpal_stream::<u16>(&self.device, &config, render, on_device_lost)?,
+            format => return Err(format!("Unsupported sample format: {:?}", format)),
+        };
+        stream.play().map_err(|e| format!("Failed to play stream: {}", e))?;
+        self.stream = Some(stream);
+        Ok(())
+    }
+}
+
+fn build_cpal_stream<T>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    mut render: RenderFn,
+    on_device_lost: DeviceLostFn,
+) -> Result<cpal::Stream, String>
+where
+    T: SizedSample + FromSample<f32>,
+{
+    let mut scratch: Vec<f32> = Vec::with_capacity(CPAL_SCRATCH_CAPACITY);
+    device
+        .build_output_stream(
+            config,
+            move |data: &mut [T], _: &cpal::OutputCallbackInfo| {
+                scratch.resize(data.len(), 0.0);
+                render(&mut scratch);
+                for (out, sample) in data.iter_mut().zip(scratch.iter()) {
+                    *out = T::from_sample(*sample);
+                }
+            },
+            move |err| {
+                eprintln!("Stream error: {}", err);
+                // 장치가 사라진 경우만 바로 알림 (그 외 에러로 스트림이 멈추면 재생 엔진의 멈춤 감지가 처리)
+                if let cpal::StreamError::DeviceNotAvailable = err {
+                    on_device_lost();
+                }
+            },
+            None,
+        )
+        .map_err(|e| format!("Failed to build stream: {}", e))
+}
+
+// 사운드 카드 대신 별도 스레드가 render를 호출 (null / WAV 출력 공용)
+// clocked면 실시간 속도로 블록 전체를 내보내고, 아니면 쉬지 않고 디코딩된 프레임만 내보냄
+struct RenderThread {
+    running: Arc<AtomicBool>,
+    handle: Option<thread::JoinHandle<()>>,
+}
+
+impl RenderThread {
+    fn spawn<F>(sample_rate: u32, channels: usize, clocked: bool, mut render: RenderFn, mut write: F) -> Self
+    where
+        F: FnMut(&[f32]) + Send + 'static,
+    {
+        let running = Arc::new(AtomicBool::new(true));
+        let running_clone = running.clone();
+        let block_frames = ((sample_rate as f64 * RENDER_BLOCK.as_secs_f64()) as usize).max(1);
+        let handle = thread::spawn(move || {
+            let mut buffer = vec![0.0f32; block_frames * channels];
+            let started = Instant::now();
+            let mut rendered_frames = 0u64;
+            while running_clone.load(Ordering::Relaxed) {
+                let frames = render(&mut buffer).min(block_frames);
+                if !clocked {
+                    // 디코딩을 기다리는 중 / 일시정지 / 재생 끝: 무음을 기록하지 않고 잠시 후 다시 시도
+                    if frames == 0 {
+                        thread::sleep(UNCLOCKED_IDLE_WAIT);
+                    } else {
+                        write(&buffer[..frames * channels]);
+                    }
+                    continue;
+                }
+                write(&buffer);
+                rendered_frames += block_frames as u64;
+                // 렌더링한 분량만큼 실제 시간이 지날 때까지 대기 (장치와 같은 속도 유지)
+                let due = Duration::from_secs_f64(rendered_frames as f64 / sample_rate as f64);
+                if let Some(wait) = due.checked_sub(started.elapsed()) {
+                    thread::sleep(wait);
+                }
+            }
+        });
+        Self {
+            running,
+            handle: Some(handle),
+        }
+    }
+}
+
+impl Drop for RenderThread {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+// 출력을 버리는 sink (사운드 카드 없는 환경에서 재생 엔진 실행용)
+pub struct NullSink {
+    sample_rate: u32,
+    channels: usize,
+    renderer: Option<RenderThread>,
+}
+
+impl NullSink {
+    pub fn new(sample_rate: u32, channels: usize) -> Self {
+        Self {
+            sample_rate,
+            channels,
+            renderer: None,
+        }
+    }
+}
+
+impl AudioSink for NullSink {
+    fn name(&self) -> String {
+        "Null output".to_string()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn channels(&self) -> usize {
+        self.channels
+    }
+
+    fn start(&mut self, render: RenderFn, _on_device_lost: DeviceLostFn) -> Result<(), String> {
+        self.renderer = Some(RenderThread::spawn(self.sample_rate, self.channels, true, render, |_| {}));
+        Ok(())
+    }
+}
+
+// 출력한 샘플을 32비트 float WAV 파일로 기록하는 sink (drop 시 헤더의 길이 갱신)
+// 실시간 대기 없이 디코딩된 만큼 바로 기록 (3분짜리 곡도 디코딩 속도로 렌더링)
+pub struct WavFileSink {
+    path: String,
+    sample_rate: u32,
+    channels: usize,
+    writer: Option<WavWriter>,
+    renderer: Option<RenderThread>,
+}
+
+impl WavFileSink {
+    pub fn create(path: &str, sample_rate: u32, channels: usize) -> Result<Self, String> {
+        let writer = WavWriter::create(path, sample_rate, channels)?;
+        Ok(Self {
+            path: path.to_string(),
+            sample_rate,
+            channels,
+            writer: Some(writer),
+            renderer: None,
+        })
+    }
+}
+
+impl AudioSink for WavFileSink {
+    fn name(&self) -> String {
+        format!("WAV file: {}", self.path)
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn channels(&self) -> usize {
+        self.channels
+    }
+
+    fn start(&mut self, render: RenderFn, _on_device_lost: DeviceLostFn) -> Result<(), String> {
+        let mut writer = self.writer.take().ok_or_else(|| "WAV sink already started".to_string())?;
+        let path = self.path.clone();
+        self.renderer = Some(RenderThread::spawn(self.sample_rate, self.channels, false, render, move |samples| {
+            if let Err(e) = writer.write_samples(samples) {
+                eprintln!("Failed to write WAV output {}: {}", path, e);
+            }
+        }));
+        Ok(())
+    }
+}
+
+struct WavWriter {
+    file: BufWriter<File>,
+    data_bytes: u64,
+}
+
+impl WavWriter {
+    fn create(path: &str, sample_rate: u32, channels: usize) -> Result<Self, String> {
+        let file = File::create(path).map_err(|e| format!("Failed to create WAV file {}: {}", path, e))?;
+        let mut writer = Self {
+            file: BufWriter::new(file),
+            data_bytes: 0,
+        };
+        writer.write_header(sample_rate, channels).map_err(|e| e.to_string())?;
+        Ok(writer)
+    }
+
+    fn write_header(&mut self, sample_rate: u32, channels: usize) -> std::io::Result<()> {
+        let block_align = (channels * 4) as u16;
+        let f = &mut self.file;
+        f.write_all(b"RIFF")?;
+        f.write_all(&0u32.to_le_bytes())?; // 파일 길이 (drop 시 갱신)
+        f.write_all(b"WAVE")?;
+        f.write_all(b"fmt ")?;
+        f.write_all(&16u32.to_le_bytes())?;
+        f.write_all(&3u16.to_le_bytes())?; // WAVE_FORMAT_IEEE_FLOAT
+        f.write_all(&(channels as u16).to_le_bytes())?;
+        f.write_all(&sample_rate.to_le_bytes())?;
+        f.write_all(&(sample_rate * block_align as u32).to_le_bytes())?;
+        f.write_all(&block_align.to_le_bytes())?;
+        f.write_all(&32u16.to_le_bytes())?;
+        f.write_all(b"data")?;
+        f.write_all(&0u32.to_le_bytes())?; // 데이터 길이 (drop 시 갱신)
+        Ok(())
+    }
+
+    fn write_samples(&mut self, samples: &[f32]) -> std::io::Result<()> {
+        for sample in samples {
+            self.file.write_all(&sample.to_le_bytes())?;
+        }
+        self.data_bytes += samples.len() as u64 * 4;
+        Ok(())
+    }
+
+    fn finalize(&mut self) -> std::io::Result<()> {
+        let data_bytes = self.data_bytes.min(u32::MAX as u64 - 36) as u32;
+        self.file.seek(SeekFrom::Start(4))?;
+        self.file.write_all(&(36 + data_bytes).to_le_bytes())?;
+        self.file.seek(SeekFrom::Start(40))?;
+        self.file.write_all(&data_bytes.to_le_bytes())?;
+        self.file.flush()
+    }
+}
+
+impl Drop for WavWriter {
+    fn drop(&mut self) {
+        if let Err(e) = self.finalize() {
+            eprintln!("Failed to finalize WAV output: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: u32 = 8000;
+    const CHANNELS: usize = 2;
+    // 렌더 함수가 내보내는 전체 길이 (블록 크기 80프레임의 배수가 아니게 해서 부분 블록도 확인)
+    const TOTAL_FRAMES: usize = 4321;
+
+    // 채널마다 다른 주파수의 사인파 (인터리브 샘플 index로 결정되므로 다시 만들어 비교 가능)
+    fn test_signal(index: usize) -> f32 {
+        let frame = (index / CHANNELS) as f32;
+        let frequency = if index.is_multiple_of(CHANNELS) { 440.0 } else { 660.0 };
+        0.5 * (2.0 * std::f32::consts::PI * frequency * frame / SAMPLE_RATE as f32).sin()
+    }
+
+    fn read_u16(bytes: &[u8], offset: usize) -> u16 {
+        u16::from_le_bytes([bytes[offset], bytes[offset + 1]])
+    }
+
+    fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+        u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+    }
+
+    #[test]
+    fn wav_sink_writes_rendered_signal() {
+        let path = std::env::temp_dir().join(format!("lcmp-wav-sink-test-{}.wav", std::process::id()));
+        let path_str = path.to_str().unwrap().to_string();
+
+        let mut sink = WavFileSink::create(&path_str, SAMPLE_RATE, CHANNELS).unwrap();
+        assert_eq!(sink.sample_rate(), SAMPLE_RATE);
+        assert_eq!(sink.channels(), CHANNELS);
+        let mut next_index = 0usize;
+        let render: RenderFn = Box::new(move |buffer: &mut [f32]| {
+            // TOTAL_FRAMES까지만 소리를 내고 이후는 무음 (기록되지 않아야 함)
+            let frames = (buffer.len() / CHANNELS).min(TOTAL_FRAMES - next_index / CHANNELS);
+            buffer.fill(0.0);
+            for sample in buffer[..frames * CHANNELS].iter_mut() {
+                *sample = test_signal(next_index);
+                next_index += 1;
+            }
+            frames
+        });
+        sink.start(render, Box::new(|| {})).unwrap();
+        // 실시간 대기가 없으므로 0.54초 분량이 이 시간 안에 모두 기록됨
+        thread::sleep(Duration::from_millis(200));
+        // drop 시 렌더 스레드 종료 + 헤더 길이 갱신
+        drop(sink);
+
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        // 헤더
+        assert_eq!(&bytes[0..4], b"RIFF");
+        assert_eq!(&bytes[8..12], b"WAVE");
+        assert_eq!(read_u16(&bytes, 20), 3); // IEEE float
+        assert_eq!(read_u16(&bytes, 22) as usize, CHANNELS);
+        assert_eq!(read_u32(&bytes, 24), SAMPLE_RATE);
+        assert_eq!(&bytes[36..40], b"data");
+        let data_len = read_u32(&bytes, 40) as usize;
+        assert_eq!(read_u32(&bytes, 4) as usize, 36 + data_len);
+        assert_eq!(bytes.len(), 44 + data_len);
+
+        // 샘플 (렌더 함수가 소리를 낸 프레임만 끊김 없이, 뒤쪽 무음은 기록되지 않음)
+        let samples: Vec<f32> = bytes[44..]
+            .chunks_exact(4)
+            .map(|chunk| f32::from_le_bytes(chunk.try_into().unwrap()))
+            .collect();
+        assert_eq!(samples.len(), TOTAL_FRAMES * CHANNELS);
+        for (index, sample) in samples.iter().enumerate() {
+            assert!((sample - test_signal(index)).abs() < 1e-6, "sample {} mismatch", index);
+        }
+    }
+}
@@ -0,0 +1,386 @@
+use std::collections::VecDeque;
+use std::fs::File;
+use std::time::{Duration, Instant};
+use symphonia::core::audio::{AudioBuffer, Channels, Signal};
+use symphonia::core::codecs::{Decoder, DecoderOptions, CODEC_TYPE_NULL};
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::{FormatOptions, FormatReader, SeekMode, SeekTo};
+use symphonia::core::io::{MediaSourceStream, MediaSourceStreamOptions};
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+use symphonia::core::units::{Time, TimeBase};
+use symphonia::default::get_probe;
+use super::channels::{channel_routing_matrix, OutputChannelRole};
+use super::loudness::ReplayGainInfo;
+
+// ✅ MAX_PACKET_SCAN을 크게 늘림 (앨범아트/메타 트랙이 많은 파일 대응)
+const MAX_PACKET_SCAN: usize = 10000;
+
+// 패킷 하나를 디코딩한 결과
+pub enum DecodeStatus {
+    Decoded,        // pending에 샘플 추가됨
+    Skipped,        // 빈 패킷 / 디코딩 실패 (다음 패킷으로 계속)
+    Eof,
+}
+
+// 재생용으로 열린 오디오 파일 (format reader + decoder)
+// 재생 중인 곡과 gapless로 이어질 다음 곡 모두 이 구조체로 다룸
+pub struct DecodeSource {
+    pub file_path: String,
+    pub sample_rate: u32,
+    pub channels: usize, // 원본 채널 수
+    pub channel_layout: Option<Channels>, // 원본 채널 구성 (컨테이너에 없으면 None)
+    pub bits_per_sample: Option<u32>, // 원본 비트 깊이 (손실 코덱은 None)
+    pub duration: Option<f64>, // 곡 길이 (초, 컨테이너에 프레임 수가 없으면 None)
+    pub decoded_ok: u64,
+    pub decoded_err: u64,
+    pub replaygain: Option<ReplayGainInfo>, // 라우드니스 분석 결과 (DB에 있을 때만)
+    pub gain: f32, // 디코딩 시 곱하는 선형 gain (ReplayGain)
+    pub position_frames: u64, // 다음에 pending에 들어갈 샘플의 곡 내 위치 (원본 샘플레이트 기준 프레임)
+    pub start_offset: f64, // 앞부분 무음을 건너뛰고 시작한 위치 (초, 건너뛰지 않았으면 0)
+    format: Box<dyn FormatReader>,
+    decoder: Box<dyn Decoder>,
+    track_id: u32,
+    time_base: Option<TimeBase>,
+    zero_frame_count: u32,
+    // Accurate seek 후 목표 위치까지 버릴 프레임 수 (패킷 시작 ~ 목표 타임스탬프)
+    skip_frames: u64,
+    // prime()으로 미리 디코딩해 둔 샘플 (다음 곡 프리디코딩용)
+    primed: Vec<VecDeque<f32>>,
+    primed_eof: bool,
+    // pending에 넣을 채널 구성 (기본 LR, 멀티채널 출력이면 원본 채널 구성)
+    layout: Vec<OutputChannelRole>,
+    // 원본 채널 -> layout 계수 (패킷의 채널 구성이 바뀔 때만 다시 계산)
+    routing: Option<(Channels, Vec<Vec<f32>>)>,
+    // CUE 가상 트랙 구간 (파일 기준 시작 프레임, 길이 프레임), 위치/길이/seek는 모두 구간 시작 기준
+    range_start: u64,
+    range_len: Option<u64>,
+}
+
+impl DecodeSource {
+    pub fn open(file_path: &str) -> Result<Self, String> {
+        let file = File::open(file_path)
+            .map_err(|e| format!("Failed to open file: {}", e))?;
+
+        // MediaSourceStream 버퍼 크기 증가 (프리로딩을 위해 충분히 큰 버퍼)
+        // VBR 파일과 큰 ID3 태그를 처리하기 위해 버퍼 크기 증가
+        let mss_opts = MediaSourceStreamOptions {
+            buffer_len: 8 * 1024 * 1024, // 8MB 버퍼 (VBR, 큰 ID3 태그 처리용)
+        };
+
+        let mss = MediaSourceStream::new(Box::new(file), mss_opts);
+        let mut hint = Hint::new();
+        if let Some(extension) = std::path::Path::new(file_path).extension() {
+            if let Some(ext_str) = extension.to_str() {
+                hint.with_extension(ext_str);
+            }
+        }
+
+        // 메타데이터 옵션: 큰 ID3 태그 처리
+        let meta_opts: MetadataOptions = Default::default();
+
+        // 포맷 옵션: VBR 파일과 ID3 태그 처리 강화
+        let fmt_opts = FormatOptions {
+            // gapless 재생 활성화 (encoder delay / padding 트리밍)
+            enable_gapless: true,
+            // ⭐⭐⭐ 매우 중요: seek_index 비활성화 (특정 파일의 초반 EOF 문제 해결)
+            // VBR 파일에서 부정확한 seek index로 인한 조기 EOF 방지
+            prebuild_seek_index: false,
+            ..Default::default()
+        };
+
+        let probed = get_probe().format(&hint, mss, &fmt_opts, &meta_opts)
+            .map_err(|e| format!("Failed to probe format: {}", e))?;
+        let format = probed.format;
+
+        // ✅ 오디오 트랙 선택: sample_rate/channels 있는 트랙 + 가장 긴 트랙 우선
+        // 첫 번째 유효한 코덱만 찾으면 비오디오 트랙(비디오/앨범아트 등)을 선택할 수 있음
+        let track = format.tracks()
+            .iter()
+            .filter(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+            .filter(|t| t.codec_params.sample_rate.is_some()) // 샘플 레이트가 있는 트랙만
+            .filter(|t| t.codec_params.channels.is_some()) // 채널 정보가 있는 트랙만
+            .max_by_key(|t| t.codec_params.n_frames.unwrap_or(0)) // 가장 긴 트랙 우선
+            .ok_or_else(|| "No valid audio track found".to_string())?;
+
+        let track_id = track.id;
+        let codec_params = track.codec_params.clone();
+        let sample_rate = codec_params.sample_rate.unwrap_or(44100);
+        let channel_layout = codec_params.channels;
+        let channels = channel_layout.map(|c| c.count()).unwrap_or(2);
+        let bits_per_sample = codec_params.bits_per_sample;
+        let duration = codec_params.n_frames.map(|n| n as f64 / sample_rate as f64);
+
+        // 디코더 옵션: 손상된 프레임 무시하고 계속 진행 (에러 복구 강화)
+        let decoder_opts = DecoderOptions { verify: false }; // 프레임 검증 비활성화 (손상된 프레임도 처리)
+
+        let decoder = symphonia::default::get_codecs()
+            .make(&codec_params, &decoder_opts)
+            .map_err(|e| format!("Failed to create decoder: {}", e))?;
+
+        Ok(Self {
+            file_path: file_path.to_string(),
+            sample_rate,
+            channels,
+            channel_layout,
+            bits_per_sample,
+            duration,
+            decoded_ok: 0,
+            decoded_err: 0,
+            replaygain: None,
+            gain: 1.0,
+            position_frames: 0,
+            start_offset: 0.0,
+            format,
+            decoder,
+            track_id,
+            time_base: codec_params.time_base,
+            zero_frame_count: 0,
+            skip_frames: 0,
+            primed: Vec::new(),
+            primed_eof: false,
+            layout: vec![OutputChannelRole::FrontLeft, OutputChannelRole::FrontRight],
+            routing: None,
+            range_start: 0,
+            range_len: None,
+        })
+    }
+
+    // 파일의 일부 구간만 재생 (CUE 가상 트랙, end가 None이면 파일 끝까지)
+    pub fn with_range(mut self, start: f64, end: Option<f64>) -> Result<Self, String> {
+        let sample_rate = self.sample_rate as f64;
+        let start = start.max(0.0);
+        self.range_start = (start * sample_rate).round() as u64;
+        self.range_len = end.map(|end| ((end - start).max(0.0) * sample_rate).round() as u64);
+        self.duration = match end {
+            Some(end) => Some((end - start).max(0.0)),
+            None => self.duration.map(|duration| (duration - start).max(0.0)),
+        };
+        self.seek(0.0, SeekMode::Accurate)
+            .or_else(|_| self.seek(0.0, SeekMode::Coarse))?;
+        Ok(self)
+    }
+
+    // 앞/뒤 무음 건너뛰기: start(초)부터 재생하고 end(초)에서 EOF (위치/길이는 곡 기준 그대로)
+    pub fn trim_silence(&mut self, start: f64, end: Option<f64>) -> Result<(), String> {
+        if let Some(end) = end {
+            let end_frames = (end.max(0.0) * self.sample_rate as f64).round() as u64;
+            self.range_len = Some(self.range_len.map_or(end_frames, |len| len.min(end_frames)));
+        }
+        if start > 0.0 {
+            self.seek(start, SeekMode::Accurate)
+                .or_else(|_| self.seek(start, SeekMode::Coarse))?;
+            self.start_offset = start;
+        }
+        Ok(())
+    }
+
+    // 지정한 시간으로 이동 (성공 시 디코더 리셋)
+    // Accurate 모드는 목표 위치 이전 샘플을 디코딩 후 잘라내서 샘플 단위로 정확하게 맞춤
+    pub fn seek(&mut self, seconds: f64, mode: SeekMode) -> Result<(), String> {
+        let seconds = seconds.max(0.0) + self.range_start as f64 / self.sample_rate as f64;
+        let whole = seconds as u64;
+        let seeked_to = self.format
+            .seek(
+                mode,
+                SeekTo::Time {
+                    track_id: Some(self.track_id),
+                    time: Time::new(whole, seconds - whole as f64),
+                },
+            )
+            .map_err(|e| format!("Failed to seek: {}", e))?;
+        self.decoder.reset();
+        let ts_diff = seeked_to.required_ts.saturating_sub(seeked_to.actual_ts);
+        let ts_to_frames = |ts: u64| match self.time_base {
+            Some(tb) => {
+                let time = tb.calc_time(ts);
+                ((time.seconds as f64 + time.frac) * self.sample_rate as f64).round() as u64
+            }
+            None => ts,
+        };
+        self.skip_frames = match mode {
+            SeekMode::Coarse => 0,
+            _ => ts_to_frames(ts_diff),
+        };
+        let position_frames = match mode {
+            SeekMode::Coarse => ts_to_frames(seeked_to.actual_ts),
+            _ => ts_to_frames(seeked_to.required_ts),
+        };
+        // 구간 시작보다 앞에 멈췄으면 (Coarse seek) 구간 시작까지 버림
+        self.skip_frames += self.range_start.saturating_sub(position_frames);
+        self.position_frames = position_frames.saturating_sub(self.range_start);
+        self.primed.clear();
+        self.primed_eof = false;
+        Ok(())
+    }
+
+    pub fn layout(&self) -> &[OutputChannelRole] {
+        &self.layout
+    }
+
+    // pending에 넣을 채널 구성 변경 (prime / 디코딩 전에 호출)
+    pub fn set_layout(&mut self, layout: Vec<OutputChannelRole>) {
+        self.layout = layout;
+        self.routing = None;
+    }
+
+    // 다음 곡 프리디코딩: 첫 오디오 패킷을 미리 디코딩해서 내부 버퍼에 보관
+    // (파일 열기/프로브/디코더 생성/첫 패킷 디코딩을 EOF 전에 끝내 두기 위함)
+    pub fn prime(&mut self) {
+        let mut primed: Vec<VecDeque<f32>> = self.layout.iter().map(|_| VecDeque::new()).collect();
+        for _ in 0..8 {
+            match self.decode_packet(&mut primed) {
+                DecodeStatus::Decoded => break,
+                DecodeStatus::Skipped => continue,
+                DecodeStatus::Eof => {
+                    self.primed_eof = true;
+                    break;
+                }
+            }
+        }
+        self.primed = primed;
+    }
+
+    // 다음 오디오 패킷을 디코딩해서 layout 채널별 샘플을 pending에 추가 (pending은 layout 채널 수만큼)
+    pub fn decode_into(&mut self, pending: &mut [VecDeque<f32>]) -> DecodeStatus {
+        if self.primed.first().is_some_and(|channel| !channel.is_empty()) {
+            for (target, primed) in pending.iter_mut().zip(self.primed.iter_mut()) {
+                target.extend(primed.drain(..));
+            }
+            return DecodeStatus::Decoded;
+        }
+        if self.primed_eof {
+            return DecodeStatus::Eof;
+        }
+        self.decode_packet(pending)
+    }
+
+    fn decode_packet(&mut self, pending: &mut [VecDeque<f32>]) -> DecodeStatus {
+        // ✅ CUE 가상 트랙: 구간 끝에 도달하면 EOF
+        if self.range_len.is_some_and(|len| self.position_frames >= len) {
+            return DecodeStatus::Eof;
+        }
+        // ✅ 오디오 트랙 패킷을 찾을 때까지 스캔 (시간 기준으로 변경)
+        let scan_start = Instant::now();
+        let scan_timeout = Duration::from_millis(500); // 500ms 동안 스캔
+        let mut attempts = 0usize;
+        let mut warned_timeout = false; // ✅ 경고는 루프당 1회만
+        let mut warned_max = false; // ✅ MAX_PACKET_SCAN 경고도 1회만
+        let packet = loop {
+            attempts += 1;
+            // 시간 기준 체크 (너무 많은 비오디오 패킷이 있어도 계속 시도)
+            if !warned_timeout && scan_start.elapsed() > scan_timeout && attempts > 100 {
+                eprintln!("Warning: audio packet scan timeout after {} attempts, continuing anyway", attempts);
+                warned_timeout = true; // 한 번만 경고
+            }
+            if !warned_max && attempts > MAX_PACKET_SCAN {
+                eprintln!("Warning: exceeded MAX_PACKET_SCAN={}, but continuing scan", MAX_PACKET_SCAN);
+                warned_max = true; // 한 번만 경고
+            }
+
+            match self.format.next_packet() {
+                Ok(packet) => {
+                    if packet.track_id() == self.track_id {
+                        break packet;
+                    }
+                    continue;
+                }
+                Err(SymphoniaError::ResetRequired) => {
+                    self.decoder.reset();
+                    continue;
+                }
+                Err(SymphoniaError::IoError(ref io_err))
+                    if io_err.kind() == std::io::ErrorKind::UnexpectedEof =>
+                {
+                    return DecodeStatus::Eof;
+                }
+                Err(_) => continue,
+            }
+        };
+
+        let audio_buf = match self.decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            Err(SymphoniaError::ResetRequired) => {
+                self.decoded_err += 1;
+                self.decoder.reset();
+                return DecodeStatus::Skipped;
+            }
+            Err(_) => {
+                self.decoded_err += 1;
+                return DecodeStatus::Skipped;
+            }
+        };
+        self.decoded_ok += 1;
+
+        let frames = audio_buf.frames();
+        if frames == 0 {
+            self.zero_frame_count += 1;
+            if self.zero_frame_count > 100 {
+                eprintln!("Too many consecutive zero-frame packets, resetting decoder");
+                self.decoder.reset();
+                self.zero_frame_count = 0;
+            }
+            return DecodeStatus::Skipped;
+        }
+        self.zero_frame_count = 0;
+
+        let spec = *audio_buf.spec();
+        let cap = audio_buf.capacity();
+
+        // ✅ cap 이상치 방어: 재생 경로에서는 완화 (정상 FLAC도 스킵하지 않도록)
+        // waveform 추출과 달리 재생에서는 cap을 믿고 버퍼를 만들되, 메모리 폭탄만 방어
+        let cap_limit = (self.sample_rate as usize * 30).max(8192); // 30초 분량 or 최소 8192
+        if cap > cap_limit {
+            eprintln!("Warning: packet capacity {} exceeds limit {}, using limit", cap, cap_limit);
+            // 스킵하지 않고 cap_limit만큼만 사용
+        }
+
+        let safe_cap = cap.min(cap_limit);
+        // ✅ safe_frames: frames가 safe_cap보다 클 수 있으므로 안전하게 제한
+        let safe_frames = frames.min(safe_cap);
+        let duration = symphonia::core::units::Duration::from(safe_cap as u64);
+        let mut f32_buf = AudioBuffer::<f32>::new(duration, spec);
+        audio_buf.convert(&mut f32_buf);
+
+        // ✅ encoder delay / padding 트리밍
+        // MP3/Vorbis 디코더는 직접 트리밍하므로 frames == dur, 그 외 디코더는 block_dur 그대로 나옴
+        let (trim_start, trim_end) = if frames as u64 == packet.block_dur() && packet.block_dur() != packet.dur() {
+            (packet.trim_start() as usize, packet.trim_end() as usize)
+        } else {
+            (0, 0)
+        };
+        let mut start = trim_start.min(safe_frames);
+        let mut end = safe_frames.saturating_sub(trim_end).max(start);
+        // ✅ Accurate seek: 목표 위치 이전 샘플 버림
+        if self.skip_frames > 0 {
+            let skip = (self.skip_frames as usize).min(end - start);
+            start += skip;
+            self.skip_frames -= skip as u64;
+        }
+        // ✅ CUE 가상 트랙: 구간 끝 이후 샘플 버림
+        if let Some(len) = self.range_len {
+            let remaining = len.saturating_sub(self.position_frames);
+            end = end.min(start.saturating_add(remaining as usize));
+        }
+
+        if self.routing.as_ref().map(|(channels, _)| *channels) != Some(spec.channels) {
+            self.routing = Some((spec.channels, channel_routing_matrix(spec.channels, &self.layout)));
+        }
+        let routing = self.routing.as_ref().map(|(_, matrix)| matrix.as_slice()).unwrap_or(&[]);
+
+        // ✅ 디코딩 후 샘플을 pending에 누적 (safe_frames만 사용)
+        // 멀티채널을 LR로 내보낼 때는 ITU 다운믹스, 출력 장치가 원본 채널을 모두 담을 수 있으면 그대로 옮김
+        for fi in start..end {
+            for (out, target) in pending.iter_mut().enumerate() {
+                let mut v = 0.0f32;
+                for (ch, coefficients) in routing.iter().enumerate() {
+                    v += f32_buf.chan(ch)[fi] * coefficients[out];
+                }
+                target.push_back(v * self.gain);
+            }
+        }
+        self.position_frames += (end - start) as u64;
+
+        DecodeStatus::Decoded
+    }
+}
@@ -0,0 +1,263 @@
+use serde::{Deserialize, Serialize};
+
+pub const SPEED_MIN: f64 = 0.5;
+pub const SPEED_MAX: f64 = 2.0;
+pub const PITCH_SEMITONES_MAX: f64 = 12.0;
+
+// WSOLA 파라미터 (초): 한 번에 복사하는 구간 / 이어 붙일 위치를 찾는 범위 / 겹쳐서 섞는 길이
+const SEQUENCE_SECONDS: f64 = 0.040;
+const SEEK_WINDOW_SECONDS: f64 = 0.015;
+const OVERLAP_SECONDS: f64 = 0.008;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TempoSettings {
+    pub speed: f64,           // 재생 속도 배율 (음정 유지)
+    pub pitch_semitones: f64, // 음정 이동 (반음, 속도와 무관)
+}
+
+impl Default for TempoSettings {
+    fn default() -> Self {
+        Self {
+            speed: 1.0,
+            pitch_semitones: 0.0,
+        }
+    }
+}
+
+impl TempoSettings {
+    pub fn clamped(self) -> Self {
+        let finite_or = |v: f64, d: f64| if v.is_finite() { v } else { d };
+        Self {
+            speed: finite_or(self.speed, 1.0).clamp(SPEED_MIN, SPEED_MAX),
+            pitch_semitones: finite_or(self.pitch_semitones, 0.0).clamp(-PITCH_SEMITONES_MAX, PITCH_SEMITONES_MAX),
+        }
+    }
+
+    pub fn is_identity(&self) -> bool {
+        (self.speed - 1.0).abs() < 1e-6 && self.pitch_semitones.abs() < 1e-6
+    }
+
+    pub fn pitch_ratio(&self) -> f64 {
+        2f64.powf(self.pitch_semitones / 12.0)
+    }
+}
+
+// 음정을 유지하는 속도 변경 + 음정 이동 (채널 인터리브, 출력 샘플레이트 기준)
+// 1) WSOLA로 길이를 pitch_ratio / speed 배로 늘림 (음정 유지)
+// 2) 3차 보간으로 pitch_ratio 배 빠르게 읽음 (길이 1 / pitch_ratio, 음정 pitch_ratio 배)
+// => 최종 길이는 1 / speed 배, 음정은 pitch_ratio 배
+pub struct TimeStretcher {
+    settings: TempoSettings,
+    channels: usize,
+    seq_frames: usize,
+    seek_frames: usize,
+    overlap_frames: usize,
+    input: Vec<f32>,   // WSOLA 입력 대기 샘플
+    overlap: Vec<f32>, // 직전 구간의 마지막 overlap 구간 (다음 구간과 섞음)
+    primed: bool,
+    skip_fract: f64,
+    stretched: Vec<f32>, // WSOLA 출력 (음정 이동 대기, 앞에 보간용 1프레임 유지)
+    read_pos: f64,       // stretched 안의 읽기 위치 (프레임)
+}
+
+impl TimeStretcher {
+    pub fn new(sample_rate: u32, channels: usize) -> Self {
+        let frames = |seconds: f64| ((sample_rate as f64 * seconds) as usize).max(16);
+        let mut stretcher = Self {
+            settings: TempoSettings::default(),
+            channels,
+            seq_frames: frames(SEQUENCE_SECONDS),
+            seek_frames: frames(SEEK_WINDOW_SECONDS),
+            overlap_frames: frames(OVERLAP_SECONDS),
+            input: Vec::new(),
+            overlap: Vec::new(),
+            primed: false,
+            skip_fract: 0.0,
+            stretched: Vec::new(),
+            read_pos: 0.0,
+        };
+        stretcher.reset();
+        stretcher
+    }
+
+    pub fn settings(&self) -> TempoSettings {
+        self.settings
+    }
+
+    // 설정 변경 (내부 버퍼는 유지해서 재생 중 변경해도 끊김 없음)
+    pub fn set(&mut self, settings: TempoSettings) {
+        self.settings = settings.clamped();
+    }
+
+    // seek 등으로 샘플이 불연속해질 때 내부 버퍼 초기화
+    pub fn reset(&mut self) {
+        self.input.clear();
+        self.overlap.clear();
+        self.primed = false;
+        self.skip_fract = 0.0;
+        self.stretched.clear();
+        self.stretched.resize(self.channels, 0.0);
+        self.read_pos = 1.0;
+    }
+
+    fn is_empty(&self) -> bool {
+        self.input.is_empty() && !self.primed && self.stretched.len() <= self.channels
+    }
+
+    // 아직 내보내지 않은 샘플이 출력에서 차지할 프레임 수 (트랙 경계 / 속도 변경 위치 계산용)
+    pub fn buffered_output_frames(&self) -> u64 {
+        if self.is_empty() {
+            return 0;
+        }
+        let ratio = self.settings.pitch_ratio();
+        let tempo = self.settings.speed / ratio;
+        // overlap 구간은 아직 input에 남아 있는 샘플이므로 따로 더하지 않음
+        let mut frames = (self.input.len() / self.channels) as f64 / tempo;
+        frames += ((self.stretched.len() / self.channels) as f64 - self.read_pos).max(0.0);
+        (frames / ratio).round() as u64
+    }
+
+    // fresh 샘플을 변환해서 같은 버퍼에 다시 채움 (1배속 / 음정 이동 없음이면 그대로 통과)
+    pub fn process(&mut self, samples: &mut Vec<f32>) {
+        if self.settings.is_identity() {
+            if self.is_empty() {
+                return;
+            }
+            // 변환 중이던 샘플을 먼저 내보내고 이후는 그대로 통과
+            let passthrough = std::mem::take(samples);
+            self.drain(samples);
+            samples.extend(passthrough);
+            return;
+        }
+        self.input.append(samples);
+        let mut stretched = Vec::new();
+        self.run_wsola(&mut stretched, usize::MAX);
+        self.stretched.extend(stretched);
+        self.transpose(samples, usize::MAX);
+    }
+
+    // EOF: 내부에 남은 샘플을 모두 변환해서 내보내고 초기화
+    pub fn drain(&mut self, out: &mut Vec<f32>) {
+        if self.is_empty() {
+            return;
+        }
+        let ratio = self.settings.pitch_ratio();
+        let tempo = self.settings.speed / ratio;
+
+        // 남은 입력이 차지할 길이만큼만 만들고 0-padding으로 생긴 부분은 잘라냄
+        let limit = ((self.input.len() / self.channels) as f64 / tempo).round() as usize;
+        let padding = (self.seek_frames + self.seq_frames + (tempo * self.seq_frames as f64) as usize) * self.channels;
+        self.input.resize(self.input.len() + padding, 0.0);
+        let mut stretched = Vec::new();
+        self.run_wsola(&mut stretched, limit);
+        if stretched.len() < limit * self.channels {
+            // 마지막 구간의 overlap 부분은 아직 내보내지 않았으므로 그대로 이어 붙임
+            stretched.extend_from_slice(&self.overlap);
+        }
+        stretched.truncate(limit * self.channels);
+        self.stretched.extend(stretched);
+
+        let available = (self.stretched.len() / self.channels) as f64 - self.read_pos;
+        let limit = (available / ratio).max(0.0).floor() as usize;
+        self.stretched.resize(self.stretched.len() + 3 * self.channels, 0.0);
+        self.transpose(out, limit);
+        self.reset();
+    }
+
+    // WSOLA: 이전 구간 끝과 가장 비슷하게 이어지는 위치를 찾아 겹쳐 섞으며 이어 붙임
+    fn run_wsola(&mut self, out: &mut Vec<f32>, limit_frames: usize) {
+        let tempo = self.settings.speed / self.settings.pitch_ratio();
+        let step = self.seq_frames - self.overlap_frames;
+        let channels = self.channels;
+        let mut produced = 0usize;
+        while produced < limit_frames {
+            let skip = tempo * step as f64 + self.skip_fract;
+            let skip_frames = skip as usize;
+            let needed = (self.seek_frames + self.seq_frames).max(skip_frames);
+            if self.input.len() / channels < needed {
+                break;
+            }
+
+            let offset = if self.primed { self.best_offset() } else { 0 };
+            let ov = self.overlap_frames;
+            let segment = &self.input[offset * channels..(offset + self.seq_frames) * channels];
+            if self.primed {
+                // 선형 크로스페이드
+                for i in 0..ov {
+                    let t = (i as f32 + 0.5) / ov as f32;
+                    for ch in 0..channels {
+                        out.push(self.overlap[i * channels + ch] * (1.0 - t) + segment[i * channels + ch] * t);
+                    }
+                }
+            } else {
+                out.extend_from_slice(&segment[..ov * channels]);
+            }
+            out.extend_from_slice(&segment[ov * channels..(self.seq_frames - ov) * channels]);
+            self.overlap.clear();
+            self.overlap.extend_from_slice(&segment[(self.seq_frames - ov) * channels..]);
+            self.primed = true;
+            produced += step;
+
+            self.skip_fract = skip - skip_frames as f64;
+            self.input.drain(..skip_frames * channels);
+        }
+    }
+
+    // 직전 overlap 구간과 상관도가 가장 높은 입력 위치 (앞쪽 LR 모노 합으로 비교)
+    fn best_offset(&self) -> usize {
+        let ov = self.overlap_frames;
+        let reference: Vec<f32> = self.overlap.chunks_exact(self.channels).map(|f| f[0] + f[1]).collect();
+        let candidate: Vec<f32> = self.input[..(self.seek_frames + ov) * self.channels]
+            .chunks_exact(self.channels)
+            .map(|f| f[0] + f[1])
+            .collect();
+
+        let mut best_offset = 0;
+        let mut best_score = f64::MIN;
+        for offset in 0..=self.seek_frames {
+            let window = &candidate[offset..offset + ov];
+            let mut corr = 0.0f64;
+            let mut norm = 0.0f64;
+            for (a, b) in reference.iter().zip(window) {
+                corr += (*a as f64) * (*b as f64);
+                norm += (*b as f64) * (*b as f64);
+            }
+            let score = corr / (norm + 1e-9).sqrt();
+            if score > best_score {
+                best_score = score;
+                best_offset = offset;
+            }
+        }
+        best_offset
+    }
+
+    // 3차 Hermite 보간으로 pitch_ratio 배 속도로 읽음 (음정 이동)
+    fn transpose(&mut self, out: &mut Vec<f32>, limit_frames: usize) {
+        let ratio = self.settings.pitch_ratio();
+        let channels = self.channels;
+        let frames = self.stretched.len() / channels;
+        let mut produced = 0usize;
+        while produced < limit_frames && self.read_pos + 2.0 < frames as f64 {
+            let i = self.read_pos as usize;
+            let t = (self.read_pos - i as f64) as f32;
+            for ch in 0..channels {
+                let s = |k: usize| self.stretched[k * channels + ch];
+                out.push(hermite(s(i - 1), s(i), s(i + 1), s(i + 2), t));
+            }
+            self.read_pos += ratio;
+            produced += 1;
+        }
+        // 보간에 필요한 직전 1프레임만 남기고 앞부분 제거
+        let consumed = (self.read_pos as usize).saturating_sub(1).min(frames);
+        self.stretched.drain(..consumed * channels);
+        self.read_pos -= consumed as f64;
+    }
+}
+
+fn hermite(y0: f32, y1: f32, y2: f32, y3: f32, t: f32) -> f32 {
+    let c1 = 0.5 * (y2 - y0);
+    let c2 = y0 - 2.5 * y1 + 2.0 * y2 - 0.5 * y3;
+    let c3 = 0.5 * (y3 - y0) + 1.5 * (y1 - y2);
+    ((c3 * t + c2) * t + c1) * t + y1
+}
@@ -0,0 +1,132 @@
+use rusqlite::params;
+use serde::Serialize;
+
+use crate::audio::read_chapters;
+use crate::database::get_connection;
+
+// 이어듣기 위치가 곡 시작/끝에 이만큼 가까우면 처음부터 재생
+const RESUME_EDGE_SECONDS: f64 = 5.0;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SongChapter {
+    pub id: i64,
+    pub song_id: i64,
+    pub title: String,
+    pub start: f64,       // 초
+    pub end: Option<f64>, // 초 (마지막 챕터는 곡 끝이라 없을 수 있음)
+}
+
+// 곡 파일의 챕터를 읽어 DB에 저장 (곡마다 한 번만, 스캔 시 호출)
+pub(crate) fn scan_song_chapters(conn: &rusqlite::Connection, file_path: &str) -> Result<(), String> {
+    let song: Option<(i64, bool)> = conn
+        .query_row(
+            "SELECT id, COALESCE(chapters_scanned, 0) FROM songs WHERE file_path = ?1 AND cue_source_path IS NULL",
+            [file_path],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .ok();
+    let Some((song_id, false)) = song else {
+        return Ok(());
+    };
+
+    let chapters = read_chapters(file_path);
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    tx.execute("DELETE FROM song_chapters WHERE song_id = ?1", [song_id])
+        .map_err(|e| e.to_string())?;
+    for (position, chapter) in chapters.iter().enumerate() {
+        tx.execute(
+            "INSERT INTO song_chapters (song_id, position, title, start_time, end_time) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![song_id, position as i64, &chapter.title, chapter.start, chapter.end],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    tx.execute("UPDATE songs SET chapters_scanned = 1 WHERE id = ?1", [song_id])
+        .map_err(|e| e.to_string())?;
+    tx.commit().map_err(|e| e.to_string())
+}
+
+// 곡의 챕터 목록 (시간순)
+#[tauri::command]
+pub async fn get_song_chapters(song_id: i64) -> Result<Vec<SongChapter>, String> {
+    let conn = get_connection().map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, song_id, title, start_time, end_time
+             FROM song_chapters
+             WHERE song_id = ?1
+             ORDER BY position ASC",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([song_id], |row| {
+            Ok(SongChapter {
+                id: row.get(0)?,
+                song_id: row.get(1)?,
+                title: row.get(2)?,
+                start: row.get(3)?,
+                end: row.get(4)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    let mut chapters = Vec::new();
+    for chapter in rows {
+        chapters.push(chapter.map_err(|e| e.to_string())?);
+    }
+    Ok(chapters)
+}
+
+#[tauri::command]
+pub async fn get_song_remember_position(song_id: i64) -> Result<bool, String> {
+    let conn = get_connection().map_err(|e| e.to_string())?;
+    conn.query_row(
+        "SELECT COALESCE(remember_position, 0) FROM songs WHERE id = ?1",
+        [song_id],
+        |row| row.get(0),
+    )
+    .map_err(|e| format!("Song not found: {}", e))
+}
+
+// 곡별 위치 기억 설정 (끄면 저장된 위치도 지움)
+#[tauri::command]
+pub async fn set_song_remember_position(song_id: i64, enabled: bool) -> Result<(), String> {
+    let conn = get_connection().map_err(|e| e.to_string())?;
+    let updated = conn
+        .execute(
+            "UPDATE songs SET remember_position = ?1, resume_position = CASE WHEN ?1 THEN resume_position ELSE NULL END WHERE id = ?2",
+            params![enabled, song_id],
+        )
+        .map_err(|e| e.to_string())?;
+    if updated == 0 {
+        return Err(format!("Song not found: {}", song_id));
+    }
+    Ok(())
+}
+
+// 위치 기억 곡의 이어듣기 위치 (처음/끝 근처면 None)
+pub(crate) fn load_resume_position(file_path: &str) -> Option<f64> {
+    let conn = get_connection().ok()?;
+    let (position, duration): (Option<f64>, Option<f64>) = conn
+        .query_row(
+            "SELECT resume_position, duration FROM songs WHERE file_path = ?1 AND remember_position = 1",
+            [file_path],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .ok()?;
+    position
+        .filter(|&position| position >= RESUME_EDGE_SECONDS)
+        .filter(|&position| duration.is_none_or(|duration| position < duration - RESUME_EDGE_SECONDS))
+}
+
+// 위치 기억 곡이면 마지막 위치 저장 (None이면 끝까지 들은 것이므로 지움)
+pub(crate) fn save_resume_position(file_path: &str, position: Option<f64>) {
+    if let Ok(conn) = get_connection() {
+        if let Err(e) = conn.execute(
+            "UPDATE songs SET resume_position = ?1 WHERE file_path = ?2 AND remember_position = 1",
+            params![position, file_path],
+        ) {
+            eprintln!("Failed to save resume position: {}", e);
+        }
+    }
+}
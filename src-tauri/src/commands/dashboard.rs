@@ -1,8 +1,13 @@
 use rusqlite::{params, Connection};
 use serde::Serialize;
 
+use crate::commands::settings::{read_setting, write_setting};
 use crate::database::get_connection;
 
+// 재생 횟수로 인정하는 기준 기본값 (곡 길이의 50% 또는 4분 이상 들으면 인정)
+const PLAY_COUNT_DEFAULT_PERCENT: f64 = 50.0;
+const PLAY_COUNT_DEFAULT_SECONDS: f64 = 240.0;
+
 #[derive(Serialize)]
 pub struct DashboardDateCount {
     pub label: String,
@@ -73,9 +78,29 @@ pub struct DashboardStats {
     pub tag_usage: Vec<DashboardTagUsage>,
 }
 
+// 재생 횟수 인정 기준 (둘 중 하나만 넘어도 인정, 0이면 해당 기준 사용 안 함)
+#[derive(Serialize)]
+pub struct PlayCountThreshold {
+    pub min_percent: f64,
+    pub min_seconds: f64,
+}
+
+fn load_play_count_threshold(conn: &Connection) -> PlayCountThreshold {
+    let read = |key: &str, default: f64| {
+        read_setting(conn, key)
+            .and_then(|value| value.parse::<f64>().ok())
+            .unwrap_or(default)
+    };
+    PlayCountThreshold {
+        min_percent: read("play_count_min_percent", PLAY_COUNT_DEFAULT_PERCENT),
+        min_seconds: read("play_count_min_seconds", PLAY_COUNT_DEFAULT_SECONDS),
+    }
+}
+
 fn get_total_size(conn: &Connection) -> i64 {
     let mut total: i64 = 0;
-    let mut stmt = match conn.prepare("SELECT file_path FROM songs") {
+    // CUE 가상 트랙은 원본 파일을 한 번만 계산
+    let mut stmt = match conn.prepare("SELECT DISTINCT COALESCE(cue_source_path, file_path) FROM songs") {
         Ok(stmt) => stmt,
         Err(_) => return 0,
     };
@@ -171,6 +196,27 @@ pub async fn record_queue_event(
     Ok(())
 }
 
+#[tauri::command]
+pub async fn get_play_count_threshold() -> Result<PlayCountThreshold, String> {
+    let conn = get_connection().map_err(|e| e.to_string())?;
+    Ok(load_play_count_threshold(&conn))
+}
+
+#[tauri::command]
+pub async fn set_play_count_threshold(min_percent: f64, min_seconds: f64) -> Result<PlayCountThreshold, String> {
+    if !min_percent.is_finite() || !min_seconds.is_finite() {
+        return Err("Invalid play count threshold".to_string());
+    }
+    let threshold = PlayCountThreshold {
+        min_percent: min_percent.clamp(0.0, 100.0),
+        min_seconds: min_seconds.max(0.0),
+    };
+    let conn = get_connection().map_err(|e| e.to_string())?;
+    write_setting(&conn, "play_count_min_percent", &threshold.min_percent.to_string())?;
+    write_setting(&conn, "play_count_min_seconds", &threshold.min_seconds.to_string())?;
+    Ok(threshold)
+}
+
 #[tauri::command]
 pub async fn get_dashboard_stats(date_unit: String) -> Result<DashboardStats, String> {
     let conn = get_connection().map_err(|e| e.to_string())?;
@@ -191,16 +237,24 @@ pub async fn get_dashboard_stats(date_unit: String) -> Result<DashboardStats, St
 
     let date_counts = get_date_counts(&conn, date_unit.as_str());
 
+    // 많이 들은 곡: 끝까지 들었거나 기준 이상 들은 기록만 재생 횟수로 인정
+    // (play_duration이 NULL인 기록은 아직 마무리되지 않았거나 비정상 종료로 남은 기록이므로 제외,
+    //  completed 컬럼이 생기기 전 기록은 마이그레이션에서 completed = 1로 채워 두었으므로 그대로 인정됨)
+    let threshold = load_play_count_threshold(&conn);
     let mut top_songs = Vec::new();
     if let Ok(mut stmt) = conn.prepare(
         "SELECT s.id, s.title, s.artist, s.file_path, COUNT(ph.id) as play_count
          FROM play_history ph
          JOIN songs s ON s.id = ph.song_id
+         WHERE ph.completed = 1
+            OR (?1 <= 0 AND ?2 <= 0)
+            OR (?1 > 0 AND s.duration > 0 AND ph.play_duration >= s.duration * ?1 / 100.0)
+            OR (?2 > 0 AND ph.play_duration >= ?2)
          GROUP BY ph.song_id
          ORDER BY play_count DESC
          LIMIT 5",
     ) {
-        if let Ok(rows) = stmt.query_map([], |row| {
+        if let Ok(rows) = stmt.query_map(params![threshold.min_percent, threshold.min_seconds], |row| {
             Ok(DashboardSongStat {
                 id: row.get(0)?,
                 title: row.get(1)?,
@@ -0,0 +1,66 @@
+use crate::audio::{DynamicsPreset, DynamicsSettings};
+use crate::commands::player::apply_live_dynamics_settings;
+use crate::commands::settings::{read_setting, write_setting};
+use crate::database::get_connection;
+
+// DB에 저장된 컴프레서/리미터 설정 (settings 테이블에 JSON으로 저장, 없으면 컴프레서 끔 + 리미터 켬)
+pub(crate) fn load_dynamics_settings() -> DynamicsSettings {
+    get_connection()
+        .ok()
+        .and_then(|conn| read_setting(&conn, "dynamics_settings"))
+        .and_then(|value| serde_json::from_str::<DynamicsSettings>(&value).ok())
+        .unwrap_or_default()
+        .clamped()
+}
+
+fn save_dynamics_settings(settings: &DynamicsSettings) -> Result<(), String> {
+    let conn = get_connection()?;
+    let value = serde_json::to_string(settings).map_err(|e| e.to_string())?;
+    write_setting(&conn, "dynamics_settings", &value)
+}
+
+#[tauri::command]
+pub async fn get_dynamics_settings() -> Result<DynamicsSettings, String> {
+    Ok(load_dynamics_settings())
+}
+
+// 컴프레서/리미터 값을 직접 조정 (프리셋은 custom으로 바뀜, 재생 중이면 즉시 반영)
+#[tauri::command]
+pub async fn set_dynamics_settings(
+    compressor_enabled: bool,
+    threshold_db: f64,
+    ratio: f64,
+    release_ms: f64,
+    makeup_db: f64,
+    limiter_enabled: bool,
+) -> Result<DynamicsSettings, String> {
+    let settings = DynamicsSettings {
+        preset: DynamicsPreset::Custom,
+        compressor_enabled,
+        threshold_db,
+        ratio,
+        release_ms,
+        makeup_db,
+        limiter_enabled,
+    }
+    .clamped();
+    save_dynamics_settings(&settings)?;
+    apply_live_dynamics_settings(&settings)?;
+    Ok(settings)
+}
+
+// 프리셋 적용 (off / light / night / heavy, 리미터 켬/끔은 그대로 유지)
+#[tauri::command]
+pub async fn apply_dynamics_preset(preset: String) -> Result<DynamicsSettings, String> {
+    let preset = DynamicsPreset::from_setting(&preset);
+    if preset == DynamicsPreset::Custom {
+        return Err("Custom is not a preset".to_string());
+    }
+    let settings = DynamicsSettings {
+        limiter_enabled: load_dynamics_settings().limiter_enabled,
+        ..DynamicsSettings::from_preset(preset)
+    };
+    save_dynamics_settings(&settings)?;
+    apply_live_dynamics_settings(&settings)?;
+    Ok(settings)
+}
@@ -0,0 +1,131 @@
+use rusqlite::params;
+use serde::Serialize;
+
+use crate::audio::{EqBand, EqSettings};
+use crate::commands::player::apply_live_eq_settings;
+use crate::commands::settings::{read_setting, write_setting};
+use crate::database::get_connection;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EqPreset {
+    pub id: i64,
+    pub name: String,
+    pub preamp_db: f64,
+    pub bands: Vec<EqBand>,
+}
+
+// DB에 저장된 EQ 설정 (settings 테이블에 JSON으로 저장, 없으면 꺼진 기본값)
+pub(crate) fn load_eq_settings() -> EqSettings {
+    get_connection()
+        .ok()
+        .and_then(|conn| read_setting(&conn, "eq_settings"))
+        .and_then(|value| serde_json::from_str::<EqSettings>(&value).ok())
+        .unwrap_or_default()
+        .clamped()
+}
+
+fn save_eq_settings(settings: &EqSettings) -> Result<(), String> {
+    let conn = get_connection()?;
+    let value = serde_json::to_string(settings).map_err(|e| e.to_string())?;
+    write_setting(&conn, "eq_settings", &value)
+}
+
+fn read_preset(conn: &rusqlite::Connection, id: i64) -> Result<EqPreset, String> {
+    let (name, preamp_db, bands): (String, f64, String) = conn
+        .query_row(
+            "SELECT name, preamp_db, bands FROM eq_presets WHERE id = ?1",
+            [id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .map_err(|e| format!("EQ preset not found: {}", e))?;
+    let bands: Vec<EqBand> = serde_json::from_str(&bands).unwrap_or_default();
+    // 잘못 저장된 밴드 값도 재생에 쓸 수 있는 범위로 맞춤
+    let settings = EqSettings { enabled: true, preamp_db, bands }.clamped();
+    Ok(EqPreset {
+        id,
+        name,
+        preamp_db: settings.preamp_db,
+        bands: settings.bands,
+    })
+}
+
+#[tauri::command]
+pub async fn get_eq_settings() -> Result<EqSettings, String> {
+    Ok(load_eq_settings())
+}
+
+// EQ 설정 저장 (재생 중이면 끊김 없이 즉시 반영)
+#[tauri::command]
+pub async fn set_eq_settings(enabled: bool, preamp_db: f64, bands: Vec<EqBand>) -> Result<EqSettings, String> {
+    let settings = EqSettings { enabled, preamp_db, bands }.clamped();
+    save_eq_settings(&settings)?;
+    apply_live_eq_settings(&settings)?;
+    Ok(settings)
+}
+
+#[tauri::command]
+pub async fn get_eq_presets() -> Result<Vec<EqPreset>, String> {
+    let conn = get_connection()?;
+    let mut stmt = conn
+        .prepare("SELECT id FROM eq_presets ORDER BY name ASC")
+        .map_err(|e| e.to_string())?;
+    let ids: Vec<i64> = stmt
+        .query_map([], |row| row.get(0))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let mut presets = Vec::new();
+    for id in ids {
+        presets.push(read_preset(&conn, id)?);
+    }
+    Ok(presets)
+}
+
+// 프리셋 저장 (같은 이름이 있으면 덮어씀)
+#[tauri::command]
+pub async fn save_eq_preset(name: String, preamp_db: f64, bands: Vec<EqBand>) -> Result<EqPreset, String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Preset name is empty".to_string());
+    }
+    let settings = EqSettings { enabled: true, preamp_db, bands }.clamped();
+    let bands_json = serde_json::to_string(&settings.bands).map_err(|e| e.to_string())?;
+
+    let conn = get_connection()?;
+    conn.execute(
+        "INSERT INTO eq_presets (name, preamp_db, bands) VALUES (?1, ?2, ?3)
+         ON CONFLICT(name) DO UPDATE SET preamp_db = excluded.preamp_db, bands = excluded.bands, updated_at = CURRENT_TIMESTAMP",
+        params![name, settings.preamp_db, bands_json],
+    )
+    .map_err(|e| e.to_string())?;
+
+    let id: i64 = conn
+        .query_row("SELECT id FROM eq_presets WHERE name = ?1", [&name], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+    read_preset(&conn, id)
+}
+
+#[tauri::command]
+pub async fn delete_eq_preset(id: i64) -> Result<(), String> {
+    let conn = get_connection()?;
+    conn.execute("DELETE FROM eq_presets WHERE id = ?1", [id])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// 프리셋을 현재 EQ 설정으로 적용 (EQ도 켬)
+#[tauri::command]
+pub async fn apply_eq_preset(id: i64) -> Result<EqSettings, String> {
+    let conn = get_connection()?;
+    let preset = read_preset(&conn, id)?;
+    let settings = EqSettings {
+        enabled: true,
+        preamp_db: preset.preamp_db,
+        bands: preset.bands,
+    };
+    save_eq_settings(&settings)?;
+    apply_live_eq_settings(&settings)?;
+    Ok(settings)
+}
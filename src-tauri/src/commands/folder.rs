@@ -1,5 +1,7 @@
-﻿use crate::database::get_connection;
+﻿use crate::audio::{cue_track_end, cue_track_path, read_cue_sheet, resolve_cue_audio_path};
+use crate::database::get_connection;
 use crate::models::Folder;
+use crate::commands::chapter::scan_song_chapters;
 use crate::commands::player::extract_metadata;
 use crate::commands::song::{normalize_tags, set_song_tags};
 use rusqlite::{Result, params, params_from_iter};
@@ -69,16 +71,107 @@ pub async fn add_folder(path: String, name: Option<String>) -> Result<Folder, St
     Ok(folder)
 }
 
+// CUE 시트의 트랙을 가상 곡으로 추가 (이미 있으면 구간만 갱신)
+// 트랙이 2개 이상인 FILE만 처리하고, 처리한 원본 파일 경로(정규화)를 반환
+fn scan_cue_sheet(
+    conn: &rusqlite::Connection,
+    cue_path: &Path,
+    audio_extensions: &[&str],
+    scanned_paths: &mut HashSet<String>,
+) -> Result<Vec<String>, String> {
+    let sheet = read_cue_sheet(cue_path)?;
+    let mut covered = Vec::new();
+
+    for cue_file in &sheet.files {
+        if cue_file.tracks.len() < 2 {
+            continue;
+        }
+        let Some(audio_path) = resolve_cue_audio_path(cue_path, &cue_file.name, audio_extensions) else {
+            eprintln!("CUE 시트가 가리키는 파일이 없습니다: {} ({})", cue_file.name, cue_path.display());
+            continue;
+        };
+        let audio_path = audio_path.to_string_lossy().to_string();
+
+        // 시트에 없는 정보는 원본 파일 메타데이터로 채움
+        let (_, artist_meta, album_meta, year_meta, genre_meta, duration_meta, tags_meta) =
+            extract_metadata(&audio_path).unwrap_or((None, None, None, None, None, None, Vec::new()));
+        let album = sheet.title.clone().or(album_meta).unwrap_or_else(|| "앨범 없음".to_string());
+        let year = sheet.year().or(year_meta);
+        let genre = sheet.genre.clone().or(genre_meta);
+
+        for (index, track) in cue_file.tracks.iter().enumerate() {
+            let file_path = cue_track_path(&audio_path, track.number);
+            let end = cue_track_end(cue_file, index);
+            let duration = end.or(duration_meta).map(|end| (end - track.start).max(0.0));
+
+            let exists: bool = conn
+                .query_row(
+                    "SELECT EXISTS(SELECT 1 FROM songs WHERE file_path = ?1)",
+                    [&file_path],
+                    |row| row.get(0),
+                )
+                .unwrap_or(false);
+
+            if exists {
+                // 시트가 수정됐을 수 있으므로 구간은 항상 갱신 (제목 등은 사용자가 편집했을 수 있으므로 유지)
+                conn.execute(
+                    "UPDATE songs SET cue_source_path = ?1, cue_start = ?2, cue_end = ?3, duration = ?4 WHERE file_path = ?5",
+                    params![&audio_path, track.start, end, duration, &file_path],
+                )
+                .map_err(|e| format!("CUE 트랙 업데이트 오류: {}", e))?;
+            } else {
+                let title = track.title.clone().unwrap_or_else(|| format!("Track {:02}", track.number));
+                let artist = track
+                    .performer
+                    .clone()
+                    .or_else(|| sheet.performer.clone())
+                    .or_else(|| artist_meta.clone())
+                    .unwrap_or_else(|| "아티스트 없음".to_string());
+                conn.execute(
+                    "INSERT OR IGNORE INTO songs (file_path, title, artist, album, duration, year, genre, cue_source_path, cue_start, cue_end)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                    params![&file_path, &title, &artist, &album, duration, year, &genre, &audio_path, track.start, end],
+                )
+                .map_err(|e| format!("CUE 트랙 추가 오류: {}", e))?;
+
+                if !tags_meta.is_empty() {
+                    let song_id = conn.last_insert_rowid();
+                    let _ = set_song_tags(conn, song_id, normalize_tags(tags_meta.clone()));
+                }
+            }
+            scanned_paths.insert(file_path.replace("\\", "/"));
+        }
+        covered.push(audio_path.replace("\\", "/"));
+    }
+
+    Ok(covered)
+}
+
 // 폴더 내부 오디오 파일을 스캔해서 DB에 추가
 // song.rs에서도 사용하므로 pub(crate)로 공개
 pub(crate) fn scan_folder_for_songs(conn: &rusqlite::Connection, folder_path: &str) -> Result<(), String> {
     // 지원 가능한 오디오 확장자
-    let audio_extensions = ["mp3", "flac", "wav", "m4a", "aac", "ogg", "opus", "wma"];
+    let audio_extensions = ["mp3", "flac", "wav", "m4a", "m4b", "aac", "ogg", "opus", "wma"];
     
     let walker = WalkDir::new(folder_path).into_iter();
     let mut scanned_paths: HashSet<String> = HashSet::new();
     let normalized_folder = folder_path.replace("\\", "/");
     
+    // ✅ CUE 시트 먼저 처리: 시트로 나뉜 파일은 곡 하나로 추가하지 않고 트랙별 가상 곡으로 추가
+    let mut cue_covered: HashSet<String> = HashSet::new();
+    for entry in WalkDir::new(folder_path).into_iter().filter_map(|entry| entry.ok()) {
+        let path = entry.path();
+        let is_cue = path.is_file()
+            && path.extension().is_some_and(|ext| ext.to_string_lossy().eq_ignore_ascii_case("cue"));
+        if !is_cue {
+            continue;
+        }
+        match scan_cue_sheet(conn, path, &audio_extensions, &mut scanned_paths) {
+            Ok(covered) => cue_covered.extend(covered),
+            Err(e) => eprintln!("CUE 시트 처리 오류 {}: {}", path.display(), e),
+        }
+    }
+    
     for entry in walker {
         let entry = entry.map_err(|e| format!("파일 스캔 오류: {}", e))?;
         let path = entry.path();
@@ -101,6 +194,9 @@ pub(crate) fn scan_folder_for_songs(conn: &rusqlite::Connection, folder_path: &s
         // 파일 경로를 문자열로 변환
         let file_path = path.to_string_lossy().to_string();
         let normalized_path = file_path.replace("\\", "/");
+        if cue_covered.contains(&normalized_path) {
+            continue;
+        }
         scanned_paths.insert(normalized_path);
         
         // DB에 존재하는지 확인
@@ -224,6 +320,11 @@ pub(crate) fn scan_folder_for_songs(conn: &rusqlite::Connection, folder_path: &s
                 .map_err(|e| format!("메타데이터 업데이트 오류: {}", e))?;
             }
         }
+        
+        // 챕터 추출 (곡마다 처음 한 번만)
+        if let Err(e) = scan_song_chapters(conn, &file_path) {
+            eprintln!("챕터 추출 오류 {}: {}", file_path, e);
+        }
     }
 
     // 스캔 결과에 없는 파일은 DB에서 제거 (폴더 경로 내부만)
@@ -289,6 +390,8 @@ pub(crate) fn scan_folder_for_songs(conn: &rusqlite::Connection, folder_path: &s
     
     // 스캔 완료 후 웨이브폼 없는 곡을 백그라운드에서 생성
     crate::commands::song::generate_waveforms_for_songs_without_waveform(conn);
+    // 무음 건너뛰기가 켜져 있으면 새로 추가된 곡의 무음 구간도 미리 검사
+    crate::commands::silence::start_silence_scan(None);
     
     Ok(())
 }
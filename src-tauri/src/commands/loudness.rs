@@ -0,0 +1,283 @@
+use std::collections::{HashSet, VecDeque};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use rusqlite::params;
+use serde::Serialize;
+
+use crate::audio::{integrated_loudness, DecodeStatus, LoudnessMeter, ReplayGainInfo, REPLAYGAIN_REFERENCE_LUFS};
+use crate::commands::player::{load_replaygain_settings, open_song_source, song_source_path};
+use crate::commands::song::write_replaygain_tags;
+use crate::database::get_connection;
+
+// 앨범 정보가 없는 곡에 folder.rs가 넣는 기본값 (앨범 라우드니스 계산에서 제외)
+const NO_ALBUM: &str = "앨범 없음";
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoudnessScanStatus {
+    pub running: bool,
+    pub current_song_id: Option<i64>,
+    pub analyzed: usize,
+    pub failed: usize,
+    pub remaining: usize,
+}
+
+// 라우드니스 분석 작업 진행 상태 (한 번에 하나의 작업만 실행)
+static LOUDNESS_SCAN_STATUS: Mutex<Option<LoudnessScanStatus>> = Mutex::new(None);
+static LOUDNESS_SCAN_CANCEL: AtomicBool = AtomicBool::new(false);
+
+// 분석 단위 (앨범 여부, [(song_id, file_path)])
+type ScanGroup = (bool, Vec<(i64, String)>);
+
+// 파일 하나 분석 결과
+struct TrackLoudness {
+    lufs: f64,
+    peak: f64,
+    block_energies: Vec<f64>,
+}
+
+// 파일 전체를 디코딩하면서 integrated loudness / true peak 측정 (CUE 가상 트랙은 해당 구간만)
+fn analyze_file(file_path: &str) -> Result<TrackLoudness, String> {
+    let mut source = open_song_source(file_path)?;
+    let mut meter = LoudnessMeter::new(source.sample_rate);
+    let mut pending: [VecDeque<f32>; 2] = Default::default();
+
+    loop {
+        if LOUDNESS_SCAN_CANCEL.load(Ordering::Relaxed) {
+            return Err("Loudness scan cancelled".to_string());
+        }
+        match source.decode_into(&mut pending) {
+            DecodeStatus::Decoded => {
+                let [pending_l, pending_r] = &mut pending;
+                for (l, r) in pending_l.drain(..).zip(pending_r.drain(..)) {
+                    meter.process(l, r);
+                }
+            }
+            DecodeStatus::Skipped => {}
+            DecodeStatus::Eof => break,
+        }
+    }
+
+    if source.decoded_ok == 0 {
+        return Err(format!("No decodable audio packets ({} errors)", source.decoded_err));
+    }
+
+    let block_energies = meter.block_energies();
+    // 무음 / 너무 짧은 곡은 기준 라우드니스로 간주 (gain 0 dB)
+    let lufs = integrated_loudness(&block_energies).unwrap_or(REPLAYGAIN_REFERENCE_LUFS);
+    Ok(TrackLoudness {
+        lufs,
+        peak: meter.true_peak(),
+        block_energies,
+    })
+}
+
+// 재생용: DB에 저장된 곡의 라우드니스 분석 결과 (분석 전이면 None)
+pub(crate) fn load_song_replaygain(file_path: &str) -> Option<ReplayGainInfo> {
+    let conn = get_connection().ok()?;
+    conn.query_row(
+        "SELECT loudness_track_lufs, loudness_track_peak, loudness_album_lufs, loudness_album_peak
+         FROM songs WHERE file_path = ?1",
+        [file_path],
+        |row| {
+            Ok((
+                row.get::<_, Option<f64>>(0)?,
+                row.get::<_, Option<f64>>(1)?,
+                row.get::<_, Option<f64>>(2)?,
+                row.get::<_, Option<f64>>(3)?,
+            ))
+        },
+    )
+    .ok()
+    .and_then(|(track_lufs, track_peak, album_lufs, album_peak)| {
+        Some(ReplayGainInfo {
+            track_lufs: track_lufs?,
+            track_peak: track_peak.unwrap_or(0.0),
+            album_lufs,
+            album_peak,
+        })
+    })
+}
+
+// 분석 단위: 같은 앨범 + 같은 폴더의 곡들은 함께 분석 (앨범 라우드니스 계산용)
+fn collect_scan_groups(conn: &rusqlite::Connection) -> Result<Vec<ScanGroup>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, file_path, album FROM songs
+             WHERE loudness_track_lufs IS NULL
+             ORDER BY album ASC, file_path ASC",
+        )
+        .map_err(|e| e.to_string())?;
+    let pending: Vec<(i64, String, Option<String>)> = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let parent_dir = |path: &str| {
+        Path::new(path)
+            .parent()
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_default()
+    };
+
+    let mut album_stmt = conn
+        .prepare("SELECT id, file_path FROM songs WHERE album = ?1 ORDER BY file_path ASC")
+        .map_err(|e| e.to_string())?;
+    let mut seen_albums: HashSet<(String, String)> = HashSet::new();
+    let mut groups = Vec::new();
+    for (id, file_path, album) in pending {
+        let album = album.filter(|a| !a.trim().is_empty() && a != NO_ALBUM);
+        let Some(album) = album else {
+            groups.push((false, vec![(id, file_path)]));
+            continue;
+        };
+        let dir = parent_dir(&file_path);
+        if !seen_albums.insert((album.clone(), dir.clone())) {
+            continue;
+        }
+        // 이미 분석된 곡도 앨범 라우드니스를 다시 계산하기 위해 함께 분석
+        let members: Vec<(i64, String)> = album_stmt
+            .query_map([&album], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(|e| e.to_string())?
+            .filter_map(|r| r.ok())
+            .filter(|(_, path): &(i64, String)| parent_dir(path) == dir)
+            .collect();
+        groups.push((true, members));
+    }
+    Ok(groups)
+}
+
+fn update_scan_status(update: impl FnOnce(&mut LoudnessScanStatus)) {
+    if let Ok(mut guard) = LOUDNESS_SCAN_STATUS.lock() {
+        if let Some(status) = guard.as_mut() {
+            update(status);
+        }
+    }
+}
+
+fn run_loudness_scan(groups: Vec<ScanGroup>, write_tags: bool) {
+    let conn = match get_connection() {
+        Ok(conn) => conn,
+        Err(e) => {
+            eprintln!("Loudness scan: failed to open database: {}", e);
+            return;
+        }
+    };
+
+    'groups: for (is_album, members) in groups {
+        let mut results: Vec<(i64, String, TrackLoudness)> = Vec::new();
+        for (song_id, file_path) in members {
+            if LOUDNESS_SCAN_CANCEL.load(Ordering::Relaxed) {
+                break 'groups;
+            }
+            update_scan_status(|status| status.current_song_id = Some(song_id));
+            if !Path::new(&song_source_path(&file_path)).exists() {
+                update_scan_status(|status| {
+                    status.failed += 1;
+                    status.remaining = status.remaining.saturating_sub(1);
+                });
+                continue;
+            }
+            match analyze_file(&file_path) {
+                Ok(track) => results.push((song_id, file_path, track)),
+                Err(e) => {
+                    eprintln!("Loudness scan failed for {}: {}", file_path, e);
+                    update_scan_status(|status| {
+                        status.failed += 1;
+                        status.remaining = status.remaining.saturating_sub(1);
+                    });
+                }
+            }
+        }
+        if LOUDNESS_SCAN_CANCEL.load(Ordering::Relaxed) {
+            break;
+        }
+
+        // 앨범 라우드니스: 앨범 내 모든 곡의 블록을 합쳐서 게이팅
+        let (album_lufs, album_peak) = if is_album && !results.is_empty() {
+            let blocks: Vec<f64> = results.iter().flat_map(|(_, _, t)| t.block_energies.iter().copied()).collect();
+            let lufs = integrated_loudness(&blocks).unwrap_or(REPLAYGAIN_REFERENCE_LUFS);
+            let peak = results.iter().map(|(_, _, t)| t.peak).fold(0.0, f64::max);
+            (Some(lufs), Some(peak))
+        } else {
+            (None, None)
+        };
+
+        for (song_id, file_path, track) in results {
+            let saved = conn.execute(
+                "UPDATE songs SET loudness_track_lufs = ?1, loudness_track_peak = ?2,
+                 loudness_album_lufs = ?3, loudness_album_peak = ?4 WHERE id = ?5",
+                params![track.lufs, track.peak, album_lufs, album_peak, song_id],
+            );
+            if let Err(e) = saved {
+                eprintln!("Failed to save loudness for {}: {}", file_path, e);
+            }
+            if write_tags {
+                let info = ReplayGainInfo {
+                    track_lufs: track.lufs,
+                    track_peak: track.peak,
+                    album_lufs,
+                    album_peak,
+                };
+                if let Err(e) = write_replaygain_tags(&file_path, &info) {
+                    eprintln!("Failed to write ReplayGain tags for {}: {}", file_path, e);
+                }
+            }
+            update_scan_status(|status| {
+                status.analyzed += 1;
+                status.remaining = status.remaining.saturating_sub(1);
+            });
+        }
+    }
+
+    update_scan_status(|status| {
+        status.running = false;
+        status.current_song_id = None;
+    });
+}
+
+// 라우드니스 분석이 안 된 곡을 백그라운드에서 분석 (write_tags 생략 시 설정값 사용)
+#[tauri::command]
+pub async fn start_loudness_scan(write_tags: Option<bool>) -> Result<LoudnessScanStatus, String> {
+    let mut status_guard = LOUDNESS_SCAN_STATUS.lock().map_err(|e| format!("Lock error: {}", e))?;
+    if let Some(status) = status_guard.as_ref() {
+        if status.running {
+            return Ok(status.clone());
+        }
+    }
+
+    let conn = get_connection()?;
+    let groups = collect_scan_groups(&conn)?;
+    let write_tags = write_tags.unwrap_or_else(|| load_replaygain_settings().write_tags);
+
+    let status = LoudnessScanStatus {
+        running: !groups.is_empty(),
+        current_song_id: None,
+        analyzed: 0,
+        failed: 0,
+        remaining: groups.iter().map(|(_, members)| members.len()).sum(),
+    };
+    *status_guard = Some(status.clone());
+    drop(status_guard);
+
+    if !groups.is_empty() {
+        LOUDNESS_SCAN_CANCEL.store(false, Ordering::Relaxed);
+        thread::spawn(move || run_loudness_scan(groups, write_tags));
+    }
+    Ok(status)
+}
+
+#[tauri::command]
+pub async fn get_loudness_scan_status() -> Result<LoudnessScanStatus, String> {
+    let status_guard = LOUDNESS_SCAN_STATUS.lock().map_err(|e| format!("Lock error: {}", e))?;
+    Ok(status_guard.clone().unwrap_or_default())
+}
+
+#[tauri::command]
+pub async fn cancel_loudness_scan() -> Result<(), String> {
+    LOUDNESS_SCAN_CANCEL.store(true, Ordering::Relaxed);
+    Ok(())
+}
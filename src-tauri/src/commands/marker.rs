@@ -0,0 +1,97 @@
+use rusqlite::params;
+use serde::Serialize;
+
+use crate::database::get_connection;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SongMarker {
+    pub id: i64,
+    pub song_id: i64,
+    pub name: String,
+    pub position: f64, // 초
+    pub created_at: String,
+}
+
+impl SongMarker {
+    fn from_row(row: &rusqlite::Row) -> rusqlite::Result<Self> {
+        Ok(SongMarker {
+            id: row.get(0)?,
+            song_id: row.get(1)?,
+            name: row.get(2)?,
+            position: row.get(3)?,
+            created_at: row.get(4)?,
+        })
+    }
+}
+
+fn get_marker_by_id(conn: &rusqlite::Connection, id: i64) -> Result<SongMarker, String> {
+    conn.query_row(
+        "SELECT id, song_id, name, position, created_at FROM song_markers WHERE id = ?1",
+        [id],
+        SongMarker::from_row,
+    )
+    .map_err(|e| format!("Marker not found: {}", e))
+}
+
+fn validate_position(position: f64) -> Result<f64, String> {
+    if !position.is_finite() || position < 0.0 {
+        return Err(format!("Invalid marker position: {}", position));
+    }
+    Ok(position)
+}
+
+// 곡의 마커 목록 (시간순)
+#[tauri::command]
+pub async fn get_song_markers(song_id: i64) -> Result<Vec<SongMarker>, String> {
+    let conn = get_connection().map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, song_id, name, position, created_at
+             FROM song_markers
+             WHERE song_id = ?1
+             ORDER BY position ASC, id ASC",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([song_id], SongMarker::from_row)
+        .map_err(|e| e.to_string())?;
+
+    let mut markers = Vec::new();
+    for marker in rows {
+        markers.push(marker.map_err(|e| e.to_string())?);
+    }
+    Ok(markers)
+}
+
+#[tauri::command]
+pub async fn add_song_marker(song_id: i64, name: String, position: f64) -> Result<SongMarker, String> {
+    let position = validate_position(position)?;
+    let conn = get_connection().map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT INTO song_markers (song_id, name, position) VALUES (?1, ?2, ?3)",
+        params![song_id, name.trim(), position],
+    )
+    .map_err(|e| e.to_string())?;
+    get_marker_by_id(&conn, conn.last_insert_rowid())
+}
+
+#[tauri::command]
+pub async fn update_song_marker(id: i64, name: String, position: f64) -> Result<SongMarker, String> {
+    let position = validate_position(position)?;
+    let conn = get_connection().map_err(|e| e.to_string())?;
+    conn.execute(
+        "UPDATE song_markers SET name = ?1, position = ?2 WHERE id = ?3",
+        params![name.trim(), position, id],
+    )
+    .map_err(|e| e.to_string())?;
+    get_marker_by_id(&conn, id)
+}
+
+#[tauri::command]
+pub async fn delete_song_marker(id: i64) -> Result<(), String> {
+    let conn = get_connection().map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM song_markers WHERE id = ?1", [id])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
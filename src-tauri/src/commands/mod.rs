@@ -4,6 +4,13 @@ pub mod song;
 pub mod player;
 pub mod settings;
 pub mod dashboard;
+pub mod loudness;
+pub mod equalizer;
+pub mod dynamics;
+pub mod marker;
+pub mod queue;
+pub mod chapter;
+pub mod silence;
 
 pub use folder::*;
 pub use playlist::*;
@@ -11,3 +18,10 @@ pub use song::*;
 pub use player::*;
 pub use settings::*;
 pub use dashboard::*;
+pub use loudness::*;
+pub use equalizer::*;
+pub use dynamics::*;
+pub use marker::*;
+pub use queue::*;
+pub use chapter::*;
+pub use silence::*;
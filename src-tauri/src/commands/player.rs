@@ -1,23 +1,34 @@
 ﻿use std::fs::File;
 use std::sync::{Arc, Mutex};
-use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU8, AtomicU32, AtomicU64, AtomicUsize, Ordering};
 use std::thread;
 use std::time::Duration;
 use std::sync::mpsc;
 use std::collections::VecDeque;
 use std::mem;
-use serde::Serialize;
-use symphonia::core::formats::FormatOptions;
-use symphonia::core::io::{MediaSourceStream, MediaSourceStreamOptions};
+use serde::{Deserialize, Serialize};
+use symphonia::core::formats::{FormatOptions, SeekMode};
+use symphonia::core::io::MediaSourceStream;
 use symphonia::core::meta::MetadataOptions;
 use symphonia::core::probe::Hint;
 use symphonia::core::codecs::CODEC_TYPE_NULL;
 use symphonia::default::get_probe;
 use symphonia::core::codecs::DecoderOptions;
 use symphonia::core::audio::Signal;
-use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
-use cpal::Sample;
-use rubato::{Resampler, SincFixedIn, SincInterpolationType, SincInterpolationParameters, WindowFunction};
+use cpal::traits::DeviceTrait;
+use crate::audio::{
+    CrossfadeCurve, CrossfadeSettings, Crossfader, DecodeSource, DecodeStatus, DynamicsProcessor, DynamicsSettings, EqSettings, Equalizer, FrameResampler, ReplayGainInfo,
+    ReplayGainMode, ReplayGainSettings, TailBuffer, TempoSettings, TimeStretcher, RS_IN_FRAMES,
+    find_bit_perfect_config, output_channel_matrix, pipeline_channel_layout, OutputChannelRole, balance_gains, ChannelOptions, AnalysisTap, SpectrumAnalyzer, SpectrumFrame,
+    AudioSink, AudioSinkKind, AudioSinkSettings, CpalSink, NullSink, RenderFn, WavFileSink, find_output_device, list_output_hosts, open_output_device, OutputDeviceSelection, OutputHostInfo,
+};
+use crate::commands::chapter::{load_resume_position, save_resume_position};
+use crate::commands::loudness::load_song_replaygain;
+use crate::commands::dynamics::load_dynamics_settings;
+use crate::commands::silence::apply_silence_trim;
+use crate::commands::equalizer::load_eq_settings;
+use crate::commands::queue::{get_queue, queue_advance_on_finish, queue_advance_on_switch, queue_upcoming_file, QueueSnapshot};
+use crate::commands::settings::{read_setting, write_setting};
 use crate::database::get_connection;
 use tauri::Manager;
 use serde_json;
@@ -25,8 +36,171 @@ use serde_json;
 #[derive(Clone, Serialize)]
 struct PlaybackFinishedPayload {
     file_path: String,
+    next_file_path: Option<String>, // gapless로 이어서 재생 중인 다음 곡 (없으면 재생 종료)
 }
 
+#[derive(Clone, Serialize)]
+struct PlaybackProgressPayload {
+    file_path: String,
+    position: f64,         // 현재 곡 재생 위치 (초)
+    duration: Option<f64>, // 현재 곡 길이 (초)
+    buffered: f64,         // 디코딩되어 출력을 기다리는 분량 (초)
+    paused: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaybackStatus {
+    pub is_playing: bool,
+    pub is_paused: bool,
+    pub file_path: Option<String>,
+    pub next_file_path: Option<String>,
+    pub position: f64,
+    pub duration: Option<f64>,
+    pub buffered: f64,
+    pub volume: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SleepTimerMode {
+    Minutes,    // 정해진 시간 후
+    EndOfTrack, // 지금 곡이 끝나면
+    EndOfQueue, // 이어서 재생할 곡이 없어지면 (대기열 / 등록된 다음 곡 끝)
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SleepTimerStatus {
+    pub mode: SleepTimerMode,
+    pub remaining_seconds: Option<f64>, // 멈출 때까지 남은 시간 (곡 끝 기준이면 현재 곡이 마지막일 때만)
+    pub fade_seconds: f64,
+}
+
+#[derive(Clone, Serialize)]
+struct SleepTimerFiredPayload {
+    mode: SleepTimerMode,
+}
+
+#[derive(Clone, Serialize)]
+struct OutputDeviceLostPayload {
+    device_name: String,              // 끊긴 장치
+    message: String,
+    recovered_device: Option<String>, // 다시 연 장치 (None이면 출력 장치가 없어 재생 중지)
+    position: f64,                    // 일시정지한 위치 (초)
+}
+
+// 앱 시작 시 복원한 재생 세션 (일시정지 상태로 열림)
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoredSession {
+    pub file_path: String,
+    pub position: f64,
+    pub queue: QueueSnapshot,
+}
+
+// 재생 에러 종류 (playback-error 이벤트)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlaybackErrorKind {
+    FileNotFound,      // 파일이 없거나 열 수 없음
+    UnsupportedFormat, // 포맷 인식 실패 / 오디오 트랙 없음
+    Decoder,           // 디코더를 만들 수 없거나 디코딩되는 패킷이 없음
+    OutputDevice,      // 출력 장치 / 출력 파일을 열 수 없음
+    Internal,
+}
+
+impl PlaybackErrorKind {
+    // DecodeSource::open 에러 메시지로 종류 구분
+    fn from_open_error(message: &str) -> Self {
+        if message.starts_with("Failed to open file") {
+            PlaybackErrorKind::FileNotFound
+        } else if message.starts_with("Failed to probe format") || message.starts_with("No valid audio track") {
+            PlaybackErrorKind::UnsupportedFormat
+        } else {
+            PlaybackErrorKind::Decoder
+        }
+    }
+
+    // 파일 자체의 문제 (다음 곡으로 넘어가도 되는 에러)
+    fn is_file_error(self) -> bool {
+        matches!(
+            self,
+            PlaybackErrorKind::FileNotFound | PlaybackErrorKind::UnsupportedFormat | PlaybackErrorKind::Decoder
+        )
+    }
+}
+
+#[derive(Clone, Serialize)]
+struct PlaybackErrorPayload {
+    kind: PlaybackErrorKind,
+    file_path: String,
+    message: String,
+    next_file_path: Option<String>, // 건너뛰고 이어서 재생하는 곡 (없으면 재생 중지)
+}
+
+// 재생 스레드 에러 (String 에러는 Internal로 변환)
+struct PlaybackError {
+    kind: PlaybackErrorKind,
+    message: String,
+}
+
+impl PlaybackError {
+    fn new(kind: PlaybackErrorKind, message: String) -> Self {
+        Self { kind, message }
+    }
+}
+
+impl From<String> for PlaybackError {
+    fn from(message: String) -> Self {
+        Self::new(PlaybackErrorKind::Internal, message)
+    }
+}
+
+#[derive(Clone, Serialize)]
+struct OutputDeviceFallbackPayload {
+    requested_host: Option<String>,
+    requested_device: Option<String>,
+    device_name: String, // 대신 사용한 기본 장치
+}
+
+// 현재 곡에서 반복 재생할 구간 (초)
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AbLoop {
+    pub start: f64,
+    pub end: f64,
+}
+
+// track_boundary 미설정 값
+const NO_TRACK_BOUNDARY: u64 = u64::MAX;
+// A-B 반복 구간 최소 길이 (초)
+const AB_LOOP_MIN_SECONDS: f64 = 0.1;
+// 디코딩 스레드가 출력보다 앞서 채널에 넣어 두는 최대 분량 (초)
+// 작을수록 EQ 등 디코딩 단계 설정 변경이 빨리 들림 (프리로딩 분량보다 커야 함)
+const DECODE_AHEAD_SECONDS: f64 = 1.5;
+// 스트림 시작 전 채워 둘 분량 (초)
+const PRELOAD_SECONDS: f64 = 1.0;
+// playback-progress 이벤트 최소 간격
+const PROGRESS_EVENT_INTERVAL: Duration = Duration::from_millis(250);
+// 재생 세션(곡 + 위치) DB 저장 간격
+const SESSION_CHECKPOINT_INTERVAL: Duration = Duration::from_secs(5);
+// 오디오 콜백이 이 시간 동안 한 번도 호출되지 않으면 스트림이 멈춘 것으로 보고 다시 엶
+const STREAM_STALL_TIMEOUT: Duration = Duration::from_secs(3);
+// 출력 장치가 끊긴 뒤 새 장치를 찾는 간격 / 최대 시도 횟수
+const DEVICE_RECOVERY_INTERVAL: Duration = Duration::from_millis(500);
+const DEVICE_RECOVERY_ATTEMPTS: usize = 20;
+// 슬립 타이머 확인 간격 / 페이드아웃 최대 길이 (초)
+const SLEEP_TIMER_TICK: Duration = Duration::from_millis(100);
+const SLEEP_FADE_MAX_SECONDS: f64 = 120.0;
+// 일시정지 / 재개 / 정지 / 곡 변경 시 gain 램프 길이 (ms)
+const TRANSITION_FADE_DEFAULT_MS: u32 = 20;
+const TRANSITION_FADE_MAX_MS: u32 = 500;
+// 램프가 끝나기를 기다리는 여유 시간 (콜백이 돌지 않는 경우 대비)
+const TRANSITION_FADE_WAIT_MARGIN: Duration = Duration::from_millis(100);
+// 스펙트럼 / 레벨 미터 갱신 간격 (약 30Hz)
+const SPECTRUM_INTERVAL: Duration = Duration::from_millis(33);
+
 // 실시간 오디오 콜백용 Atomic 상태 (Mutex 없이 접근 가능)
 struct RtState {
     should_stop: AtomicBool,
@@ -34,7 +208,40 @@ struct RtState {
     decoder_finished: AtomicBool,
     is_paused: AtomicBool,
     volume: AtomicU32, // f32를 u32 bits로 저장
-    samples_played: AtomicU64, // 프레임 수 (채널 수와 무관)
+    output_sample_rate: AtomicU32, // 출력 장치 샘플레이트 (samples_played -> 초 변환용)
+    bit_perfect: AtomicBool, // 원본 포맷 그대로 출력 중 (소프트웨어 볼륨 / ReplayGain 건너뜀)
+    balance: AtomicU32, // 좌우 밸런스 (f32 bits, -1.0 ~ 1.0)
+    mono_sum: AtomicBool, // L+R 합산 출력
+    swap_channels: AtomicBool, // 좌우 바꾸기
+    surround_upmix: AtomicBool, // 3채널 이상 장치에서 센터/서라운드 스피커에도 LR을 복제
+    device_lost: AtomicBool, // 출력 장치가 끊김 (스트림 에러 콜백이 설정, 감시 루프가 복구)
+    callbacks: AtomicU64, // 오디오 콜백 호출 횟수 (스트림 멈춤 감지용)
+    fade_gain: AtomicU32, // 슬립 타이머 페이드아웃 gain (f32 bits, 콜백이 부드럽게 따라감)
+    stopping: AtomicBool, // 정지 요청됨 (콜백이 페이드아웃한 뒤 should_stop 설정)
+    transition_fade_ms: AtomicU32, // 일시정지 / 재개 / 정지 램프 길이
+    ramp_silent: AtomicBool, // 램프가 0까지 내려가 무음 출력 중 (pause_audio / stop_audio가 기다림)
+    analysis: AnalysisTap, // 콜백이 출력한 샘플 (스펙트럼 분석 스레드가 읽음)
+    replaygain_mode: AtomicU8, // ReplayGainMode (디코딩 스레드가 곡마다 gain 계산)
+    replaygain_preamp: AtomicU32, // 프리앰프 dB (f32 bits)
+    samples_played: AtomicU64, // 재생 위치 (원본 시간 기준 프레임 수 = 초 * 출력 샘플레이트, 채널 수와 무관)
+    listened_frames: AtomicU64, // 실제로 소리를 낸 누적 프레임 수 (seek로 버린 샘플 제외, 청취 시간 기록용)
+    frames_consumed: AtomicU64, // 스트림 시작 후 채널에서 꺼내 출력한 누적 프레임 수 (seek/곡 전환과 무관)
+    frames_sent: AtomicU64, // 디코딩 스레드가 채널로 보낸 누적 프레임 수 (frames_consumed와의 차이 = 출력 대기 분량)
+    track_boundary: AtomicU64, // gapless 다음 곡이 시작되는 누적 프레임 위치 (NO_TRACK_BOUNDARY면 없음)
+    next_track_offset: AtomicU64, // gapless 다음 곡의 시작 위치 (출력 프레임, 앞부분 무음을 건너뛰었으면 0보다 큼)
+    track_switched: AtomicBool, // 다음 곡 첫 샘플이 출력됨 (감시 루프에서 이벤트 처리)
+    seek_pending: AtomicBool, // seek 요청됨 (디코딩 스레드가 처리하면 false)
+    seek_target: AtomicU64, // seek 목표 위치 (초, f64 bits)
+    discard_until: AtomicU64, // seek 이전에 보낸 샘플 끝 위치 (누적 프레임, 콜백이 여기까지 버림)
+    eq_version: AtomicU64, // EQ 설정이 바뀔 때마다 증가 (디코딩 스레드가 PlayerState.eq를 다시 읽음)
+    dynamics_version: AtomicU64, // 컴프레서/리미터 설정이 바뀔 때마다 증가 (디코딩 스레드가 PlayerState.dynamics를 다시 읽음)
+    tempo_version: AtomicU64, // 속도/음정 설정이 바뀔 때마다 증가 (디코딩 스레드가 PlayerState.tempo를 다시 읽음)
+    speed: AtomicU64, // 지금 출력 중인 샘플의 재생 속도 (f64 bits, samples_played 증가량에 곱함)
+    next_speed: AtomicU64, // speed_boundary부터 적용할 재생 속도 (f64 bits)
+    speed_boundary: AtomicU64, // 새 속도로 처리된 첫 샘플의 누적 프레임 위치 (NO_TRACK_BOUNDARY면 없음)
+    loop_version: AtomicU64, // A-B 반복 구간이 바뀔 때마다 증가 (디코딩 스레드가 PlayerState.ab_loop를 다시 읽음)
+    loop_start: AtomicU64, // A-B 반복 시작 위치 (samples_played 단위, 디코딩 스레드가 설정)
+    loop_end: AtomicU64, // A-B 반복 끝 위치 (samples_played 단위, NO_TRACK_BOUNDARY면 반복 없음)
 }
 
 impl RtState {
@@ -45,7 +252,40 @@ impl RtState {
             decoder_finished: AtomicBool::new(false),
             is_paused: AtomicBool::new(false),
             volume: AtomicU32::new(volume.to_bits()),
+            output_sample_rate: AtomicU32::new(0),
+            bit_perfect: AtomicBool::new(false),
+            balance: AtomicU32::new(0.0f32.to_bits()),
+            mono_sum: AtomicBool::new(false),
+            swap_channels: AtomicBool::new(false),
+            surround_upmix: AtomicBool::new(false),
+            device_lost: AtomicBool::new(false),
+            callbacks: AtomicU64::new(0),
+            fade_gain: AtomicU32::new(1.0f32.to_bits()),
+            stopping: AtomicBool::new(false),
+            transition_fade_ms: AtomicU32::new(TRANSITION_FADE_DEFAULT_MS),
+            ramp_silent: AtomicBool::new(true),
+            analysis: AnalysisTap::new(),
+            replaygain_mode: AtomicU8::new(ReplayGainMode::Off.to_u8()),
+            replaygain_preamp: AtomicU32::new(0.0f32.to_bits()),
             samples_played: AtomicU64::new(0),
+            listened_frames: AtomicU64::new(0),
+            frames_consumed: AtomicU64::new(0),
+            frames_sent: AtomicU64::new(0),
+            track_boundary: AtomicU64::new(NO_TRACK_BOUNDARY),
+            next_track_offset: AtomicU64::new(0),
+            track_switched: AtomicBool::new(false),
+            seek_pending: AtomicBool::new(false),
+            seek_target: AtomicU64::new(0.0f64.to_bits()),
+            discard_until: AtomicU64::new(0),
+            eq_version: AtomicU64::new(0),
+            dynamics_version: AtomicU64::new(0),
+            tempo_version: AtomicU64::new(0),
+            speed: AtomicU64::new(1.0f64.to_bits()),
+            next_speed: AtomicU64::new(1.0f64.to_bits()),
+            speed_boundary: AtomicU64::new(NO_TRACK_BOUNDARY),
+            loop_version: AtomicU64::new(0),
+            loop_start: AtomicU64::new(0),
+            loop_end: AtomicU64::new(NO_TRACK_BOUNDARY),
         }
     }
     
@@ -56,11 +296,63 @@ impl RtState {
     fn set_volume(&self, vol: f32) {
         self.volume.store(vol.to_bits(), Ordering::Relaxed);
     }
+
+    // 현재 곡 재생 위치 (초)
+    fn position_seconds(&self) -> f64 {
+        match self.output_sample_rate.load(Ordering::Relaxed) {
+            0 => 0.0,
+            rate => self.samples_played.load(Ordering::Relaxed) as f64 / rate as f64,
+        }
+    }
+
+    // 디코딩되어 출력을 기다리는 분량 (초, 출력 시간 기준)
+    fn buffered_seconds(&self) -> f64 {
+        let queued = self.frames_sent.load(Ordering::Relaxed).saturating_sub(self.frames_consumed.load(Ordering::Relaxed));
+        match self.output_sample_rate.load(Ordering::Relaxed) {
+            0 => 0.0,
+            rate => queued as f64 / rate as f64,
+        }
+    }
+
+    fn request_seek(&self, seconds: f64) {
+        self.seek_target.store(seconds.max(0.0).to_bits(), Ordering::Relaxed);
+        self.seek_pending.store(true, Ordering::Release);
+    }
+
+    fn set_channel_options(&self, options: &ChannelOptions) {
+        self.balance.store(options.balance.to_bits(), Ordering::Relaxed);
+        self.mono_sum.store(options.mono, Ordering::Relaxed);
+        self.swap_channels.store(options.swap_channels, Ordering::Relaxed);
+    }
+
+    fn set_replaygain(&self, settings: &ReplayGainSettings) {
+        self.replaygain_mode.store(settings.mode.to_u8(), Ordering::Relaxed);
+        self.replaygain_preamp.store((settings.preamp_db as f32).to_bits(), Ordering::Relaxed);
+    }
+
+    // 곡의 라우드니스 정보로 현재 모드/프리앰프에 맞는 선형 gain 계산 (분석 전 곡은 1.0)
+    fn replaygain_gain(&self, info: Option<&ReplayGainInfo>) -> f32 {
+        if self.bit_perfect.load(Ordering::Relaxed) {
+            return 1.0;
+        }
+        let mode = ReplayGainMode::from_u8(self.replaygain_mode.load(Ordering::Relaxed));
+        let preamp_db = f32::from_bits(self.replaygain_preamp.load(Ordering::Relaxed)) as f64;
+        info.map(|info| info.linear_gain(mode, preamp_db)).unwrap_or(1.0)
+    }
 }
 
 // ✅ RT 콜백에서 사용하는 전역 디버그 상태 (모듈 스코프로 명확히)
 static DISCONNECT_LOGGED: AtomicBool = AtomicBool::new(false);
 static VOLUME_LOG_COUNT: AtomicU32 = AtomicU32::new(0);
+// 비주얼라이저용 스펙트럼 분석 켜짐 여부 / 마지막 분석 결과
+static SPECTRUM_FEED_ENABLED: AtomicBool = AtomicBool::new(false);
+static SPECTRUM_SNAPSHOT: Mutex<Option<SpectrumFrame>> = Mutex::new(None);
+// 저장된 출력 장치가 없어서 기본 장치로 대체했다는 알림을 이미 보냈는지 (장치 변경 시 리셋)
+static OUTPUT_FALLBACK_NOTIFIED: AtomicBool = AtomicBool::new(false);
+
+// 재생할 수 없는 파일을 연속으로 건너뛴 횟수 (재생이 시작되면 리셋, 전부 재생 불가인 반복 대기열에서 무한 반복 방지)
+static UNPLAYABLE_SKIPS: AtomicUsize = AtomicUsize::new(0);
+const MAX_UNPLAYABLE_SKIPS: usize = 20;
 
 // 플레이어 상태 (비실시간 접근용)
 struct PlayerState {
@@ -72,6 +364,16 @@ struct PlayerState {
     samples_played: u64, // 실제로 오디오 스트림에서 출력된 프레임 수 (채널 수와 무관)
     rt_state: Option<Arc<RtState>>, // 실시간 상태 참조
     // ✅ should_stop은 rt_state.should_stop만 사용 (중복 제거)
+    next_file: Option<String>, // gapless로 이어서 재생할 다음 곡 (set_next_track으로 등록)
+    pending_gapless_file: Option<String>, // 디코딩은 넘어갔지만 아직 출력되지 않은 다음 곡
+    duration: Option<f64>, // 현재 곡 길이 (초)
+    pending_gapless_duration: Option<f64>, // pending_gapless_file의 길이 (곡 전환 시 duration으로 옮김)
+    crossfade: CrossfadeSettings, // 디코딩 스레드가 곡 전환 시 참조 (set_crossfade_settings로 즉시 반영)
+    eq: EqSettings, // 디코딩 스레드가 RtState.eq_version 변경 시 참조
+    dynamics: DynamicsSettings, // 디코딩 스레드가 RtState.dynamics_version 변경 시 참조
+    tempo: TempoSettings, // 디코딩 스레드가 RtState.tempo_version 변경 시 참조
+    ab_loop: Option<AbLoop>, // 디코딩 스레드가 RtState.loop_version 변경 시 참조 (곡이 바뀌면 해제)
+    decode_failures: Vec<PlaybackErrorPayload>, // 디코딩 스레드가 건너뛴 곡 (재생 스레드가 이벤트로 전송)
 }
 
 impl Default for PlayerState {
@@ -84,6 +386,16 @@ impl Default for PlayerState {
             seek_time: None,
             samples_played: 0,
             rt_state: None,
+            next_file: None,
+            pending_gapless_file: None,
+            duration: None,
+            pending_gapless_duration: None,
+            crossfade: CrossfadeSettings::default(),
+            eq: EqSettings::default(),
+            dynamics: DynamicsSettings::default(),
+            tempo: TempoSettings::default(),
+            ab_loop: None,
+            decode_failures: Vec::new(),
         }
     }
 }
@@ -92,8 +404,77 @@ impl Default for PlayerState {
 static PLAYER_STATE: Mutex<Option<Arc<Mutex<PlayerState>>>> = Mutex::new(None);
 // Stream은 Send가 아니므로 전역에 저장하지 않음
 
+// 슬립 타이머 (웹뷰가 숨겨지거나 느려져도 동작하도록 백엔드 스레드에서 처리)
+struct SleepTimer {
+    id: u64, // 타이머 스레드가 자신이 아직 유효한지 확인 (취소/교체 시 바뀜)
+    mode: SleepTimerMode,
+    deadline: Option<std::time::Instant>, // Minutes 모드만
+    fade_seconds: f64,
+}
+
+static SLEEP_TIMER: Mutex<Option<SleepTimer>> = Mutex::new(None);
+static SLEEP_TIMER_ID: AtomicU64 = AtomicU64::new(0);
+
+// 아직 마무리되지 않은 play_history 기록 (재생 스레드가 다시 시작돼도 같은 곡이면 이어서 누적)
+struct ActivePlay {
+    history_id: i64,
+    file_path: String,
+    listened_seconds: f64,
+}
+
+static ACTIVE_PLAY: Mutex<Option<ActivePlay>> = Mutex::new(None);
+
+// CUE 가상 트랙 (원본 파일의 [start, end) 구간, end가 None이면 파일 끝까지)
+pub(crate) struct CueRange {
+    pub source_path: String,
+    pub start: f64,
+    pub end: Option<f64>,
+}
+
+// songs.file_path가 CUE 가상 트랙이면 원본 파일과 구간 반환
+pub(crate) fn resolve_cue_track(file_path: &str) -> Option<CueRange> {
+    // 가상 트랙 경로는 디스크에 없으므로 실제 파일이면 DB 조회 생략
+    if std::path::Path::new(file_path).exists() {
+        return None;
+    }
+    let conn = get_connection().ok()?;
+    conn.query_row(
+        "SELECT cue_source_path, cue_start, cue_end FROM songs WHERE file_path = ?1 AND cue_source_path IS NOT NULL",
+        [file_path],
+        |row| {
+            Ok(CueRange {
+                source_path: row.get(0)?,
+                start: row.get::<_, Option<f64>>(1)?.unwrap_or(0.0),
+                end: row.get(2)?,
+            })
+        },
+    )
+    .ok()
+}
+
+// 곡의 실제 파일 경로 (CUE 가상 트랙이면 원본 파일)
+pub(crate) fn song_source_path(file_path: &str) -> String {
+    resolve_cue_track(file_path)
+        .map(|cue| cue.source_path)
+        .unwrap_or_else(|| file_path.to_string())
+}
+
+// 재생/분석용으로 곡 열기 (CUE 가상 트랙이면 원본 파일의 해당 구간만)
+pub(crate) fn open_song_source(file_path: &str) -> Result<DecodeSource, String> {
+    match resolve_cue_track(file_path) {
+        Some(cue) => {
+            let mut source = DecodeSource::open(&cue.source_path)?.with_range(cue.start, cue.end)?;
+            source.file_path = file_path.to_string();
+            Ok(source)
+        }
+        None => DecodeSource::open(file_path),
+    }
+}
+
 #[tauri::command]
 pub async fn get_audio_duration(file_path: String) -> Result<f64, String> {
+    let cue = resolve_cue_track(&file_path);
+    let file_path = cue.as_ref().map(|cue| cue.source_path.clone()).unwrap_or(file_path);
     let file = File::open(&file_path)
         .map_err(|e| format!("Failed to open file: {}", e))?;
     
@@ -128,7 +509,11 @@ pub async fn get_audio_duration(file_path: String) -> Result<f64, String> {
         return Err("Could not calculate duration: no frame count".to_string());
     };
     
-    Ok(duration)
+    // CUE 가상 트랙은 구간 길이
+    Ok(match cue {
+        Some(cue) => (cue.end.unwrap_or(duration).min(duration) - cue.start).max(0.0),
+        None => duration,
+    })
 }
 
 // 메타데이터 추출 함수
@@ -298,7 +683,8 @@ pub fn extract_metadata(file_path: &str) -> Result<(Option<String>, Option<Strin
 }
 
 #[tauri::command]
-pub async fn get_audio_format_info(file_path: String) -> Result<(String, Option<u32>, Option<u32>, Option<u8>), String> {
+pub async fn get_audio_format_info(file_path: String) -> Result<(String, Option<u32>, Option<u32>, Option<u8>, bool), String> {
+    let file_path = song_source_path(&file_path);
     let file = File::open(&file_path)
         .map_err(|e| format!("Failed to open file: {}", e))?;
     
@@ -356,7 +742,28 @@ pub async fn get_audio_format_info(file_path: String) -> Result<(String, Option<
         None
     };
     
-    Ok((format_name, sample_rate, bitrate, channels))
+    let bit_perfect = is_bit_perfect_output(&file_path, sample_rate, channels);
+    Ok((format_name, sample_rate, bitrate, channels, bit_perfect))
+}
+
+// 이 파일이 지금 비트퍼펙트로 출력되고 있는지 (재생 중인 곡 + 스트림이 원본 샘플레이트로 열림)
+fn is_bit_perfect_output(file_path: &str, sample_rate: Option<u32>, channels: Option<u8>) -> bool {
+    let Ok(state_guard) = PLAYER_STATE.lock() else {
+        return false;
+    };
+    let Some(state) = state_guard.as_ref() else {
+        return false;
+    };
+    let Ok(player_state) = state.lock() else {
+        return false;
+    };
+    let Some(rt_state) = player_state.rt_state.as_ref() else {
+        return false;
+    };
+    player_state.current_file.as_deref() == Some(file_path)
+        && rt_state.bit_perfect.load(Ordering::Relaxed)
+        && sample_rate == Some(rt_state.output_sample_rate.load(Ordering::Relaxed))
+        && channels.map(|c| c <= 2).unwrap_or(false)
 }
 
 #[tauri::command]
@@ -381,6 +788,11 @@ pub async fn extract_waveform(file_path: String, samples: usize) -> Result<Vec<f
         }
     }
     
+    // CUE 가상 트랙은 원본 파일의 해당 구간만
+    if resolve_cue_track(&file_path).is_some() {
+        return extract_cue_waveform(&file_path, samples);
+    }
+    
     // 오디오 파일 열기
     let file = File::open(&file_path)
         .map_err(|e| format!("Failed to open file: {}", e))?;
@@ -513,17 +925,16 @@ pub async fn extract_waveform(file_path: String, samples: usize) -> Result<Vec<f
         return Err("No audio data found".to_string());
     }
     
+    Ok(finish_waveform(&waveform_chunks))
+}
+
+// 청크별 (sum_squares, count) -> 정규화된 RMS 웨이폼
+fn finish_waveform(waveform_chunks: &[(f32, usize)]) -> Vec<f32> {
     // 누적된 데이터를 기반으로 RMS 계산
-    let mut waveform = Vec::with_capacity(samples);
-    for i in 0..samples {
-        let (sum_squares, count) = waveform_chunks[i];
-        if count > 0 {
-            let rms = (sum_squares / count as f32).sqrt();
-            waveform.push(rms);
-        } else {
-            waveform.push(0.0);
-        }
-    }
+    let mut waveform: Vec<f32> = waveform_chunks
+        .iter()
+        .map(|&(sum_squares, count)| if count > 0 { (sum_squares / count as f32).sqrt() } else { 0.0 })
+        .collect();
     
     // 정규화 (0.0 ~ 1.0)
     let max = waveform.iter().copied().fold(0.0f32, f32::max);
@@ -533,15 +944,75 @@ pub async fn extract_waveform(file_path: String, samples: usize) -> Result<Vec<f
         }
     }
     
-    Ok(waveform)
+    waveform
+}
+
+// CUE 가상 트랙 웨이폼 (원본 파일의 해당 구간만 디코딩)
+fn extract_cue_waveform(file_path: &str, samples: usize) -> Result<Vec<f32>, String> {
+    let mut source = open_song_source(file_path)?;
+    let total_frames = source.duration.map(|d| (d * source.sample_rate as f64) as usize).unwrap_or(0);
+    let chunk_size = if total_frames > 0 {
+        (total_frames as f64 / samples as f64).ceil().max(1.0) as usize
+    } else {
+        1024
+    };
+    
+    let mut waveform_chunks: Vec<(f32, usize)> = vec![(0.0, 0); samples];
+    let mut sample_counter = 0usize;
+    let mut pending: [VecDeque<f32>; 2] = Default::default();
+    loop {
+        match source.decode_into(&mut pending) {
+            DecodeStatus::Decoded => {
+                let [pending_l, pending_r] = &mut pending;
+                for (l, r) in pending_l.drain(..).zip(pending_r.drain(..)) {
+                    let mono_sample = (l + r) * 0.5;
+                    let chunk_idx = (sample_counter / chunk_size).min(samples - 1);
+                    waveform_chunks[chunk_idx].0 += mono_sample * mono_sample;
+                    waveform_chunks[chunk_idx].1 += 1;
+                    sample_counter += 1;
+                }
+            }
+            DecodeStatus::Skipped => {}
+            DecodeStatus::Eof => break,
+        }
+    }
+    
+    if sample_counter == 0 {
+        return Err("No audio data found".to_string());
+    }
+    
+    Ok(finish_waveform(&waveform_chunks))
 }
 
 #[tauri::command]
 pub async fn play_audio(app_handle: tauri::AppHandle, file_path: String, volume: f32, seek_time: Option<f64>) -> Result<(), String> {
-    // ✅ 기존 재생 중지 및 완전 종료 대기
-    stop_audio().await.ok();
+    start_playback(app_handle, file_path, volume, seek_time, true).await
+}
+
+// 재생 시작 (record_history가 false면 새 재생 기록을 만들지 않음: 세션 복원 / 일시정지 중 재시작은 실제로 재생을 재개할 때 기록)
+async fn start_playback(
+    app_handle: tauri::AppHandle,
+    file_path: String,
+    volume: f32,
+    seek_time: Option<f64>,
+    record_history: bool,
+) -> Result<(), String> {
+    // ✅ 기존 재생 중지 및 완전 종료 대기 (재생 기록은 아래에서 이어 쓰거나 새로 시작)
+    stop_playback().await.ok();
     
+    // 위치를 지정한 재생(seek / 재시작)만 같은 곡 기록을 이어서 사용 (이어듣기 위치로 시작하는 것은 새 재생)
+    let continue_record = seek_time.is_some() && is_active_play(&file_path);
+    
+    // 위치 기억 곡은 마지막으로 멈춘 위치부터 이어서 재생
+    let seek_time = seek_time.or_else(|| load_resume_position(&file_path));
+    
+    // 대기열의 현재 곡을 재생하는 경우 다음 곡을 gapless로 미리 등록
+    let next_file = queue_upcoming_file(&file_path);
     let rt_state = Arc::new(RtState::new(volume.max(0.0).min(1.0)));
+    rt_state.set_replaygain(&load_replaygain_settings());
+    rt_state.surround_upmix.store(load_surround_upmix_setting(), Ordering::Relaxed);
+    rt_state.set_channel_options(&load_channel_options());
+    rt_state.transition_fade_ms.store(load_transition_fade_ms(), Ordering::Relaxed);
     let state = Arc::new(Mutex::new(PlayerState {
         is_playing: true,
         is_paused: false,
@@ -550,139 +1021,234 @@ pub async fn play_audio(app_handle: tauri::AppHandle, file_path: String, volume:
         seek_time,
         samples_played: 0,
         rt_state: Some(rt_state.clone()),
+        next_file,
+        pending_gapless_file: None,
+        duration: None,
+        pending_gapless_duration: None,
+        crossfade: load_crossfade_settings(),
+        eq: load_eq_settings(),
+        dynamics: load_dynamics_settings(),
+        tempo: load_tempo_settings(),
+        ab_loop: None,
+        decode_failures: Vec::new(),
     }));
     
     *PLAYER_STATE.lock().map_err(|e| format!("Lock error: {}", e))? = Some(state.clone());
 
-    // 재생 시작 기록 (seek 기반 재생은 제외)
-    if seek_time.is_none() {
-        if let Ok(conn) = get_connection() {
-            let song_id = conn.query_row(
-                "SELECT id FROM songs WHERE file_path = ?1",
-                [&file_path],
-                |row| row.get::<_, i64>(0),
-            );
-            if let Ok(song_id) = song_id {
-                let _ = conn.execute(
-                    "INSERT INTO play_history (song_id, play_duration) VALUES (?1, NULL)",
-                    [song_id],
-                );
-            }
-        }
+    // 재생 시작 기록 (seek 기반 재생은 같은 곡 기록을 이어서 사용, 이어갈 기록이 없으면 새로 기록)
+    if record_history && !continue_record {
+        record_play_start(&file_path);
     }
     
     // ✅ 재생 스레드 시작
     let app_handle_clone = app_handle.clone();
     let _handle = thread::spawn(move || {
-        if let Err(e) = play_audio_thread(file_path, state, app_handle_clone) {
-            eprintln!("Audio playback error: {}", e);
+        if let Err(e) = play_audio_thread(file_path.clone(), state.clone(), app_handle_clone.clone()) {
+            eprintln!("Audio playback error ({:?}): {}", e.kind, e.message);
+            handle_playback_error(&app_handle_clone, &state, &file_path, e);
         }
     });
     
     Ok(())
 }
 
-fn play_audio_thread(file_path: String, state: Arc<Mutex<PlayerState>>, app_handle: tauri::AppHandle) -> Result<(), String> {
-    // rt_state 추출
-    let rt_state = {
-        let state_guard = state.lock().map_err(|e| format!("Lock error: {}", e))?;
-        state_guard.rt_state.clone().ok_or_else(|| "RtState not initialized".to_string())?
+// 재생 스레드가 시작하지 못함: 프론트엔드에 알리고, 파일 문제면 대기열의 다음 곡으로 넘어감
+fn handle_playback_error(app_handle: &tauri::AppHandle, state: &Arc<Mutex<PlayerState>>, file_path: &str, error: PlaybackError) {
+    let volume = match state.lock() {
+        Ok(mut state_guard) => {
+            state_guard.is_playing = false;
+            state_guard.is_paused = false;
+            state_guard.volume
+        }
+        Err(_) => 0.5,
     };
-    let file_path_for_event = file_path.clone();
-    let file = File::open(&file_path)
-        .map_err(|e| format!("Failed to open file: {}", e))?;
-    
-    // MediaSourceStream 버퍼 크기 증가 (프리로딩을 위해 충분히 큰 버퍼)
-    // VBR 파일과 큰 ID3 태그를 처리하기 위해 버퍼 크기 증가
-    let mut mss_opts = MediaSourceStreamOptions::default();
-    mss_opts.buffer_len = 8 * 1024 * 1024; // 8MB 버퍼 (VBR, 큰 ID3 태그 처리용)
-    
-    let mss = MediaSourceStream::new(Box::new(file), mss_opts);
-    let mut hint = Hint::new();
-    if let Some(extension) = std::path::Path::new(&file_path).extension() {
-        if let Some(ext_str) = extension.to_str() {
-            hint.with_extension(ext_str);
+    finalize_active_play(Some(file_path), false);
+
+    let skip = error.kind.is_file_error()
+        && load_skip_unplayable_setting()
+        && is_current_player_state(state)
+        && UNPLAYABLE_SKIPS.fetch_add(1, Ordering::Relaxed) < MAX_UNPLAYABLE_SKIPS;
+    let next_file = if skip { queue_advance_on_finish(app_handle, file_path) } else { None };
+    let _ = app_handle.emit_all(
+        "playback-error",
+        PlaybackErrorPayload {
+            kind: error.kind,
+            file_path: file_path.to_string(),
+            message: error.message,
+            next_file_path: next_file.clone(),
+        },
+    );
+    if let Some(next_file) = next_file {
+        let app_handle = app_handle.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = play_audio(app_handle, next_file, volume, None).await {
+                eprintln!("Failed to play next queue item: {}", e);
+            }
+        });
+    }
+}
+
+// play_history에 재생 시작 기록 (이전 곡 기록은 중간에 넘긴 것으로 마무리)
+fn record_play_start(file_path: &str) {
+    finalize_active_play(None, false);
+    if let Ok(conn) = get_connection() {
+        let song_id = conn.query_row(
+            "SELECT id FROM songs WHERE file_path = ?1",
+            [file_path],
+            |row| row.get::<_, i64>(0),
+        );
+        if let Ok(song_id) = song_id {
+            if conn.execute(
+                "INSERT INTO play_history (song_id, play_duration) VALUES (?1, NULL)",
+                [song_id],
+            ).is_ok() {
+                if let Ok(mut active) = ACTIVE_PLAY.lock() {
+                    *active = Some(ActivePlay {
+                        history_id: conn.last_insert_rowid(),
+                        file_path: file_path.to_string(),
+                        listened_seconds: 0.0,
+                    });
+                }
+            }
+        }
+    }
+}
+
+fn is_active_play(file_path: &str) -> bool {
+    ACTIVE_PLAY
+        .lock()
+        .map(|active| active.as_ref().is_some_and(|play| play.file_path == file_path))
+        .unwrap_or(false)
+}
+
+// 재생 스레드가 출력한 시간을 현재 기록에 누적
+fn add_listened_seconds(file_path: &str, seconds: f64) {
+    if seconds <= 0.0 {
+        return;
+    }
+    if let Ok(mut active) = ACTIVE_PLAY.lock() {
+        if let Some(play) = active.as_mut().filter(|play| play.file_path == file_path) {
+            play.listened_seconds += seconds;
+        }
+    }
+}
+
+// 현재 기록에 들은 시간과 완료 여부 저장 (file_path가 있으면 그 곡의 기록일 때만)
+fn finalize_active_play(file_path: Option<&str>, completed: bool) {
+    let play = match ACTIVE_PLAY.lock() {
+        Ok(mut active) => {
+            if file_path.is_some_and(|path| active.as_ref().is_some_and(|play| play.file_path != path)) {
+                return;
+            }
+            active.take()
+        }
+        Err(_) => return,
+    };
+    if let (Some(play), Ok(conn)) = (play, get_connection()) {
+        if let Err(e) = conn.execute(
+            "UPDATE play_history SET play_duration = ?1, completed = ?2 WHERE id = ?3",
+            rusqlite::params![play.listened_seconds, completed as i64, play.history_id],
+        ) {
+            eprintln!("Failed to finalize play history: {}", e);
+        }
+    }
+}
+
+// 설정에 저장된 출력 장치로 cpal sink를 엶 (비트퍼펙트 모드면 원본 포맷 설정 사용)
+fn open_cpal_sink(app_handle: &tauri::AppHandle, rt_state: &RtState, source: &DecodeSource) -> Result<CpalSink, String> {
+    // ✅ 설정에 저장된 출력 장치 (사라졌으면 기본 장치로 대체하고 프론트엔드에 알림)
+    let selection = load_output_device_selection();
+    let (device, fell_back) = open_output_device(&selection)?;
+    let device_name = device.name().unwrap_or_default();
+    if fell_back {
+        eprintln!("Output device {:?}/{:?} not found, falling back to default device: {}",
+            selection.host, selection.device, device_name);
+        if !OUTPUT_FALLBACK_NOTIFIED.swap(true, Ordering::Relaxed) {
+            let _ = app_handle.emit_all(
+                "output-device-fallback",
+                OutputDeviceFallbackPayload {
+                    requested_host: selection.host.clone(),
+                    requested_device: selection.device.clone(),
+                    device_name: device_name.clone(),
+                },
+            );
         }
     }
-    
-    // 메타데이터 옵션: 큰 ID3 태그 처리
-    let meta_opts: MetadataOptions = Default::default();
-    
-    // 포맷 옵션: VBR 파일과 ID3 태그 처리 강화
-    let mut fmt_opts = FormatOptions::default();
-    // gapless 재생 활성화 (ID3 태그와 오디오 스트림 구분 강화)
-    fmt_opts.enable_gapless = true;
-    // ⭐⭐⭐ 매우 중요: seek_index 비활성화 (특정 파일의 초반 EOF 문제 해결)
-    // VBR 파일에서 부정확한 seek index로 인한 조기 EOF 방지
-    fmt_opts.prebuild_seek_index = false;
-    
-    let probe = get_probe();
-    let mut probed = probe.format(&hint, mss, &fmt_opts, &meta_opts)
-        .map_err(|e| format!("Failed to probe format: {}", e))?;
-    
-    // track 정보를 먼저 추출 (borrow 충돌 방지)
-    // ✅ 오디오 트랙 선택 개선: sample_rate/channels 있는 트랙 + 가장 긴 트랙 우선
-    // 첫 번째 유효한 코덱만 찾으면 비오디오 트랙(비디오/앨범아트 등)을 선택할 수 있음
-    let track = probed.format.tracks()
-        .iter()
-        .filter(|t| t.codec_params.codec != CODEC_TYPE_NULL)
-        .filter(|t| t.codec_params.sample_rate.is_some()) // 샘플 레이트가 있는 트랙만
-        .filter(|t| t.codec_params.channels.is_some()) // 채널 정보가 있는 트랙만
-        .max_by_key(|t| t.codec_params.n_frames.unwrap_or(0)) // 가장 긴 트랙 우선
-        .ok_or_else(|| "No valid audio track found".to_string())?;
-    
-    // track에서 필요한 정보를 먼저 추출
-    let track_id = track.id;
-    let codec_params = track.codec_params.clone();
-    let source_sample_rate = codec_params.sample_rate.unwrap_or(44100);
-    
-    // 예상 duration 계산 (파일 끝 감지용) - VBR 파일의 경우 부정확할 수 있으므로 참고용으로만 사용
-    // ✅ expected_duration은 현재 미사용 (향후 필요 시 재추가 가능)
-    
-    // 디코더 옵션: 손상된 프레임 무시하고 계속 진행 (에러 복구 강화)
-    let mut decoder_opts = DecoderOptions::default();
-    decoder_opts.verify = false; // 프레임 검증 비활성화 (손상된 프레임도 처리)
-    
-    let mut decoder = symphonia::default::get_codecs()
-        .make(&codec_params, &decoder_opts)
-        .map_err(|e| format!("Failed to create decoder: {}", e))?;
-    
-    let host = cpal::default_host();
-    let device = host.default_output_device()
-        .ok_or_else(|| "No output device available".to_string())?;
     
     let default_config = device.default_output_config()
         .map_err(|e| format!("Failed to get default output config: {}", e))?;
+
+    // ✅ 비트퍼펙트 모드: 장치가 원본 샘플레이트/채널/포맷을 지원하면 그 설정으로 스트림을 엶
+    // (리샘플링, 소프트웨어 볼륨, ReplayGain 모두 건너뜀)
+    let bit_perfect_config = if load_bit_perfect_setting() {
+        let found = find_bit_perfect_config(&device, source.sample_rate, source.channels, source.bits_per_sample);
+        if found.is_none() {
+            eprintln!("Bit-perfect output not supported for {} Hz / {} ch / {:?} bit, using default config",
+                source.sample_rate, source.channels, source.bits_per_sample);
+        }
+        found
+    } else {
+        None
+    };
+    rt_state.bit_perfect.store(bit_perfect_config.is_some(), Ordering::Relaxed);
+    // ✅ CPAL 채널 수는 절대 변경하지 않음 (출력 장치 채널 수 = 진리)
+    let config = bit_perfect_config.unwrap_or(default_config);
+    eprintln!("Output device format: {:?}", config.sample_format());
+    Ok(CpalSink::new(device, config))
+}
+
+fn play_audio_thread(file_path: String, state: Arc<Mutex<PlayerState>>, app_handle: tauri::AppHandle) -> Result<(), PlaybackError> {
+    // rt_state 추출
+    let rt_state = {
+        let state_guard = state.lock().map_err(|e| format!("Lock error: {}", e))?;
+        state_guard.rt_state.clone().ok_or_else(|| "RtState not initialized".to_string())?
+    };
+    let mut file_path_for_event = file_path.clone();
+    let mut source = open_song_source(&file_path)
+        .map_err(|e| PlaybackError::new(PlaybackErrorKind::from_open_error(&e), e))?;
+    source.replaygain = load_song_replaygain(&file_path);
+    apply_silence_trim(&mut source);
+    let source_sample_rate = source.sample_rate;
+    if let Ok(mut state_guard) = state.lock() {
+        state_guard.duration = source.duration;
+    }
     
-    let target_sample_rate = default_config.sample_rate().0 as u32;
+    // ✅ 출력 sink 선택 (기본: cpal 사운드 카드, 설정에 따라 null / WAV 파일)
+    let sink_settings = load_audio_sink_settings();
+    let output_error = |e: String| PlaybackError::new(PlaybackErrorKind::OutputDevice, e);
+    let mut sink: Box<dyn AudioSink> = match sink_settings.kind {
+        AudioSinkKind::Cpal => Box::new(open_cpal_sink(&app_handle, &rt_state, &source).map_err(output_error)?),
+        AudioSinkKind::Null => Box::new(NullSink::new(sink_settings.sample_rate, 2)),
+        AudioSinkKind::Wav => {
+            let path = sink_settings
+                .wav_path
+                .as_deref()
+                .filter(|path| !path.is_empty())
+                .ok_or_else(|| output_error("WAV output path is not set".to_string()))?;
+            Box::new(WavFileSink::create(path, sink_settings.sample_rate, 2).map_err(output_error)?)
+        }
+    };
+    let device_name = sink.name();
+    source.gain = rt_state.replaygain_gain(source.replaygain.as_ref());
+    // 출력 장치가 원본 스피커 위치를 모두 갖고 있으면 멀티채널 그대로, 아니면 LR로 다운믹스
+    source.set_layout(pipeline_channel_layout(source.channel_layout, sink.channels()));
+    let layout = source.layout().to_vec();
     
-    // ✅ CPAL 채널 수는 절대 변경하지 않음 (출력 장치 채널 수 = 진리)
-    let config = default_config.config();
-    // config.channels 그대로 둠 (출력 장치 채널 수 유지)
+    let target_sample_rate = sink.sample_rate();
+    rt_state.output_sample_rate.store(target_sample_rate, Ordering::Relaxed);
     
     // 디버깅: 채널 수 확인
-    eprintln!("Output channels: {}, Sample rate: {}", config.channels, config.sample_rate.0);
+    eprintln!("Output: {}, channels: {}, Sample rate: {}, bit-perfect: {}, layout: {:?}",
+        device_name, sink.channels(), target_sample_rate, rt_state.bit_perfect.load(Ordering::Relaxed), layout);
     
     // ✅ Seek 처리: Seek = 재생 재시작 (참고 코드 패턴)
     // ❌ Seek 후 첫 패킷을 미리 읽지 않음 (디코딩 루프에서 자연스럽게 처리)
     // Seek 후 패킷을 미리 읽으면 format 상태가 불일치하여 EOF 루프에 빠질 수 있음
-    let seek_time = state.lock().unwrap().seek_time.unwrap_or(0.0);
-    let seek_seconds = seek_time as u64;
-    let seek_frac = seek_time - seek_seconds as f64;
-    
-    // Seek 수행
-    let seek_result = probed.format.seek(
-        symphonia::core::formats::SeekMode::Accurate,
-        symphonia::core::formats::SeekTo::Time {
-            track_id: Some(track_id),
-            time: symphonia::core::units::Time::new(seek_seconds, seek_frac),
-        }
-    );
+    // 위치 지정이 없으면 앞부분 무음을 건너뛴 위치부터
+    let seek_time = state.lock().unwrap().seek_time.unwrap_or(source.start_offset);
     
     // ✅ initial_packet_time 제거: _current_packet_time 미사용으로 불필요
-    if seek_result.is_ok() {
-        // Seek 성공 시 디코더 리셋 및 카운터 초기화
-        decoder.reset();
+    if source.seek(seek_time, SeekMode::Accurate).is_ok() {
         // Seek 시 samples_played를 반드시 초기화 (프레임 기준)
         let expected_frames = (seek_time * target_sample_rate as f64) as u64;
         rt_state.samples_played.store(expected_frames, Ordering::Relaxed);
@@ -695,15 +1261,7 @@ fn play_audio_thread(file_path: String, state: Arc<Mutex<PlayerState>>, app_hand
     } else {
         eprintln!("Seek to {:.2}s failed, attempting to seek to 0.0", seek_time);
         // Seek 실패 시 0초로 시도
-        let seek_result_0 = probed.format.seek(
-            symphonia::core::formats::SeekMode::Coarse,
-            symphonia::core::formats::SeekTo::Time {
-                track_id: Some(track_id),
-                time: symphonia::core::units::Time::new(0, 0.0),
-            }
-        );
-        if seek_result_0.is_ok() {
-            decoder.reset();
+        if source.seek(0.0, SeekMode::Coarse).is_ok() {
             rt_state.samples_played.store(0, Ordering::Relaxed);
             {
                 let mut state_guard = state.lock().map_err(|e| format!("Lock error: {}", e))?;
@@ -716,402 +1274,137 @@ fn play_audio_thread(file_path: String, state: Arc<Mutex<PlayerState>>, app_hand
     }
     
     // 채널을 통한 오디오 데이터 전달 (bounded channel로 버퍼 크기 제한)
-    // ✅ Vec 개수 기준으로 현실적인 크기 설정 (각 Vec는 BATCH_MAX_SAMPLES로 제한됨)
-    // 256개 Vec = 약 256 * 16384 * 2 = 8M 샘플 = 약 90초 분량 (44.1kHz 기준)
+    // ✅ Vec 개수 기준으로 현실적인 크기 설정 (각 Vec는 BATCH_MAX_FRAMES 프레임으로 제한됨)
+    // 실제로 채널에 쌓이는 분량은 디코딩 스레드가 DECODE_AHEAD_SECONDS로 제한
     let buffer_size = 256; // Vec 메시지 개수 (BATCH_MAX로 각 Vec 크기 제한)
     let (tx, rx) = mpsc::sync_channel::<Vec<f32>>(buffer_size);
     
-    // 디코딩 스레드 (format과 decoder를 클로저로 이동)
+    // 디코딩 스레드 (source를 이동, 다음 곡은 스레드 안에서 직접 열어서 이어 붙임)
     let rt_state_clone = rt_state.clone(); // ✅ 디코딩 스레드에서 사용
-    let mut format_reader = probed.format;
-    let needs_resampling = source_sample_rate != target_sample_rate;
-    let resample_ratio = if needs_resampling {
-        target_sample_rate as f64 / source_sample_rate as f64
-    } else {
-        1.0
-    };
+    let state_clone = state.clone();
+    eprintln!("Source sample rate: {}, resampling: {}", source_sample_rate, source_sample_rate != target_sample_rate);
+    thread::spawn(move || {
+        decode_thread(source, state_clone, rt_state_clone, tx, target_sample_rate);
+    });
     
-    // 클로저로 이동할 변수들
-    let audio_track_id = track_id; // ✅ 오디오 트랙 ID (다른 트랙 패킷 스킵용)
-    // ✅ target_sample_rate는 build_stream에서 직접 사용하므로 클로저로 이동 불필요
-    // ✅ codec_params_clone, initial_packet_time_clone 제거: _current_packet_time 미사용으로 불필요
-    let needs_resampling_clone = needs_resampling; // EOF에서 리샘플러 flush를 위해 필요
+    // 출력 시작 (rt_state 전달, 장치가 사라지면 감시 루프가 복구)
+    let render = build_renderer(rx, rt_state.clone(), target_sample_rate, sink.channels(), layout);
+    let rt_state_for_error = rt_state.clone();
+    sink.start(render, Box::new(move || rt_state_for_error.device_lost.store(true, Ordering::Relaxed)))
+        .map_err(output_error)?;
+    UNPLAYABLE_SKIPS.store(0, Ordering::Relaxed);
     
-    // 여러 패킷을 배치로 처리하여 효율성 향상
-    thread::spawn(move || {
-        const BATCH_SIZE: usize = 1;
-        // ✅ MAX_PACKET_SCAN을 크게 늘림 (앨범아트/메타 트랙이 많은 파일 대응)
-        const MAX_PACKET_SCAN: usize = 10000;
-        // ✅ 리샘플러 고정 입력 프레임 크기
-        const RS_IN_FRAMES: usize = 8192;
-        // ✅ pending 최대 길이 상한 (메모리 폭증 방지)
-        const PENDING_MAX: usize = RS_IN_FRAMES * 20; // 약 20블록 분량
-        // ✅ batch_samples 최대 크기 (한 번에 보내는 샘플 수 제한)
-        const BATCH_MAX_SAMPLES: usize = 16384 * 2; // 16384 프레임 * 2채널 (LR)
-
-        let mut batch_samples: Vec<f32> = Vec::new();
-        let mut resampler: Option<SincFixedIn<f32>> = None;
-        // ✅ pending 버퍼: 리샘플러에 고정 크기 블록을 전달하기 위한 누적 버퍼
-        let mut pending_l: VecDeque<f32> = VecDeque::with_capacity(RS_IN_FRAMES * 3);
-        let mut pending_r: VecDeque<f32> = VecDeque::with_capacity(RS_IN_FRAMES * 3);
-        // ✅ _current_packet_time 제거: 현재 미사용 (향후 UI 이벤트 필요 시 재추가 가능)
-        let mut zero_frame_count = 0u32;
-
-        // 디버깅 카운터
-        let mut decoded_ok = 0u64;
-        let mut decoded_err = 0u64;
-        let mut sent_samples = 0u64;
-        let mut last_log = std::time::Instant::now();
-        let mut last_pending_warn = std::time::Instant::now(); // ✅ pending 경고 쿨다운
-        let mut last_flush = std::time::Instant::now(); // ✅ 시간 기반 flush
-        const FLUSH_INTERVAL: Duration = Duration::from_millis(30); // 30ms마다 flush
-        const MIN_FLUSH_SAMPLES: usize = 1024 * 2; // 최소 1024 프레임 * 2 (LR) - 메시지 폭발 방지
-        const FORCE_FLUSH_INTERVAL: Duration = Duration::from_millis(200); // 200ms 경과 시 MIN_FLUSH 무시하고 강제 전송 (방탄 백업)
-
-        'decode_loop: loop {
-            if rt_state_clone.should_stop.load(Ordering::Relaxed) {
-                break 'decode_loop;
+    // ✅ 스펙트럼 분석 스레드 (RT 콜백 밖에서 계산, 비주얼라이저가 켜져 있을 때만)
+    {
+        let rt_state = rt_state.clone();
+        let app_handle = app_handle.clone();
+        thread::spawn(move || run_spectrum_analysis(rt_state, app_handle, target_sample_rate));
+    }
+    
+    // sink를 유지해야 재생이 계속됩니다 (drop하면 재생이 중지됨)
+    // 재생이 끝날 때까지 대기
+    let mut last_progress = std::time::Instant::now() - PROGRESS_EVENT_INTERVAL;
+    let mut last_paused = false;
+    let mut last_checkpoint = std::time::Instant::now();
+    let mut last_callbacks = 0u64;
+    let mut last_callback_at = std::time::Instant::now();
+    let mut device_recovering = false;
+    let mut last_listened_frames = 0u64;
+    loop {
+        thread::sleep(Duration::from_millis(100));
+        // ✅ 실제로 출력한 시간을 재생 기록에 누적 (곡 전환 처리 전에 이전 곡 몫으로)
+        let listened_frames = rt_state.listened_frames.load(Ordering::Relaxed);
+        add_listened_seconds(&file_path_for_event, (listened_frames - last_listened_frames) as f64 / target_sample_rate as f64);
+        last_listened_frames = listened_frames;
+        // ✅ Atomic으로 빠른 체크
+        if rt_state.should_stop.load(Ordering::Relaxed) {
+            break;
+        }
+        // ✅ 출력 장치 분리 / 스트림 멈춤 감지: 일시정지 후 같은 위치에서 새 장치로 다시 엶
+        let callbacks = rt_state.callbacks.load(Ordering::Relaxed);
+        if callbacks != last_callbacks {
+            last_callbacks = callbacks;
+            last_callback_at = std::time::Instant::now();
+        }
+        if !device_recovering {
+            let reason = if rt_state.device_lost.load(Ordering::Relaxed) {
+                Some("Output device disconnected")
+            } else if last_callback_at.elapsed() >= STREAM_STALL_TIMEOUT {
+                Some("Output stream stalled")
+            } else {
+                None
+            };
+            if let Some(reason) = reason {
+                eprintln!("{} ({}), reopening output", reason, device_name);
+                device_recovering = true;
+                recover_output_device(app_handle.clone(), state.clone(), device_name.clone(), reason.to_string());
             }
-
-            // ✅ batch_samples.clear() 제거: 샘플을 누적하다가 임계치 넘으면 전송
-
-            for _ in 0..BATCH_SIZE {
-                // ✅ 오디오 트랙 패킷을 찾을 때까지 스캔 (시간 기준으로 변경)
-                let scan_start = std::time::Instant::now();
-                let scan_timeout = Duration::from_millis(500); // 500ms 동안 스캔
-                let mut attempts = 0usize;
-                let mut warned_timeout = false; // ✅ 경고는 루프당 1회만
-                let mut warned_max = false; // ✅ MAX_PACKET_SCAN 경고도 1회만
-                let packet = loop {
-                    attempts += 1;
-                    // 시간 기준 체크 (너무 많은 비오디오 패킷이 있어도 계속 시도)
-                    if !warned_timeout && scan_start.elapsed() > scan_timeout && attempts > 100 {
-                        eprintln!("Warning: audio packet scan timeout after {} attempts, continuing anyway", attempts);
-                        warned_timeout = true; // 한 번만 경고
-                    }
-                    if !warned_max && attempts > MAX_PACKET_SCAN {
-                        eprintln!("Warning: exceeded MAX_PACKET_SCAN={}, but continuing scan", MAX_PACKET_SCAN);
-                        warned_max = true; // 한 번만 경고
-                    }
-
-                    match format_reader.next_packet() {
-                        Ok(packet) => {
-                            if packet.track_id() == audio_track_id {
-                                break packet;
-                            }
-                            continue;
-                        }
-                        Err(symphonia::core::errors::Error::ResetRequired) => {
-                            decoder.reset();
-                            continue;
-                        }
-                        Err(symphonia::core::errors::Error::IoError(ref io_err))
-                            if io_err.kind() == std::io::ErrorKind::UnexpectedEof =>
-                        {
-                            eprintln!("Decoder reached EOF (file fully consumed)");
-
-                            // ✅ EOF 처리: pending 잔여 처리 + flush
-                            if needs_resampling_clone {
-                                // (A) pending이 남아있으면 0-padding으로 RS_IN_FRAMES 채워서 1회 처리
-                                if !pending_l.is_empty() || !pending_r.is_empty() {
-                                    let mut in_l = Vec::with_capacity(RS_IN_FRAMES);
-                                    let mut in_r = Vec::with_capacity(RS_IN_FRAMES);
-
-                                    while in_l.len() < RS_IN_FRAMES {
-                                        in_l.push(pending_l.pop_front().unwrap_or(0.0));
-                                        in_r.push(pending_r.pop_front().unwrap_or(0.0));
-                                    }
-
-                                    let input_channels = vec![in_l, in_r];
-                                    if let Some(ref mut rs) = resampler {
-                                        if let Ok(out) = rs.process(&input_channels, None) {
-                                            if !out.is_empty() && !out[0].is_empty() {
-                                                let out_frames = out[0].len();
-                                                let has_r = out.len() > 1;
-                                                for i in 0..out_frames {
-                                                    batch_samples.push(out[0][i]);
-                                                    batch_samples.push(if has_r { out[1][i] } else { out[0][i] });
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
-
-                                // (B) 그리고 flush
-                                if let Some(ref mut rs) = resampler {
-                                    let flush_flags = [true, true];
-                                    let empty_input: Vec<Vec<f32>> = vec![vec![], vec![]];
-                                    if let Ok(out) = rs.process(&empty_input, Some(&flush_flags)) {
-                                        if !out.is_empty() && !out[0].is_empty() {
-                                            let out_frames = out[0].len();
-                                            let has_r = out.len() > 1;
-                                            for i in 0..out_frames {
-                                                batch_samples.push(out[0][i]);
-                                                batch_samples.push(if has_r { out[1][i] } else { out[0][i] });
-                                            }
-                                        }
-                                    }
-                                }
-                            } else {
-                                // 리샘플링 없을 때도 pending 처리
-                                while let (Some(l), Some(r)) = (pending_l.pop_front(), pending_r.pop_front()) {
-                                    batch_samples.push(l);
-                                    batch_samples.push(r);
-                                }
-                            }
-
-                            // ✅ EOF에서도 mem::take로 통째 전송 (복사 비용 제거)
-                            if !batch_samples.is_empty() {
-                                let out = mem::take(&mut batch_samples);
-                                let _ = tx.send(out);
-                            }
-                            break 'decode_loop;
-                        }
-                        Err(_) => continue,
+        }
+        // ✅ gapless: 다음 곡의 첫 샘플이 실제로 출력되면 곡 전환 처리
+        if rt_state.track_switched.swap(false, Ordering::Relaxed) {
+            let next_file = {
+                let mut state_guard = state.lock().map_err(|e| format!("Lock error: {}", e))?;
+                let next_file = state_guard.pending_gapless_file.take();
+                if next_file.is_some() {
+                    state_guard.current_file = next_file.clone();
+                    state_guard.duration = state_guard.pending_gapless_duration.take();
+                }
+                next_file
+            };
+            if let Some(next_file) = next_file {
+                // 타이머 설정 전에 이미 이어 붙은 다음 곡이면 여기서 멈춤 (페이드아웃으로 이미 무음)
+                if sleep_timer_mode() == Some(SleepTimerMode::EndOfTrack) {
+                    fire_sleep_timer(&app_handle);
+                    tauri::async_runtime::spawn(stop_audio());
+                }
+                let _ = app_handle.emit_all(
+                    "playback-finished",
+                    PlaybackFinishedPayload {
+                        file_path: file_path_for_event.clone(),
+                        next_file_path: Some(next_file.clone()),
+                    },
+                );
+                finalize_active_play(Some(&file_path_for_event), true);
+                save_resume_position(&file_path_for_event, None);
+                record_play_start(&next_file);
+                save_session(&next_file, 0.0);
+                last_checkpoint = std::time::Instant::now();
+                // 대기열 위치를 옮기고 그다음 곡을 이어서 등록
+                if let Some(upcoming) = queue_advance_on_switch(&app_handle, &file_path_for_event, &next_file) {
+                    if let Ok(mut state_guard) = state.lock() {
+                        state_guard.next_file.get_or_insert(upcoming);
                     }
-                };
-
-                // ✅ packet_ts, time_base 사용 제거: _current_packet_time 미사용으로 불필요
-
-                match decoder.decode(&packet) {
-                    Ok(decoded) => {
-                        decoded_ok += 1;
-                        let audio_buf = decoded;
-                        let frames = audio_buf.frames();
-
-                        if frames == 0 {
-                            zero_frame_count += 1;
-                            if zero_frame_count > 100 {
-                                eprintln!("Too many consecutive zero-frame packets, resetting decoder");
-                                decoder.reset();
-                                zero_frame_count = 0;
-                            }
-                            continue;
-                        }
-                        zero_frame_count = 0;
-
-                        let channels_count = audio_buf.spec().channels.count() as usize;
-                        let spec = *audio_buf.spec();
-                        let cap = audio_buf.capacity();
-
-                        // ✅ cap 이상치 방어: 재생 경로에서는 완화 (정상 FLAC도 스킵하지 않도록)
-                        // waveform 추출과 달리 재생에서는 cap을 믿고 버퍼를 만들되, 메모리 폭탄만 방어
-                        let cap_limit = (source_sample_rate as usize * 30).max(8192); // 30초 분량 or 최소 8192
-                        if cap > cap_limit {
-                            eprintln!("Warning: packet capacity {} exceeds limit {}, using limit", cap, cap_limit);
-                            // 스킵하지 않고 cap_limit만큼만 사용
-                        }
-
-                        let safe_cap = cap.min(cap_limit);
-                        // ✅ safe_frames: frames가 safe_cap보다 클 수 있으므로 안전하게 제한
-                        let safe_frames = frames.min(safe_cap);
-                        let duration = symphonia::core::units::Duration::from(safe_cap as u64);
-                        let mut f32_buf = symphonia::core::audio::AudioBuffer::<f32>::new(duration, spec);
-                        audio_buf.convert(&mut f32_buf);
-
-                        // ✅ (C) 디코딩 후 샘플을 pending에 누적 (safe_frames만 사용)
-                        for fi in 0..safe_frames {
-                            let (l, r) = if channels_count > 2 {
-                                // 멀티채널 다운믹스 -> mono -> LR
-                                let mut sum = 0.0f32;
-                                for ch in 0..channels_count {
-                                    sum += f32_buf.chan(ch)[fi];
-                                }
-                                let mono = sum / channels_count as f32;
-                                (mono, mono)
-                            } else if channels_count == 1 {
-                                let v = f32_buf.chan(0)[fi];
-                                (v, v)
-                            } else {
-                                (f32_buf.chan(0)[fi], f32_buf.chan(1)[fi])
-                            };
-
-                            pending_l.push_back(l);
-                            pending_r.push_back(r);
-                        }
-                        
-                        // ✅ (A) pending 상한 체크는 패킷 처리 후 한 번만 (로그는 쿨다운으로 제한)
-                        if pending_l.len() > PENDING_MAX || pending_r.len() > PENDING_MAX {
-                            // 가장 오래된 샘플 drop
-                            while pending_l.len() > PENDING_MAX {
-                                pending_l.pop_front();
-                            }
-                            while pending_r.len() > PENDING_MAX {
-                                pending_r.pop_front();
-                            }
-                            // ✅ 로그는 1초에 1번만
-                            if last_pending_warn.elapsed() > Duration::from_secs(1) {
-                                eprintln!("Warning: pending buffer exceeded limit, dropping oldest samples");
-                                last_pending_warn = std::time::Instant::now();
-                            }
-                        }
-
-                        // ✅ 리샘플링 처리
-                        if needs_resampling {
-                            // (B) resampler 초기화: RS_IN_FRAMES로 고정
-                            if resampler.is_none() {
-                                // ✅ SincInterpolationType::Linear는 sinc 커널 계산 시 내부 보간 방식
-                                // (선형 보간 리샘플러가 아님 - sinc 기반 고품질 리샘플러)
-                                let params = SincInterpolationParameters {
-                                    sinc_len: 256,
-                                    f_cutoff: 0.95,
-                                    interpolation: SincInterpolationType::Linear, // sinc 내부 테이블 보간 방식
-                                    oversampling_factor: 256,
-                                    window: WindowFunction::BlackmanHarris2,
-                                };
-                                match SincFixedIn::<f32>::new(resample_ratio, 2.0, params, RS_IN_FRAMES, 2) {
-                                    Ok(r) => {
-                                        resampler = Some(r);
-                                    }
-                                    Err(e) => {
-                                        eprintln!("[resample_init_err] {:?}", e);
-                                        // 초기화 실패 시 리샘플링 없이 진행
-                                    }
-                                }
-                            }
-
-                            // ✅ (D) pending이 RS_IN_FRAMES 이상 모이면 "딱 RS_IN_FRAMES만" 뽑아서 process
-                            while pending_l.len() >= RS_IN_FRAMES && pending_r.len() >= RS_IN_FRAMES {
-                                // 고정 길이 블록 만들기
-                                let mut in_l = Vec::with_capacity(RS_IN_FRAMES);
-                                let mut in_r = Vec::with_capacity(RS_IN_FRAMES);
-                                for _ in 0..RS_IN_FRAMES {
-                                    in_l.push(pending_l.pop_front().unwrap());
-                                    in_r.push(pending_r.pop_front().unwrap());
-                                }
-
-                                let input_channels = vec![in_l, in_r];
-
-                                if let Some(ref mut rs) = resampler {
-                                    match rs.process(&input_channels, None) {
-                                        Ok(out) => {
-                                            // out[0] = L, out[1] = R (보통 2채널)
-                                            if !out.is_empty() && !out[0].is_empty() {
-                                                let out_frames = out[0].len();
-                                                let has_r = out.len() > 1;
-
-                                                // batch_samples는 LR 인터리브로
-                                                batch_samples.reserve(out_frames * 2);
-                                                for i in 0..out_frames {
-                                                    batch_samples.push(out[0][i]);
-                                                    batch_samples.push(if has_r { out[1][i] } else { out[0][i] });
-                                                }
-                                            }
-                                        }
-                                        Err(e) => {
-                                            // 리샘플러 에러: reset하고 계속
-                                            eprintln!("[resample_err] {:?} (reset resampler)", e);
-                                            resampler = None;
-                                        }
-                                    }
-                                }
-                            }
-                        } else {
-                            // ✅ (B) 리샘플링 없음: pending에서 batch_samples로
-                            // (batch_samples는 루프 끝에서 최대 크기로 잘라서 여러 번 전송)
-                            while let (Some(l), Some(r)) = (pending_l.pop_front(), pending_r.pop_front()) {
-                                batch_samples.push(l);
-                                batch_samples.push(r);
-                            }
-                        }
-                    }
-                    Err(symphonia::core::errors::Error::ResetRequired) => {
-                        decoded_err += 1;
-                        decoder.reset();
-                        continue;
-                    }
-                    Err(_) => {
-                        decoded_err += 1;
-                        continue;
-                    }
-                }
-            }
-
-            // ✅ (B) batch_samples가 BATCH_MAX_SAMPLES 이상이면 고정 크기로 전송 (성능 최적화: split_off + mem::take)
-            // ✅ while 루프로 여러 번 전송 가능 (큰 덩어리가 쌓였을 때 대응)
-            // ✅ >= 조건으로 딱 맞게 쌓인 경우도 즉시 전송 (예측 가능성 향상)
-            while batch_samples.len() >= BATCH_MAX_SAMPLES {
-                // split_off로 나머지 분리 후 mem::take로 정확히 BATCH_MAX_SAMPLES만 전송 (복사/할당 최소화)
-                let rest = batch_samples.split_off(BATCH_MAX_SAMPLES);
-                let out = mem::take(&mut batch_samples); // 정확히 BATCH_MAX_SAMPLES
-                let send_count = out.len();
-                if tx.send(out).is_ok() {
-                    sent_samples += send_count as u64;
-                } else {
-                    // 수신자가 없음: 재생 중지
-                    break 'decode_loop;
-                }
-                batch_samples = rest; // 나머지로 교체
-            }
-            
-            // ✅ 시간 기반 flush: BATCH_MAX에 못 미쳐도 일정 주기로 전송 (초반 무음/지연 방지)
-            // ✅ 최소 샘플 수 하한으로 메시지 폭발 방지
-            // ✅ FORCE_FLUSH_INTERVAL 백업 규칙: 200ms 경과 시 MIN_FLUSH 무시하고 강제 전송 (방탄)
-            let flush_elapsed = last_flush.elapsed();
-            let should_flush = if flush_elapsed >= FORCE_FLUSH_INTERVAL {
-                // 200ms 경과 시 MIN_FLUSH 무시하고 강제 전송 (이상 케이스 대비)
-                !batch_samples.is_empty()
-            } else if flush_elapsed >= FLUSH_INTERVAL {
-                // 30ms 경과 + MIN_FLUSH 이상일 때만 전송
-                batch_samples.len() >= MIN_FLUSH_SAMPLES
-            } else {
-                false
-            };
-            
-            if should_flush {
-                let out = mem::take(&mut batch_samples);
-                let send_count = out.len();
-                if tx.send(out).is_ok() {
-                    sent_samples += send_count as u64;
-                } else {
-                    // 수신자가 없음: 재생 중지
-                    break 'decode_loop;
                 }
-                last_flush = std::time::Instant::now();
-            }
-
-            if last_log.elapsed() > Duration::from_secs(2) {
-                eprintln!("[dbg] ok={}, err={}, sent={}, batch={}", decoded_ok, decoded_err, sent_samples, batch_samples.len());
-                last_log = std::time::Instant::now();
+                file_path_for_event = next_file;
+                last_progress = std::time::Instant::now() - PROGRESS_EVENT_INTERVAL;
             }
         }
-
-        // ✅ 루프 종료 시 남은 batch_samples 전송
-        if !batch_samples.is_empty() {
-            let out = mem::take(&mut batch_samples);
-            let send_count = out.len();
-            if tx.send(out).is_ok() {
-                sent_samples += send_count as u64;
-            }
+        // ✅ 디코딩 스레드가 건너뛴 곡 (디코딩되는 패킷이 하나도 없음) 알림
+        let decode_failures = state.lock().map(|mut state_guard| mem::take(&mut state_guard.decode_failures)).unwrap_or_default();
+        for failure in decode_failures {
+            let _ = app_handle.emit_all("playback-error", failure);
         }
-        
-        eprintln!("Decoding thread: exiting, closing channel");
-        eprintln!("Debug stats: decoded_ok={}, decoded_err={}, sent_samples={}", decoded_ok, decoded_err, sent_samples);
-        drop(tx);
-        if !rt_state_clone.should_stop.load(Ordering::Relaxed) {
-            rt_state_clone.decoder_finished.store(true, Ordering::Relaxed);
+        let paused = rt_state.is_paused.load(Ordering::Relaxed);
+        // ✅ 세션 체크포인트 (일정 간격, 일시정지/재개 시에는 즉시)
+        if paused != last_paused || last_checkpoint.elapsed() >= SESSION_CHECKPOINT_INTERVAL {
+            save_session(&file_path_for_event, rt_state.position_seconds());
+            last_checkpoint = std::time::Instant::now();
         }
-    });
-    
-    // cpal 스트림 생성 (rt_state 전달)
-    let stream = match default_config.sample_format() {
-        cpal::SampleFormat::F32 => build_stream::<f32>(&device, &config, rx, rt_state.clone(), target_sample_rate)?,
-        cpal::SampleFormat::I16 => build_stream::<i16>(&device, &config, rx, rt_state.clone(), target_sample_rate)?,
-        cpal::SampleFormat::I32 => build_stream::<i32>(&device, &config, rx, rt_state.clone(), target_sample_rate)?,
-        cpal::SampleFormat::I64 => build_stream::<i64>(&device, &config, rx, rt_state.clone(), target_sample_rate)?,
-        cpal::SampleFormat::U16 => build_stream::<u16>(&device, &config, rx, rt_state.clone(), target_sample_rate)?,
-        format => return Err(format!("Unsupported sample format: {:?}", format)),
-    };
-    
-    stream.play().map_err(|e| format!("Failed to play stream: {}", e))?;
-    
-    // Stream을 유지해야 재생이 계속됩니다 (drop하면 재생이 중지됨)
-    // 재생이 끝날 때까지 대기
-    loop {
-        thread::sleep(Duration::from_millis(100));
-        // ✅ Atomic으로 빠른 체크
-        if rt_state.should_stop.load(Ordering::Relaxed) {
-            break;
+        // ✅ 재생 위치 이벤트 (일정 간격, 일시정지/재개 시에는 즉시)
+        if paused != last_paused || last_progress.elapsed() >= PROGRESS_EVENT_INTERVAL {
+            let duration = state.lock().ok().and_then(|state_guard| state_guard.duration);
+            let _ = app_handle.emit_all(
+                "playback-progress",
+                PlaybackProgressPayload {
+                    file_path: file_path_for_event.clone(),
+                    position: rt_state.position_seconds(),
+                    duration,
+                    buffered: rt_state.buffered_seconds(),
+                    paused,
+                },
+            );
+            last_progress = std::time::Instant::now();
+            last_paused = paused;
         }
         if rt_state.finished.load(Ordering::Relaxed) {
             if let Ok(mut state_guard) = state.lock() {
@@ -1129,300 +1422,1621 @@ fn play_audio_thread(file_path: String, state: Arc<Mutex<PlayerState>>, app_hand
         drop(state_guard);
     }
     
-    // 재생이 끝나면 스트림 정리
-    drop(stream);
+    // 재생이 끝나면 출력 정리
+    drop(sink);
+    let listened_frames = rt_state.listened_frames.load(Ordering::Relaxed);
+    add_listened_seconds(&file_path_for_event, (listened_frames - last_listened_frames) as f64 / target_sample_rate as f64);
     
     if rt_state.finished.load(Ordering::Relaxed) && !rt_state.should_stop.load(Ordering::Relaxed) {
+        finalize_active_play(Some(&file_path_for_event), true);
+        save_resume_position(&file_path_for_event, None);
+        // ✅ gapless로 이어지지 않았으면 대기열의 다음 곡을 새로 재생 (다른 곡이 이미 재생 중이면 건드리지 않음)
+        let sleep_mode = sleep_timer_mode();
+        let next_file = if sleep_mode != Some(SleepTimerMode::EndOfTrack) && is_current_player_state(&state) {
+            // 끝까지 들은 곡은 다음 실행 때 처음부터 열리도록 위치를 되돌려 둠
+            save_session(&file_path_for_event, 0.0);
+            queue_advance_on_finish(&app_handle, &file_path_for_event)
+        } else {
+            None
+        };
         let _ = app_handle.emit_all(
             "playback-finished",
             PlaybackFinishedPayload {
                 file_path: file_path_for_event,
+                next_file_path: next_file.clone(),
             },
         );
+        if next_file.is_none() && matches!(sleep_mode, Some(SleepTimerMode::EndOfTrack | SleepTimerMode::EndOfQueue)) {
+            fire_sleep_timer(&app_handle);
+        }
+        if let Some(next_file) = next_file {
+            let volume = rt_state.get_volume();
+            let app_handle = app_handle.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = play_audio(app_handle, next_file, volume, None).await {
+                    eprintln!("Failed to play next queue item: {}", e);
+                }
+            });
+        }
     }
     
     Ok(())
 }
 
-fn build_stream<T>(
-    device: &cpal::Device,
-    config: &cpal::StreamConfig,
-    rx: mpsc::Receiver<Vec<f32>>,
-    rt_state: Arc<RtState>,
-    _target_sample_rate: u32,
-) -> Result<cpal::Stream, String>
-where
-    T: Sample + cpal::FromSample<f32> + cpal::SizedSample,
-{
-    // 버퍼 사용 (로컬 파일이므로 작은 버퍼로도 충분)
-    let sample_rate = config.sample_rate.0 as usize;
-    let channels = config.channels as usize;
-    // ✅ 내부는 항상 LR 2채널 고정이므로 sample_rate * 2로 설정
-    let mut sample_queue: VecDeque<f32> = VecDeque::with_capacity(sample_rate * 2);
-    // ✅ last_lr: 항상 2개 고정 (LR) - 모노 출력에서도 안전하게 접근
-    let mut last_lr = [0.0f32, 0.0f32]; // 마지막 LR 샘플 저장 (끊김 방지)
-    
-    // 재생 시작 전에 버퍼를 미리 채우기 (프리로딩)
-    // 최소 버퍼 크기: 약 2초 분량 (AIMP처럼 안정적인 재생을 위해)
-    // ✅ 프레임 기준으로 계산 (내부는 항상 LR 2채널)
-    let min_frames = sample_rate * 2; // 2초 분량의 프레임 수
-    let min_buffer_size = min_frames * 2; // LR 인터리브 (2채널)
-    let mut preload_attempts = 0;
-    const MAX_PRELOAD_ATTEMPTS: usize = 100; // 프리로딩을 위해 더 많은 시도 허용
-    let mut silent_preload_loops = 0; // 빈 샘플 연속 카운트
-    
-    while sample_queue.len() < min_buffer_size && preload_attempts < MAX_PRELOAD_ATTEMPTS {
-        match rx.try_recv() {
-            Ok(samples) => {
-                if samples.is_empty() {
-                    // ✅ 빈 샘플 처리: encoder delay / priming frames로 인한 정상적인 무음 패킷
-                    silent_preload_loops += 1;
-                    if silent_preload_loops > 50 {
-                        // 🔥 더 이상 기다리지 말고 그냥 재생 시작
-                        // 초반 무음 패킷이 계속 오는 경우 무한 대기 방지
-                        break;
-                    }
-                    continue; // 빈 샘플은 스킵하고 다음 시도
-                }
-                sample_queue.extend(samples);
-                silent_preload_loops = 0; // 정상 샘플 수신 시 리셋
-                preload_attempts = 0; // 성공하면 카운터 리셋
+// 스펙트럼 분석 스레드: 콜백이 출력한 최근 샘플로 약 30Hz마다 스펙트럼 / 피크 / RMS 계산 후 이벤트 전송
+// (출력이 멈추면 무음 프레임을 한 번 보내고 다시 출력될 때까지 쉼)
+fn run_spectrum_analysis(rt_state: Arc<RtState>, app_handle: tauri::AppHandle, sample_rate: u32) {
+    let mut analyzer = SpectrumAnalyzer::new(sample_rate);
+    let level_frames = (sample_rate as f64 * SPECTRUM_INTERVAL.as_secs_f64()) as usize;
+    let mut last_pos = rt_state.analysis.write_pos();
+    let mut silent_sent = false;
+    while !rt_state.should_stop.load(Ordering::Relaxed) && !rt_state.finished.load(Ordering::Relaxed) {
+        thread::sleep(SPECTRUM_INTERVAL);
+        if !SPECTRUM_FEED_ENABLED.load(Ordering::Relaxed) {
+            continue;
+        }
+        let pos = rt_state.analysis.write_pos();
+        let frame = if pos != last_pos {
+            last_pos = pos;
+            silent_sent = false;
+            analyzer.analyze(&rt_state.analysis, level_frames)
+        } else if !silent_sent {
+            silent_sent = true;
+            analyzer.silent_frame()
+        } else {
+            continue;
+        };
+        if let Ok(mut snapshot) = SPECTRUM_SNAPSHOT.lock() {
+            *snapshot = Some(frame.clone());
+        }
+        let _ = app_handle.emit_all("audio-spectrum", frame);
+    }
+}
+
+// 이 재생 상태가 아직 PLAYER_STATE의 현재 재생인지 (그사이 다른 곡을 재생했거나 중지했으면 false)
+fn is_current_player_state(state: &Arc<Mutex<PlayerState>>) -> bool {
+    PLAYER_STATE
+        .lock()
+        .ok()
+        .and_then(|guard| guard.as_ref().map(|current| Arc::ptr_eq(current, state)))
+        .unwrap_or(false)
+}
+
+// 출력 장치가 끊기면 일시정지하고, 쓸 수 있는 장치(설정한 장치 또는 새 기본 장치)가 생기면 같은 위치에서 다시 엶
+// 복구 후에도 일시정지 상태로 열어서 헤드폰을 뽑았을 때 스피커로 갑자기 소리가 나지 않게 함
+fn recover_output_device(app_handle: tauri::AppHandle, state: Arc<Mutex<PlayerState>>, device_name: String, message: String) {
+    let position = {
+        let Ok(mut state_guard) = state.lock() else {
+            return;
+        };
+        state_guard.is_paused = true;
+        match &state_guard.rt_state {
+            Some(rt_state) => {
+                rt_state.is_paused.store(true, Ordering::Relaxed);
+                rt_state.position_seconds()
             }
-            Err(mpsc::TryRecvError::Empty) => {
-                preload_attempts += 1;
-                thread::sleep(Duration::from_millis(5)); // 짧게 대기
+            None => return,
+        }
+    };
+
+    thread::spawn(move || {
+        let mut recovered_device = None;
+        for _ in 0..DEVICE_RECOVERY_ATTEMPTS {
+            // 장치가 빠진 직후에는 OS의 기본 장치가 아직 바뀌지 않았을 수 있으므로 먼저 대기
+            thread::sleep(DEVICE_RECOVERY_INTERVAL);
+            if !is_current_player_state(&state) {
+                return;
             }
-            Err(mpsc::TryRecvError::Disconnected) => {
-                // 채널이 닫혔으면 프리로딩 중단하고 빈 버퍼로 시작
-                // 디코딩 스레드가 곧 데이터를 보낼 것이므로 괜찮음
+            if let Ok((device, _)) = open_output_device(&load_output_device_selection()) {
+                recovered_device = Some(device.name().unwrap_or_default());
                 break;
             }
         }
+
+        let result = match recovered_device {
+            Some(_) => tauri::async_runtime::block_on(restart_playback(app_handle.clone(), position)),
+            None => tauri::async_runtime::block_on(stop_audio()),
+        };
+        if let Err(e) = result {
+            eprintln!("Failed to recover output device: {}", e);
+        }
+        let _ = app_handle.emit_all(
+            "output-device-lost",
+            OutputDeviceLostPayload {
+                device_name,
+                message,
+                recovered_device,
+                position,
+            },
+        );
+    });
+}
+
+// 등록된 다음 곡(next_file)을 미리 열어 둠 (open + probe + 첫 패킷 디코딩)
+// 등록이 취소/변경되면 준비해 둔 소스도 버림 (채널 구성은 지금 재생 중인 스트림의 layout을 따름)
+fn prepare_next_source(
+    state: &Arc<Mutex<PlayerState>>,
+    rt_state: &RtState,
+    next_source: &mut Option<DecodeSource>,
+    layout: &[OutputChannelRole],
+) {
+    let requested = match state.lock() {
+        Ok(state_guard) => state_guard.next_file.clone(),
+        Err(_) => return,
+    };
+    // 곡이 끝나면 멈추는 슬립 타이머가 있으면 다음 곡을 이어 붙이지 않음
+    let requested = requested.filter(|_| sleep_timer_mode() != Some(SleepTimerMode::EndOfTrack));
+    let Some(path) = requested else {
+        *next_source = None;
+        return;
+    };
+    if next_source.as_ref().map(|s| s.file_path.as_str()) == Some(path.as_str()) {
+        return;
+    }
+    match open_song_source(&path) {
+        Ok(mut source) => {
+            // 프리디코딩 샘플에도 ReplayGain이 적용되도록 prime 전에 gain 설정
+            source.replaygain = load_song_replaygain(&path);
+            source.gain = rt_state.replaygain_gain(source.replaygain.as_ref());
+            apply_silence_trim(&mut source);
+            source.set_layout(layout.to_vec());
+            source.prime();
+            eprintln!("Next track prepared for gapless playback: {}", path);
+            *next_source = Some(source);
+        }
+        Err(e) => {
+            eprintln!("Failed to prepare next track {}: {}", path, e);
+            *next_source = None;
+            // 같은 파일로 매 패킷마다 재시도하지 않도록 등록 해제
+            if let Ok(mut state_guard) = state.lock() {
+                if state_guard.next_file.as_deref() == Some(path.as_str()) {
+                    state_guard.next_file = None;
+                }
+            }
+        }
     }
-    
-    let stream = device.build_output_stream(
-        config,
-        move |data: &mut [T], _: &cpal::OutputCallbackInfo| {
-            // ✅ Atomic으로 빠른 체크 (Mutex 없음 - 드롭아웃/지터 방지)
-            if rt_state.should_stop.load(Ordering::Relaxed) {
-                for sample in data.iter_mut() {
-                    *sample = T::from_sample(0.0);
+}
+
+fn current_crossfade_settings(state: &Arc<Mutex<PlayerState>>) -> CrossfadeSettings {
+    state.lock().map(|state_guard| state_guard.crossfade).unwrap_or_default()
+}
+
+// 채널 전송 결과
+enum SendOutcome {
+    Sent,
+    Interrupted, // seek/정지 요청으로 전송 취소 (샘플은 폐기)
+    Disconnected,
+}
+
+// ✅ 채널이 가득 차 있어도 seek/정지 요청에 바로 반응하도록 try_send로 대기
+fn send_samples(tx: &mpsc::SyncSender<Vec<f32>>, rt_state: &RtState, samples: Vec<f32>) -> SendOutcome {
+    let mut samples = samples;
+    loop {
+        if rt_state.should_stop.load(Ordering::Relaxed) || rt_state.seek_pending.load(Ordering::Acquire) {
+            return SendOutcome::Interrupted;
+        }
+        match tx.try_send(samples) {
+            Ok(()) => return SendOutcome::Sent,
+            Err(mpsc::TrySendError::Full(back)) => {
+                samples = back;
+                thread::sleep(Duration::from_millis(5));
+            }
+            Err(mpsc::TrySendError::Disconnected(_)) => return SendOutcome::Disconnected,
+        }
+    }
+}
+
+// 지금 디코딩 스레드 안에 있는 샘플(batch / 꼬리 버퍼 / 속도 변환 / 리샘플링 대기)이 출력에서 차지할 프레임 수
+fn frames_until_output(
+    batch_samples: &[f32],
+    tail: &TailBuffer,
+    stretcher: &TimeStretcher,
+    resampler: &FrameResampler,
+    pending_frames: usize,
+    channels: usize,
+) -> u64 {
+    (batch_samples.len() / channels) as u64
+        + tail.buffered_frames() as u64
+        + stretcher.buffered_output_frames()
+        + (resampler.output_frames_for(pending_frames) as f64 / stretcher.settings().speed).round() as u64
+}
+
+// ✅ A-B 반복: B 이후 샘플을 버리고 A 위치로 이동
+// B 직전 약 5ms는 꺼내서 돌려주고, A에서 새로 디코딩한 첫 샘플과 섞어 이음새 클릭을 없앰
+// (섞는 길이만큼 A보다 앞에서 시작해서 반복 길이는 정확히 B - A로 유지)
+fn start_loop_wrap(
+    source: &mut DecodeSource,
+    pending: &mut [VecDeque<f32>],
+    ab_loop: AbLoop,
+) -> Option<Vec<VecDeque<f32>>> {
+    let sample_rate = source.sample_rate as f64;
+    let end_frames = (ab_loop.end * sample_rate).round() as u64;
+    let excess = source.position_frames.saturating_sub(end_frames) as usize;
+    let keep = pending[0].len().saturating_sub(excess);
+    for channel in pending.iter_mut() {
+        channel.truncate(keep);
+    }
+
+    let splice = (source.sample_rate as usize / 200).min(keep);
+    let start_frames = ((ab_loop.start * sample_rate).round() as u64).saturating_sub(splice as u64);
+    let start = start_frames as f64 / sample_rate;
+    let seeked = source
+        .seek(start, SeekMode::Accurate)
+        .or_else(|_| source.seek(start, SeekMode::Coarse));
+    if let Err(e) = seeked {
+        eprintln!("A-B loop: failed to seek back to {:.2}s: {}", ab_loop.start, e);
+        return None;
+    }
+    let split_at = keep - splice;
+    Some(pending.iter_mut().map(|channel| channel.split_off(split_at)).collect())
+}
+
+// A 위치에서 새로 디코딩한 샘플(pending의 from 이후) 머리에 B 직전 꼬리를 섞음
+fn mix_loop_splice(splice: Vec<VecDeque<f32>>, pending: &mut [VecDeque<f32>], from: usize) {
+    for (tail, channel) in splice.iter().zip(pending.iter_mut()) {
+        let frames = tail.len().min(channel.len().saturating_sub(from));
+        for i in 0..frames {
+            let t = (i as f32 + 0.5) / frames as f32;
+            channel[from + i] = tail[i] * (1.0 - t) + channel[from + i] * t;
+        }
+    }
+}
+
+fn decode_thread(
+    mut source: DecodeSource,
+    state: Arc<Mutex<PlayerState>>,
+    rt_state: Arc<RtState>,
+    tx: mpsc::SyncSender<Vec<f32>>,
+    target_sample_rate: u32,
+) {
+    // ✅ pending 최대 길이 상한 (메모리 폭증 방지)
+    const PENDING_MAX: usize = RS_IN_FRAMES * 20; // 약 20블록 분량
+    // ✅ batch_samples 최대 크기 (한 번에 보내는 샘플 수 제한)
+    const BATCH_MAX_FRAMES: usize = 16384; // 16384 프레임 (샘플 수는 채널 수만큼 곱함)
+    const FLUSH_INTERVAL: Duration = Duration::from_millis(30); // 30ms마다 flush
+    const MIN_FLUSH_FRAMES: usize = 1024; // 최소 1024 프레임 - 메시지 폭발 방지
+    const FORCE_FLUSH_INTERVAL: Duration = Duration::from_millis(200); // 200ms 경과 시 MIN_FLUSH 무시하고 강제 전송 (방탄 백업)
+    // ✅ EOF에서 다음 곡 등록을 기다리는 동안 최소한 남겨 둘 출력 대기 분량 (초)
+    const NEXT_TRACK_WAIT_MIN_SECONDS: u64 = 1;
+
+    // ✅ 파이프라인 채널 구성 (기본 LR, 멀티채널 출력이면 원본 채널 구성), 스트림이 끝날 때까지 유지
+    let layout = source.layout().to_vec();
+    let channels = layout.len();
+    let batch_max_samples = BATCH_MAX_FRAMES * channels;
+    let min_flush_samples = MIN_FLUSH_FRAMES * channels;
+
+    let mut batch_samples: Vec<f32> = Vec::new();
+    let mut resampler = FrameResampler::new(source.sample_rate, target_sample_rate, channels);
+    // ✅ pending 버퍼: 리샘플러에 고정 크기 블록을 전달하기 위한 채널별 누적 버퍼
+    let mut pending: Vec<VecDeque<f32>> = (0..channels).map(|_| VecDeque::with_capacity(RS_IN_FRAMES * 3)).collect();
+    // gapless로 이어 붙일 다음 곡 (EOF 전에 미리 열어 둠)
+    let mut next_source: Option<DecodeSource> = None;
+    // ✅ 크로스페이드: 현재 곡 꼬리를 붙잡아 두는 버퍼 + 다음 곡 머리에 꼬리를 섞는 믹서
+    // 파이프라인: 디코딩 -> pending -> 리샘플링(fresh) -> 크로스페이드 믹스 -> 꼬리 버퍼 -> batch_samples
+    let mut fresh: Vec<f32> = Vec::new();
+    let mut tail = TailBuffer::new(0, channels);
+    let mut crossfader: Option<Crossfader> = None;
+    // ✅ EQ: 꼬리 버퍼에서 batch로 나가는 샘플에 적용 (모든 샘플이 정확히 한 번 통과)
+    let mut equalizer = Equalizer::new(target_sample_rate, channels);
+    let mut eq_version = u64::MAX;
+    // ✅ 컴프레서 + 리미터: EQ 바로 다음에 적용 (EQ / ReplayGain으로 커진 피크도 리미터가 잡음)
+    let mut dynamics = DynamicsProcessor::new(target_sample_rate, channels);
+    let mut dynamics_version = u64::MAX;
+    // ✅ 속도/음정: 리샘플링 직후 fresh에 적용 (크로스페이드/EQ는 변환된 출력 기준으로 동작)
+    let mut stretcher = TimeStretcher::new(target_sample_rate, channels);
+    let mut tempo_version = u64::MAX;
+    // ✅ A-B 반복: B를 지나면 디코딩 스레드 안에서 바로 A로 이동 (seek_audio를 거치지 않음)
+    let mut loop_version = u64::MAX;
+    let mut ab_loop: Option<AbLoop> = None;
+    let mut loop_splice: Option<Vec<VecDeque<f32>>> = None;
+    let mut loop_wrap_at: Option<u64> = None; // 마지막으로 A로 돌아간 지점의 누적 프레임 위치
+    let decode_ahead_frames = (target_sample_rate as f64 * DECODE_AHEAD_SECONDS) as u64;
+
+    // 디버깅 카운터
+    let mut sent_samples = 0u64;
+    // ✅ 채널로 보낸 누적 프레임 수 (RtState.frames_consumed와 비교해서 트랙 경계 계산)
+    let mut sent_frames = 0u64;
+    let mut last_log = std::time::Instant::now();
+    let mut last_pending_warn = std::time::Instant::now(); // ✅ pending 경고 쿨다운
+    let mut last_flush = std::time::Instant::now(); // ✅ 시간 기반 flush
+
+    'decode_loop: loop {
+        if rt_state.should_stop.load(Ordering::Relaxed) {
+            break 'decode_loop;
+        }
+        rt_state.frames_sent.store(sent_frames, Ordering::Relaxed);
+
+        // ✅ seek 요청: 스트림/재생 스레드는 그대로 두고 디코딩 위치만 이동
+        if rt_state.seek_pending.load(Ordering::Acquire) {
+            let seconds = f64::from_bits(rt_state.seek_target.load(Ordering::Relaxed));
+            // 디코딩은 넘어갔지만 아직 출력되지 않은 gapless 전환은 취소하고 지금 들리는 곡을 다시 엶
+            if rt_state.track_boundary.swap(NO_TRACK_BOUNDARY, Ordering::Relaxed) != NO_TRACK_BOUNDARY {
+                let current_file = match state.lock() {
+                    Ok(mut state_guard) => {
+                        state_guard.pending_gapless_duration = None;
+                        if let Some(pending) = state_guard.pending_gapless_file.take() {
+                            state_guard.next_file.get_or_insert(pending);
+                        }
+                        state_guard.current_file.clone()
+                    }
+                    Err(_) => None,
+                };
+                if let Some(current_file) = current_file {
+                    match open_song_source(&current_file) {
+                        Ok(mut reopened) => {
+                            reopened.replaygain = load_song_replaygain(&current_file);
+                            apply_silence_trim(&mut reopened);
+                            reopened.set_layout(layout.clone());
+                            source = reopened;
+                        }
+                        Err(e) => eprintln!("Failed to reopen {} for seek: {}", current_file, e),
+                    }
                 }
-                return;
             }
-            
-            if rt_state.finished.load(Ordering::Relaxed) {
-                for sample in data.iter_mut() {
-                    *sample = T::from_sample(0.0);
+
+            // 이전 위치의 샘플은 모두 폐기 (pending / 리샘플러 / 크로스페이드 꼬리 / batch)
+            pending.iter_mut().for_each(VecDeque::clear);
+            fresh.clear();
+            tail.take();
+            crossfader = None;
+            batch_samples.clear();
+            equalizer.reset();
+            dynamics.reset();
+            stretcher.reset();
+            loop_splice = None;
+            loop_wrap_at = None;
+            resampler = FrameResampler::new(source.sample_rate, target_sample_rate, channels);
+            // 아직 출력되지 않은 속도 변경은 seek 위치부터 바로 적용
+            if rt_state.speed_boundary.swap(NO_TRACK_BOUNDARY, Ordering::Relaxed) != NO_TRACK_BOUNDARY {
+                rt_state.speed.store(rt_state.next_speed.load(Ordering::Relaxed), Ordering::Relaxed);
+            }
+
+            let position = match source.seek(seconds, SeekMode::Accurate) {
+                Ok(()) => Some(seconds),
+                Err(e) => {
+                    eprintln!("Accurate seek to {:.2}s failed ({}), trying coarse seek", seconds, e);
+                    source.seek(seconds, SeekMode::Coarse).ok().map(|_| seconds)
+                }
+            };
+            if let Some(position) = position {
+                let expected_frames = (position * target_sample_rate as f64) as u64;
+                rt_state.samples_played.store(expected_frames, Ordering::Relaxed);
+                if let Ok(mut state_guard) = state.lock() {
+                    state_guard.samples_played = expected_frames;
                 }
-                return;
             }
-            
-            if rt_state.is_paused.load(Ordering::Relaxed) {
-                for sample in data.iter_mut() {
-                    *sample = T::from_sample(0.0);
+
+            // 콜백은 seek 이전에 보낸 샘플(sent_frames까지)을 버리고 새 위치부터 출력
+            rt_state.discard_until.store(sent_frames, Ordering::Relaxed);
+            rt_state.decoder_finished.store(false, Ordering::Relaxed);
+            rt_state.seek_pending.store(false, Ordering::Release);
+            last_flush = std::time::Instant::now();
+        }
+
+        prepare_next_source(&state, &rt_state, &mut next_source, &layout);
+
+        // ✅ EQ 설정 변경 반영 (비트퍼펙트 출력이면 적용 안 함)
+        let current_eq_version = rt_state.eq_version.load(Ordering::Acquire);
+        if current_eq_version != eq_version {
+            if let Ok(state_guard) = state.lock() {
+                let mut eq = state_guard.eq.clone();
+                eq.enabled &= !rt_state.bit_perfect.load(Ordering::Relaxed);
+                equalizer.set(&eq);
+            }
+            eq_version = current_eq_version;
+        }
+
+        // ✅ 컴프레서/리미터 설정 변경 반영 (비트퍼펙트 출력이면 적용 안 함)
+        let current_dynamics_version = rt_state.dynamics_version.load(Ordering::Acquire);
+        if current_dynamics_version != dynamics_version {
+            if let Ok(state_guard) = state.lock() {
+                let mut settings = state_guard.dynamics;
+                if rt_state.bit_perfect.load(Ordering::Relaxed) {
+                    settings.compressor_enabled = false;
+                    settings.limiter_enabled = false;
                 }
-                return;
+                dynamics.set(&settings);
             }
-            
-            let volume = rt_state.get_volume();
-            
-            // ✅ 버퍼가 부족하면 채널에서 데이터 가져오기 (non-blocking)
-            // RT 콜백에서는 블로킹하지 않음 - try_recv만 사용
-            // ✅ 프레임 기준으로 계산 (내부는 LR 2채널, 출력 장치 채널 수와 무관)
-            let frames = data.len() / channels;
-            let need_lr = frames * 2; // LR 인터리브
-            while sample_queue.len() < need_lr {
-                match rx.try_recv() {
-                    Ok(samples) => {
-                        sample_queue.extend(samples);
+            dynamics_version = current_dynamics_version;
+        }
+
+        // ✅ 속도/음정 설정 변경 반영 (이전 속도 변경이 아직 출력되지 않았으면 그 뒤에 반영)
+        let current_tempo_version = rt_state.tempo_version.load(Ordering::Acquire);
+        if current_tempo_version != tempo_version && rt_state.speed_boundary.load(Ordering::Relaxed) == NO_TRACK_BOUNDARY {
+            let mut tempo = state.lock().map(|state_guard| state_guard.tempo).unwrap_or_default();
+            if rt_state.bit_perfect.load(Ordering::Relaxed) {
+                tempo = TempoSettings::default();
+            }
+            if tempo.speed != stretcher.settings().speed {
+                // 이미 만들어 둔 샘플(전송 대기 / 꼬리 버퍼 / 변환 중)이 모두 출력된 뒤부터 새 속도로 위치 계산
+                let boundary = sent_frames
+                    + (batch_samples.len() / channels) as u64
+                    + tail.buffered_frames() as u64
+                    + stretcher.buffered_output_frames();
+                rt_state.next_speed.store(tempo.speed.to_bits(), Ordering::Relaxed);
+                rt_state.speed_boundary.store(boundary, Ordering::Relaxed);
+            }
+            stretcher.set(tempo);
+            tempo_version = current_tempo_version;
+        }
+
+        // ✅ A-B 반복 구간 변경 반영
+        let current_loop_version = rt_state.loop_version.load(Ordering::Acquire);
+        if current_loop_version != loop_version {
+            loop_version = current_loop_version;
+            ab_loop = state.lock().ok().and_then(|state_guard| state_guard.ab_loop);
+            let to_frames = |seconds: f64| (seconds * target_sample_rate as f64) as u64;
+            match ab_loop {
+                Some(l) => {
+                    rt_state.loop_start.store(to_frames(l.start), Ordering::Relaxed);
+                    rt_state.loop_end.store(to_frames(l.end), Ordering::Relaxed);
+                }
+                None => rt_state.loop_end.store(NO_TRACK_BOUNDARY, Ordering::Relaxed),
+            }
+
+            // 이미 디코딩해 둔 샘플이 새 구간과 맞지 않으면 현재 위치(구간 밖이면 A)에서 다시 디코딩
+            let played = rt_state.samples_played.load(Ordering::Relaxed) as f64 / target_sample_rate as f64;
+            let decoded = source.position_frames as f64 / source.sample_rate as f64;
+            let wrap_in_flight = loop_wrap_at
+                .map(|at| at > rt_state.frames_consumed.load(Ordering::Relaxed))
+                .unwrap_or(false);
+            let resync = match ab_loop {
+                Some(l) if played < l.start || played >= l.end => Some(l.start),
+                Some(l) if wrap_in_flight || decoded >= l.end => Some(played),
+                None if wrap_in_flight => Some(played),
+                _ => None,
+            };
+            if let Some(seconds) = resync {
+                rt_state.request_seek(seconds);
+                continue 'decode_loop;
+            }
+        }
+
+        // ✅ 출력 대기 분량이 충분하면 디코딩을 잠시 쉼 (설정 변경이 늦게 들리지 않도록 앞서 가는 양 제한)
+        let queued = sent_frames.saturating_sub(rt_state.frames_consumed.load(Ordering::Relaxed));
+        if queued >= decode_ahead_frames {
+            thread::sleep(Duration::from_millis(5));
+            continue 'decode_loop;
+        }
+
+        // ✅ ReplayGain 설정 변경을 다음 패킷부터 반영
+        source.gain = rt_state.replaygain_gain(source.replaygain.as_ref());
+        let crossfade = current_crossfade_settings(&state);
+        let crossfade_frames = crossfade.frames(target_sample_rate);
+        tail.set_frames(crossfade_frames);
+
+        let pending_before = pending[0].len();
+        match source.decode_into(&mut pending) {
+            DecodeStatus::Decoded => {
+                // ✅ A-B 반복: A에서 다시 디코딩한 첫 샘플에 B 직전 꼬리를 섞고, B를 지났으면 A로 이동
+                if let Some(splice) = loop_splice.take() {
+                    mix_loop_splice(splice, &mut pending, pending_before);
+                }
+                if let Some(l) = ab_loop {
+                    let end_frames = (l.end * source.sample_rate as f64).round() as u64;
+                    let decoded_from = source.position_frames.saturating_sub((pending[0].len() - pending_before) as u64);
+                    if decoded_from < end_frames && source.position_frames >= end_frames {
+                        loop_splice = start_loop_wrap(&mut source, &mut pending, l);
+                        loop_wrap_at = Some(sent_frames + frames_until_output(&batch_samples, &tail, &stretcher, &resampler, pending[0].len(), channels));
                     }
-                    Err(mpsc::TryRecvError::Empty) => {
-                        // 버퍼가 비어있으면 마지막 샘플 반복 (끊김 방지)
-                        break;
+                }
+
+                // ✅ pending 상한 체크는 패킷 처리 후 한 번만 (로그는 쿨다운으로 제한)
+                if pending.iter().any(|channel| channel.len() > PENDING_MAX) {
+                    // 가장 오래된 샘플 drop
+                    for channel in pending.iter_mut() {
+                        let excess = channel.len().saturating_sub(PENDING_MAX);
+                        channel.drain(..excess);
+                    }
+                    // ✅ 로그는 1초에 1번만
+                    if last_pending_warn.elapsed() > Duration::from_secs(1) {
+                        eprintln!("Warning: pending buffer exceeded limit, dropping oldest samples");
+                        last_pending_warn = std::time::Instant::now();
+                    }
+                }
+
+                // ✅ 리샘플링 처리 (필요 없으면 pending -> fresh 그대로)
+                resampler.process(&mut pending, &mut fresh);
+                stretcher.process(&mut fresh);
+                if let Some(ref mut cf) = crossfader {
+                    cf.mix(&mut fresh);
+                    if cf.is_done() {
+                        crossfader = None;
                     }
-                    Err(mpsc::TryRecvError::Disconnected) => {
-                        // 채널이 닫혔으면 디코딩 끝난 것
-                        // sample_queue에 남은 샘플이 있으면 계속 재생, 비었고 디코딩 종료면 자연 종료 플래그 설정
-                        if DISCONNECT_LOGGED.compare_exchange(false, true, Ordering::Relaxed, Ordering::Relaxed).is_ok() {
-                            eprintln!("[rt] rx disconnected");
+                }
+                let eq_start = batch_samples.len();
+                tail.push(&mut fresh, &mut batch_samples);
+                equalizer.process(&mut batch_samples[eq_start..]);
+                dynamics.process(&mut batch_samples[eq_start..]);
+            }
+            DecodeStatus::Skipped => {}
+            DecodeStatus::Eof => {
+                // ✅ B가 곡 끝보다 뒤면 곡 끝에서 A로 돌아감 (콜백의 위치 되감기도 곡 끝 기준으로)
+                if let Some(l) = ab_loop {
+                    let position = source.position_frames as f64 / source.sample_rate as f64;
+                    if position >= l.start + AB_LOOP_MIN_SECONDS && position < l.end {
+                        rt_state.loop_end.store((position * target_sample_rate as f64) as u64, Ordering::Relaxed);
+                        let l = AbLoop { start: l.start, end: position };
+                        if let Some(splice) = start_loop_wrap(&mut source, &mut pending, l) {
+                            loop_splice = Some(splice);
+                            loop_wrap_at = Some(sent_frames + frames_until_output(&batch_samples, &tail, &stretcher, &resampler, pending[0].len(), channels));
+                            rt_state.decoder_finished.store(false, Ordering::Relaxed);
+                            continue 'decode_loop;
                         }
-                        if sample_queue.is_empty() && rt_state.decoder_finished.load(Ordering::Relaxed) {
-                            rt_state.finished.store(true, Ordering::Relaxed);
+                    }
+                }
+
+                eprintln!("Decoder reached EOF (file fully consumed): {}", source.file_path);
+                // 패킷을 하나도 디코딩하지 못한 곡은 재생할 수 없는 파일로 알림 (다음 곡이 있으면 그대로 이어짐)
+                let undecodable = source.decoded_ok == 0 && source.decoded_err > 0;
+
+                // ✅ 다음 곡을 기다리는 동안 재생이 끊기지 않도록 지금까지 모인 샘플은 먼저 전송
+                if !batch_samples.is_empty() {
+                    let out = mem::take(&mut batch_samples);
+                    let send_count = out.len();
+                    match send_samples(&tx, &rt_state, out) {
+                        SendOutcome::Sent => {
+                            sent_samples += send_count as u64;
+                            sent_frames += (send_count / channels) as u64;
                         }
-                        break;
+                        SendOutcome::Interrupted => continue 'decode_loop,
+                        SendOutcome::Disconnected => break 'decode_loop,
                     }
                 }
-            }
-            
-            // ✅ 디버그: 볼륨 확인 (처음 몇 번만, 모듈 스코프의 Atomic 사용)
-            let count = VOLUME_LOG_COUNT.fetch_add(1, Ordering::Relaxed);
-            if count < 3 {
-                eprintln!("[rt] volume={}, queue_len={}", volume, sample_queue.len());
-            }
-            
-            // ✅ 데이터 출력: 내부는 항상 LR 인터리브, 출력 시에만 장치 채널 수에 맞게 복제
-            // 내부 데이터 구조: L, R, L, R, ... (항상 2채널)
-            // CPAL 출력: 장치 채널 수에 맞게 복제 (1채널→L만, 2채널→LR, 6채널→LRLRLR)
-            // ✅ 핵심: frame 단위로 LR을 정확히 1번만 pop, 채널 수는 복제만 함
-            let frames = data.len() / channels;
-            let mut lr_pairs_outputted = 0u64; // 실제 LR 쌍 수 추적
-            
-            for frame in 0..frames {
-                // 각 frame마다 LR 쌍을 정확히 1번만 pop
-                // ✅ 모노 출력(channels == 1)이면 1샘플만 소비, 스테레오 이상이면 LR 2샘플 소비
-                let (l, r) = if channels == 1 {
-                    // 🔥 모노 출력이면 1샘플만 소비 (L만 pop)
-                    let l = sample_queue.pop_front().unwrap_or(last_lr[0]);
-                    last_lr[0] = l;
-                    last_lr[1] = l; // 내부 상태 유지
-                    lr_pairs_outputted += 1;
-                    (l, l) // 모노이므로 R도 L과 동일
-                } else {
-                    // 스테레오 이상: LR 2샘플 소비
-                    if sample_queue.len() >= 2 {
-                        let l = sample_queue.pop_front().unwrap_or(last_lr[0]);
-                        let r = sample_queue.pop_front().unwrap_or(last_lr[1]);
-                        last_lr[0] = l;
-                        last_lr[1] = r;
-                        lr_pairs_outputted += 1;
-                        (l, r)
-                    } else if sample_queue.len() == 1 {
-                        // 마지막 샘플 하나만 남은 경우
-                        let l = sample_queue.pop_front().unwrap_or(last_lr[0]);
-                        last_lr[0] = l;
-                        last_lr[1] = l; // 모노인 경우
-                        lr_pairs_outputted += 1;
-                        (l, l)
+
+                // 다음 곡이 등록되어 있거나, 출력 대기 분량이 바닥나기 전까지 등록을 기다림
+                let next = loop {
+                    if rt_state.should_stop.load(Ordering::Relaxed) {
+                        break 'decode_loop;
+                    }
+                    if rt_state.seek_pending.load(Ordering::Acquire) {
+                        continue 'decode_loop;
+                    }
+                    prepare_next_source(&state, &rt_state, &mut next_source, &layout);
+                    if next_source.is_some() {
+                        // 이전 곡 전환이 아직 출력되지 않았으면 경계가 겹치지 않도록 대기
+                        if rt_state.track_boundary.load(Ordering::Relaxed) == NO_TRACK_BOUNDARY {
+                            break next_source.take();
+                        }
                     } else {
-                        // 버퍼 부족: 마지막 샘플 사용
-                        (last_lr[0], last_lr[1])
+                        let queued = sent_frames.saturating_sub(rt_state.frames_consumed.load(Ordering::Relaxed));
+                        if queued < target_sample_rate as u64 * NEXT_TRACK_WAIT_MIN_SECONDS {
+                            break None;
+                        }
                     }
+                    thread::sleep(Duration::from_millis(20));
                 };
-                
-                // 각 채널에 LR 샘플 복제
-                for ch in 0..channels {
-                    let sample = if ch % 2 == 0 {
-                        l // 짝수 채널 = L
-                    } else {
-                        r // 홀수 채널 = R
-                    };
-                    data[frame * channels + ch] = T::from_sample(sample * volume);
+                if undecodable {
+                    eprintln!("No decodable packets in {} ({} errors)", source.file_path, source.decoded_err);
+                    if let Ok(mut state_guard) = state.lock() {
+                        state_guard.decode_failures.push(PlaybackErrorPayload {
+                            kind: PlaybackErrorKind::Decoder,
+                            file_path: source.file_path.clone(),
+                            message: format!("No decodable audio packets ({} decode errors)", source.decoded_err),
+                            next_file_path: next.as_ref().map(|next| next.file_path.clone()),
+                        });
+                    }
                 }
-            }
-            
-            // ✅ 프레임 기반으로 samples_played 업데이트 (채널 수와 무관)
-            // Atomic으로 빠른 업데이트 (Mutex 없음)
-            if lr_pairs_outputted > 0 {
-                let frames_outputted = lr_pairs_outputted; // 프레임 수 (LR 쌍 = 1 프레임)
-                if !rt_state.is_paused.load(Ordering::Relaxed) {
-                    rt_state.samples_played.fetch_add(frames_outputted, Ordering::Relaxed);
+
+                // ✅ 크로스페이드하거나 샘플레이트가 바뀌면 현재 곡 pending을 여기서 모두 출력으로 내보냄
+                // (같은 샘플레이트 gapless 전환이면 pending/리샘플러 상태를 그대로 이어서 사용)
+                let use_crossfade = next.is_some() && crossfade_frames > 0;
+                let must_drain = match next.as_ref() {
+                    Some(next) => use_crossfade || crossfader.is_some() || next.sample_rate != source.sample_rate,
+                    None => true,
+                };
+                if must_drain {
+                    // ✅ EOF 처리: pending 잔여 처리 + flush
+                    resampler.drain(&mut pending, &mut fresh);
+                    stretcher.process(&mut fresh);
+                    stretcher.drain(&mut fresh);
                 }
-            }
-        },
-        |err| eprintln!("Stream error: {}", err),
-        None,
-    ).map_err(|e| format!("Failed to build stream: {}", e))?;
-    
-    Ok(stream)
+                // 진행 중인 크로스페이드가 있으면 (현재 곡이 크로스페이드 구간보다 짧음) 남은 꼬리를 마무리
+                if let Some(mut cf) = crossfader.take() {
+                    cf.mix(&mut fresh);
+                    cf.finish(&mut fresh);
+                }
+                let eq_start = batch_samples.len();
+                tail.push(&mut fresh, &mut batch_samples);
+                let fade_tail = if use_crossfade { tail.take() } else { Vec::new() };
+                batch_samples.extend(tail.take());
+                equalizer.process(&mut batch_samples[eq_start..]);
+                dynamics.process(&mut batch_samples[eq_start..]);
+
+                let Some(next) = next else {
+                    // 리미터 지연 버퍼에 남은 마지막 샘플까지 내보냄
+                    dynamics.drain(&mut batch_samples);
+                    // ✅ EOF에서도 mem::take로 통째 전송 (복사 비용 제거)
+                    if !batch_samples.is_empty() {
+                        let out = mem::take(&mut batch_samples);
+                        let send_count = out.len();
+                        match send_samples(&tx, &rt_state, out) {
+                            SendOutcome::Sent => {
+                                sent_samples += send_count as u64;
+                                sent_frames += (send_count / channels) as u64;
+                            }
+                            SendOutcome::Interrupted => continue 'decode_loop,
+                            SendOutcome::Disconnected => break 'decode_loop,
+                        }
+                    }
+                    // ✅ 끝까지 디코딩 완료: 남은 샘플이 모두 출력될 때까지는 seek 요청을 받을 수 있게 대기
+                    rt_state.decoder_finished.store(true, Ordering::Relaxed);
+                    loop {
+                        if rt_state.should_stop.load(Ordering::Relaxed) || rt_state.finished.load(Ordering::Relaxed) {
+                            break 'decode_loop;
+                        }
+                        // 마지막 곡 끝부분에서 A-B 반복을 설정한 경우에도 디코딩 재개
+                        if rt_state.seek_pending.load(Ordering::Acquire)
+                            || rt_state.loop_version.load(Ordering::Acquire) != loop_version
+                        {
+                            continue 'decode_loop;
+                        }
+                        thread::sleep(Duration::from_millis(20));
+                    }
+                };
+
+                // ✅ 다음 곡 첫 샘플의 출력 위치 (누적 프레임 기준)
+                // 크로스페이드면 꼬리를 섞기 시작하는 지점이 곧 다음 곡 시작 지점
+                let boundary = sent_frames + frames_until_output(&batch_samples, &tail, &stretcher, &resampler, pending[0].len(), channels);
+                if must_drain {
+                    resampler = FrameResampler::new(next.sample_rate, target_sample_rate, channels);
+                }
+                if !fade_tail.is_empty() {
+                    crossfader = Some(Crossfader::new(fade_tail, crossfade.curve, channels));
+                }
+                eprintln!("Gapless transition: {} -> {} (boundary frame: {}, crossfade: {:.1}s)",
+                    source.file_path, next.file_path, boundary, crossfade.duration_secs);
+                {
+                    if let Ok(mut state_guard) = state.lock() {
+                        state_guard.pending_gapless_file = Some(next.file_path.clone());
+                        state_guard.pending_gapless_duration = next.duration;
+                        state_guard.next_file = None;
+                    }
+                }
+                rt_state.next_track_offset.store((next.start_offset * target_sample_rate as f64) as u64, Ordering::Relaxed);
+                rt_state.track_boundary.store(boundary, Ordering::Relaxed);
+                source = next;
+                // A-B 반복은 곡이 바뀌면 해제
+                if ab_loop.take().is_some() {
+                    if let Ok(mut state_guard) = state.lock() {
+                        state_guard.ab_loop = None;
+                    }
+                    rt_state.loop_end.store(NO_TRACK_BOUNDARY, Ordering::Relaxed);
+                }
+            }
+        }
+
+        // ✅ batch_samples가 batch_max_samples 이상이면 고정 크기로 전송 (성능 최적화: split_off + mem::take)
+        // ✅ while 루프로 여러 번 전송 가능 (큰 덩어리가 쌓였을 때 대응)
+        // ✅ >= 조건으로 딱 맞게 쌓인 경우도 즉시 전송 (예측 가능성 향상)
+        while batch_samples.len() >= batch_max_samples {
+            // split_off로 나머지 분리 후 mem::take로 정확히 batch_max_samples만 전송 (복사/할당 최소화)
+            let rest = batch_samples.split_off(batch_max_samples);
+            let out = mem::take(&mut batch_samples); // 정확히 batch_max_samples
+            let send_count = out.len();
+            match send_samples(&tx, &rt_state, out) {
+                SendOutcome::Sent => {
+                    sent_samples += send_count as u64;
+                    sent_frames += (send_count / channels) as u64;
+                }
+                // seek 요청: 남은 batch는 루프 처음에서 폐기
+                SendOutcome::Interrupted => continue 'decode_loop,
+                // 수신자가 없음: 재생 중지
+                SendOutcome::Disconnected => break 'decode_loop,
+            }
+            batch_samples = rest; // 나머지로 교체
+        }
+        
+        // ✅ 시간 기반 flush: BATCH_MAX에 못 미쳐도 일정 주기로 전송 (초반 무음/지연 방지)
+        // ✅ 최소 샘플 수 하한으로 메시지 폭발 방지
+        // ✅ FORCE_FLUSH_INTERVAL 백업 규칙: 200ms 경과 시 MIN_FLUSH 무시하고 강제 전송 (방탄)
+        let flush_elapsed = last_flush.elapsed();
+        let should_flush = if flush_elapsed >= FORCE_FLUSH_INTERVAL {
+            // 200ms 경과 시 MIN_FLUSH 무시하고 강제 전송 (이상 케이스 대비)
+            !batch_samples.is_empty()
+        } else if flush_elapsed >= FLUSH_INTERVAL {
+            // 30ms 경과 + MIN_FLUSH 이상일 때만 전송
+            batch_samples.len() >= min_flush_samples
+        } else {
+            false
+        };
+        
+        if should_flush {
+            let out = mem::take(&mut batch_samples);
+            let send_count = out.len();
+            match send_samples(&tx, &rt_state, out) {
+                SendOutcome::Sent => {
+                    sent_samples += send_count as u64;
+                    sent_frames += (send_count / channels) as u64;
+                }
+                SendOutcome::Interrupted => continue 'decode_loop,
+                // 수신자가 없음: 재생 중지
+                SendOutcome::Disconnected => break 'decode_loop,
+            }
+            last_flush = std::time::Instant::now();
+        }
+
+        if last_log.elapsed() > Duration::from_secs(2) {
+            eprintln!("[dbg] ok={}, err={}, sent={}, batch={}", source.decoded_ok, source.decoded_err, sent_samples, batch_samples.len());
+            last_log = std::time::Instant::now();
+        }
+    }
+
+    // ✅ 루프 종료 시 남은 batch_samples 전송
+    if !batch_samples.is_empty() {
+        let out = mem::take(&mut batch_samples);
+        let send_count = out.len();
+        if let SendOutcome::Sent = send_samples(&tx, &rt_state, out) {
+            sent_samples += send_count as u64;
+        }
+    }
+    
+    eprintln!("Decoding thread: exiting, closing channel");
+    eprintln!("Debug stats: decoded_ok={}, decoded_err={}, sent_samples={}", source.decoded_ok, source.decoded_err, sent_samples);
+    drop(tx);
+    if !rt_state.should_stop.load(Ordering::Relaxed) {
+        rt_state.decoder_finished.store(true, Ordering::Relaxed);
+    }
+}
+
+fn build_renderer(
+    rx: mpsc::Receiver<Vec<f32>>,
+    rt_state: Arc<RtState>,
+    sample_rate: u32,
+    channels: usize,
+    layout: Vec<OutputChannelRole>,
+) -> RenderFn {
+    // 버퍼 사용 (로컬 파일이므로 작은 버퍼로도 충분)
+    let sample_rate = sample_rate as usize;
+    // ✅ 내부 채널 수 (기본 LR 2채널, 멀티채널 원본을 그대로 내보낼 때는 원본 채널 수)
+    let width = layout.len();
+    let mut sample_queue: VecDeque<f32> = VecDeque::with_capacity(sample_rate * width);
+    let mut last_frame = vec![0.0f32; width]; // 마지막 프레임 저장 (끊김 방지)
+    let mut frame_samples = vec![0.0f32; width]; // 이번 프레임 (콜백 안에서 할당하지 않도록 미리 만들어 둠)
+    // ✅ seek 시 클릭 방지용 짧은 페이드 (약 5ms)
+    let seek_fade_frames = (sample_rate / 200).max(1);
+    let mut seek_faded_out = false; // seek 요청 후 페이드아웃을 마쳤는지
+    let mut fade_in_remaining = 0usize;
+    let mut played_fract = 0.0f64; // 재생 속도를 곱한 samples_played 증가량의 소수 부분
+    // 슬립 타이머 페이드 gain (목표값까지 약 50ms에 걸쳐 따라가서 계단 소리 방지)
+    let mut fade_gain = f32::from_bits(rt_state.fade_gain.load(Ordering::Relaxed));
+    let fade_step = 1.0 / (sample_rate as f32 * 0.05);
+    // 일시정지 / 재개 / 정지 / 곡 시작 램프 gain (새 스트림은 무음에서 시작해서 페이드인)
+    let mut transport_gain = 0.0f32;
+    // 분석용 링 버퍼에 쓸 다음 위치
+    let mut tap_pos = rt_state.analysis.write_pos();
+    // ✅ 출력 장치 채널별 내부 채널 계수 (upmix 꺼짐 / 켜짐)
+    let channel_matrices = [
+        output_channel_matrix(&layout, channels, false),
+        output_channel_matrix(&layout, channels, true),
+    ];
+    
+    // 재생 시작 전에 버퍼를 미리 채우기 (프리로딩)
+    // 최소 버퍼 크기: PRELOAD_SECONDS 분량 (안정적인 재생 시작을 위해)
+    // ✅ 프레임 기준으로 계산
+    let min_frames = (sample_rate as f64 * PRELOAD_SECONDS) as usize; // PRELOAD_SECONDS 분량의 프레임 수
+    let min_buffer_size = min_frames * width; // 내부 채널 인터리브
+    let mut preload_attempts = 0;
+    const MAX_PRELOAD_ATTEMPTS: usize = 100; // 프리로딩을 위해 더 많은 시도 허용
+    let mut silent_preload_loops = 0; // 빈 샘플 연속 카운트
+    
+    while sample_queue.len() < min_buffer_size && preload_attempts < MAX_PRELOAD_ATTEMPTS {
+        match rx.try_recv() {
+            Ok(samples) => {
+                if samples.is_empty() {
+                    // ✅ 빈 샘플 처리: encoder delay / priming frames로 인한 정상적인 무음 패킷
+                    silent_preload_loops += 1;
+                    if silent_preload_loops > 50 {
+                        // 🔥 더 이상 기다리지 말고 그냥 재생 시작
+                        // 초반 무음 패킷이 계속 오는 경우 무한 대기 방지
+                        break;
+                    }
+                    continue; // 빈 샘플은 스킵하고 다음 시도
+                }
+                sample_queue.extend(samples);
+                silent_preload_loops = 0; // 정상 샘플 수신 시 리셋
+                preload_attempts = 0; // 성공하면 카운터 리셋
+            }
+            Err(mpsc::TryRecvError::Empty) => {
+                preload_attempts += 1;
+                thread::sleep(Duration::from_millis(5)); // 짧게 대기
+            }
+            Err(mpsc::TryRecvError::Disconnected) => {
+                // 채널이 닫혔으면 프리로딩 중단하고 빈 버퍼로 시작
+                // 디코딩 스레드가 곧 데이터를 보낼 것이므로 괜찮음
+                break;
+            }
+        }
+    }
+    
+    Box::new(move |data: &mut [f32]| {
+        rt_state.callbacks.fetch_add(1, Ordering::Relaxed);
+        // ✅ Atomic으로 빠른 체크 (Mutex 없음 - 드롭아웃/지터 방지)
+        if rt_state.should_stop.load(Ordering::Relaxed) {
+            for sample in data.iter_mut() {
+                *sample = 0.0;
+            }
+            rt_state.ramp_silent.store(true, Ordering::Release);
+            return 0;
+        }
+        
+        if rt_state.finished.load(Ordering::Relaxed) {
+            for sample in data.iter_mut() {
+                *sample = 0.0;
+            }
+            rt_state.ramp_silent.store(true, Ordering::Release);
+            return 0;
+        }
+        
+        // ✅ 일시정지 / 정지 요청: 바로 무음으로 바꾸지 않고 램프가 0이 될 때까지 계속 출력 (클릭 방지)
+        let transport_target = if rt_state.is_paused.load(Ordering::Relaxed) || rt_state.stopping.load(Ordering::Relaxed) {
+            0.0f32
+        } else {
+            1.0f32
+        };
+        if transport_target == 0.0 && transport_gain <= 0.0 {
+            for sample in data.iter_mut() {
+                *sample = 0.0;
+            }
+            rt_state.ramp_silent.store(true, Ordering::Release);
+            return 0;
+        }
+        let transport_step = match rt_state.transition_fade_ms.load(Ordering::Relaxed) {
+            0 => 1.0,
+            ms => 1000.0 / (sample_rate as f32 * ms as f32),
+        };
+
+        // ✅ seek 요청: 이번 콜백에서 출력 중인 소리를 페이드아웃하고, 디코딩 스레드가 처리할 때까지 무음
+        let seek_pending = rt_state.seek_pending.load(Ordering::Acquire);
+        if seek_pending && seek_faded_out {
+            for sample in data.iter_mut() {
+                *sample = 0.0;
+            }
+            // 이미 무음이므로 일시정지 / 정지 램프도 끝난 것으로 처리
+            transport_gain = transport_gain.min(transport_target);
+            rt_state.ramp_silent.store(transport_gain <= 0.0, Ordering::Release);
+            return 0;
+        }
+        if !seek_pending {
+            // ✅ seek 처리 완료: seek 이전 위치에서 디코딩된 샘플(로컬 큐 + 채널)을 버림
+            let discard_until = rt_state.discard_until.load(Ordering::Relaxed);
+            let consumed = rt_state.frames_consumed.load(Ordering::Relaxed);
+            if consumed < discard_until {
+                let mut to_drop = (discard_until - consumed) as usize;
+                let local = (sample_queue.len() / width).min(to_drop);
+                sample_queue.drain(..local * width);
+                to_drop -= local;
+                while to_drop > 0 {
+                    match rx.try_recv() {
+                        Ok(samples) => {
+                            let frames = samples.len() / width;
+                            if frames <= to_drop {
+                                to_drop -= frames;
+                            } else {
+                                sample_queue.extend(&samples[to_drop * width..]);
+                                to_drop = 0;
+                            }
+                        }
+                        Err(_) => break,
+                    }
+                }
+                let dropped = (discard_until - consumed) - to_drop as u64;
+                rt_state.frames_consumed.fetch_add(dropped, Ordering::Relaxed);
+                if to_drop > 0 {
+                    // 아직 채널에 도착하지 않은 이전 샘플이 있음: 다음 콜백에서 계속 버림
+                    for sample in data.iter_mut() {
+                        *sample = 0.0;
+                    }
+                    transport_gain = transport_gain.min(transport_target);
+                    rt_state.ramp_silent.store(transport_gain <= 0.0, Ordering::Release);
+                    return 0;
+                }
+                fade_in_remaining = seek_fade_frames;
+            }
+            seek_faded_out = false;
+        }
+        
+        // 비트퍼펙트 출력이면 소프트웨어 볼륨 / 밸런스 / 모노 / 좌우 바꾸기 적용 안 함
+        let bit_perfect = rt_state.bit_perfect.load(Ordering::Relaxed);
+        let volume = if bit_perfect { 1.0 } else { rt_state.get_volume() };
+        let swap_channels = !bit_perfect && rt_state.swap_channels.load(Ordering::Relaxed);
+        let mono_sum = !bit_perfect && rt_state.mono_sum.load(Ordering::Relaxed);
+        let (balance_l, balance_r) = if bit_perfect {
+            (1.0, 1.0)
+        } else {
+            balance_gains(f32::from_bits(rt_state.balance.load(Ordering::Relaxed)))
+        };
+        
+        // ✅ 버퍼가 부족하면 채널에서 데이터 가져오기 (non-blocking)
+        // RT 콜백에서는 블로킹하지 않음 - try_recv만 사용
+        // ✅ 프레임 기준으로 계산 (내부 채널 수와 출력 장치 채널 수는 다를 수 있음)
+        let frames = data.len() / channels;
+        let need = frames * width; // 내부 채널 인터리브
+        while sample_queue.len() < need {
+            match rx.try_recv() {
+                Ok(samples) => {
+                    sample_queue.extend(samples);
+                }
+                Err(mpsc::TryRecvError::Empty) => {
+                    // 디코딩 스레드가 끝까지 디코딩하고 seek 대기 중이면 (채널은 열려 있음) 여기서 종료 판단
+                    if !seek_pending && sample_queue.is_empty() && rt_state.decoder_finished.load(Ordering::Relaxed) {
+                        rt_state.finished.store(true, Ordering::Relaxed);
+                    }
+                    // 버퍼가 비어있으면 마지막 샘플 반복 (끊김 방지)
+                    break;
+                }
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    // 채널이 닫혔으면 디코딩 끝난 것
+                    // sample_queue에 남은 샘플이 있으면 계속 재생, 비었고 디코딩 종료면 자연 종료 플래그 설정
+                    if DISCONNECT_LOGGED.compare_exchange(false, true, Ordering::Relaxed, Ordering::Relaxed).is_ok() {
+                        eprintln!("[rt] rx disconnected");
+                    }
+                    if sample_queue.is_empty() && rt_state.decoder_finished.load(Ordering::Relaxed) {
+                        rt_state.finished.store(true, Ordering::Relaxed);
+                    }
+                    break;
+                }
+            }
+        }
+        
+        // ✅ 디버그: 볼륨 확인 (처음 몇 번만, 모듈 스코프의 Atomic 사용)
+        let count = VOLUME_LOG_COUNT.fetch_add(1, Ordering::Relaxed);
+        if count < 3 {
+            eprintln!("[rt] volume={}, queue_len={}", volume, sample_queue.len());
+        }
+        
+        // ✅ 데이터 출력: 내부 채널 인터리브를 출력 시에만 장치 채널 구성에 맞게 배치
+        // 내부 데이터 구조: 기본 L, R, L, R, ... / 멀티채널 원본을 그대로 내보낼 때는 layout 순서 (앞의 두 채널은 항상 L, R)
+        // CPAL 출력: 채널 계수로 배치 (1채널→(L+R)/2, 2채널→LR, 6채널→앞쪽 LR + 선택적 upmix 또는 원본 채널 그대로)
+        // ✅ 핵심: 장치 채널 수와 무관하게 frame 단위로 내부 프레임을 정확히 1번만 pop
+        let frames = data.len() / channels;
+        let mut frames_popped = 0u64; // 실제 꺼낸 프레임 수 추적
+        let channel_matrix = &channel_matrices[rt_state.surround_upmix.load(Ordering::Relaxed) as usize];
+        let fade_target = f32::from_bits(rt_state.fade_gain.load(Ordering::Relaxed));
+        let tap_enabled = SPECTRUM_FEED_ENABLED.load(Ordering::Relaxed);
+        
+        for frame in 0..frames {
+            // 각 frame마다 내부 프레임을 정확히 1번만 pop
+            if !sample_queue.is_empty() {
+                // 프레임 일부만 남은 경우 나머지 채널은 마지막 샘플 사용
+                for (sample, last) in frame_samples.iter_mut().zip(last_frame.iter_mut()) {
+                    *sample = sample_queue.pop_front().unwrap_or(*last);
+                    *last = *sample;
+                }
+                frames_popped += 1;
+            } else {
+                // 버퍼 부족: 마지막 샘플 사용
+                frame_samples.copy_from_slice(&last_frame);
+            }
+            
+            // ✅ 앞쪽 LR에만 좌우 바꾸기 -> 모노 합산 -> 밸런스
+            let (l, r) = (frame_samples[0], frame_samples[1]);
+            let (l, r) = if swap_channels { (r, l) } else { (l, r) };
+            let (l, r) = if mono_sum {
+                let mono = (l + r) * 0.5;
+                (mono, mono)
+            } else {
+                (l, r)
+            };
+            let (l, r) = (l * balance_l, r * balance_r);
+            frame_samples[0] = l;
+            frame_samples[1] = r;
+            
+            // seek 페이드아웃 / 페이드인 gain
+            let ramp = if seek_pending {
+                1.0 - (frame + 1) as f32 / frames as f32
+            } else if fade_in_remaining > 0 {
+                fade_in_remaining -= 1;
+                1.0 - fade_in_remaining as f32 / seek_fade_frames as f32
+            } else {
+                1.0
+            };
+
+            if fade_gain != fade_target {
+                fade_gain = if fade_gain < fade_target {
+                    (fade_gain + fade_step).min(fade_target)
+                } else {
+                    (fade_gain - fade_step).max(fade_target)
+                };
+            }
+            if transport_gain != transport_target {
+                transport_gain = if transport_gain < transport_target {
+                    (transport_gain + transport_step).min(transport_target)
+                } else {
+                    (transport_gain - transport_step).max(transport_target)
+                };
+            }
+            // smoothstep 곡선 (램프 시작 / 끝의 기울기 변화를 부드럽게)
+            let transport_ramp = transport_gain * transport_gain * (3.0 - 2.0 * transport_gain);
+            let ramp = ramp * fade_gain * transport_ramp;
+
+            // 각 채널에 내부 채널 샘플 배치
+            for (ch, coefficients) in channel_matrix.iter().enumerate() {
+                let sample: f32 = coefficients.iter().zip(&frame_samples).map(|(c, s)| c * s).sum();
+                data[frame * channels + ch] = sample * volume * ramp;
+            }
+            if tap_enabled {
+                rt_state.analysis.push(tap_pos, l * volume * ramp, r * volume * ramp);
+                tap_pos += 1;
+            }
+        }
+        if tap_enabled {
+            rt_state.analysis.publish(tap_pos);
+        }
+        if seek_pending {
+            seek_faded_out = true;
+        }
+        rt_state.ramp_silent.store(transport_gain <= 0.0, Ordering::Release);
+        
+        // ✅ 프레임 기반으로 samples_played 업데이트 (채널 수와 무관)
+        // Atomic으로 빠른 업데이트 (Mutex 없음)
+        if frames_popped > 0 {
+            let frames_outputted = frames_popped;
+            let consumed = rt_state.frames_consumed.fetch_add(frames_outputted, Ordering::Relaxed) + frames_outputted;
+            if !seek_pending {
+                rt_state.listened_frames.fetch_add(frames_outputted, Ordering::Relaxed);
+            }
+            let boundary = rt_state.track_boundary.load(Ordering::Relaxed);
+            // ✅ 새 속도로 처리된 샘플이 출력되기 시작하면 위치 계산에 쓰는 속도 교체
+            let speed_boundary = rt_state.speed_boundary.load(Ordering::Relaxed);
+            if speed_boundary != NO_TRACK_BOUNDARY && consumed >= speed_boundary {
+                rt_state.speed.store(rt_state.next_speed.load(Ordering::Relaxed), Ordering::Relaxed);
+                rt_state.speed_boundary.store(NO_TRACK_BOUNDARY, Ordering::Relaxed);
+            }
+            let speed = f64::from_bits(rt_state.speed.load(Ordering::Relaxed));
+            if seek_pending {
+                // seek 대기 중: 재생 위치는 디코딩 스레드가 seek 위치로 설정
+            } else if boundary != NO_TRACK_BOUNDARY && consumed >= boundary {
+                // ✅ gapless 다음 곡 첫 샘플 출력: 재생 위치를 새 곡 기준으로 재설정
+                let offset = rt_state.next_track_offset.load(Ordering::Relaxed);
+                rt_state.samples_played.store(((consumed - boundary) as f64 * speed) as u64 + offset, Ordering::Relaxed);
+                rt_state.track_boundary.store(NO_TRACK_BOUNDARY, Ordering::Relaxed);
+                rt_state.track_switched.store(true, Ordering::Relaxed);
+            } else {
+                // 출력 프레임 수에 재생 속도를 곱해 원본 시간 기준으로 누적 (일시정지 램프 중 출력분 포함)
+                played_fract += frames_outputted as f64 * speed;
+                let advance = played_fract as u64;
+                played_fract -= advance as f64;
+                let previous = rt_state.samples_played.fetch_add(advance, Ordering::Relaxed);
+                // ✅ A-B 반복: B를 지나면 재생 위치를 A로 되감음 (디코딩 스레드가 같은 지점에서 A로 이어 붙임)
+                let loop_end = rt_state.loop_end.load(Ordering::Relaxed);
+                if loop_end != NO_TRACK_BOUNDARY && previous < loop_end && previous + advance >= loop_end {
+                    let loop_start = rt_state.loop_start.load(Ordering::Relaxed).min(loop_end);
+                    rt_state.samples_played.fetch_sub(loop_end - loop_start, Ordering::Relaxed);
+                }
+            }
+        }
+        frames_popped as usize
+    })
+}
+
+#[tauri::command]
+pub async fn pause_audio() -> Result<(), String> {
+    let rt_state_opt = {
+        let state_guard = PLAYER_STATE.lock().map_err(|e| format!("Lock error: {}", e))?;
+        if let Some(state) = state_guard.as_ref() {
+            let mut player_state = state.lock().map_err(|e| format!("Lock error: {}", e))?;
+            player_state.is_paused = true;
+            player_state.rt_state.clone()
+        } else {
+            None
+        }
+    };
+    // rt_state도 업데이트 (콜백이 페이드아웃을 마칠 때까지 기다린 뒤 반환)
+    if let Some(rt_state) = rt_state_opt {
+        rt_state.is_paused.store(true, Ordering::Relaxed);
+        wait_for_ramp_out(&rt_state).await;
+    }
+    Ok(())
+}
+
+// 일시정지 / 정지 램프가 끝나 출력이 무음이 될 때까지 대기 (콜백이 돌지 않으면 램프 길이 + 여유 시간 후 포기)
+// async 런타임 워커를 붙잡지 않도록 tokio sleep으로 기다림
+async fn wait_for_ramp_out(rt_state: &RtState) {
+    let timeout = Duration::from_millis(rt_state.transition_fade_ms.load(Ordering::Relaxed) as u64) + TRANSITION_FADE_WAIT_MARGIN;
+    let started = std::time::Instant::now();
+    while !rt_state.ramp_silent.load(Ordering::Acquire) && started.elapsed() < timeout {
+        tokio::time::sleep(Duration::from_millis(2)).await;
+    }
+}
+
+#[tauri::command]
+pub async fn resume_audio() -> Result<(), String> {
+    let current_file = {
+        let state_guard = PLAYER_STATE.lock().map_err(|e| format!("Lock error: {}", e))?;
+        if let Some(state) = state_guard.as_ref() {
+            let (rt_state_opt, current_file) = {
+                let mut player_state = state.lock().map_err(|e| format!("Lock error: {}", e))?;
+                player_state.is_paused = false;
+                player_state.is_playing = true;
+                (player_state.rt_state.clone(), player_state.current_file.clone())
+            };
+            // rt_state도 업데이트 (이미 끝났거나 멈추는 중인 스트림은 재생 기록 대상 아님)
+            match rt_state_opt {
+                Some(rt_state) => {
+                    rt_state.is_paused.store(false, Ordering::Relaxed);
+                    current_file.filter(|_| !rt_state.should_stop.load(Ordering::Relaxed) && !rt_state.finished.load(Ordering::Relaxed))
+                }
+                None => None,
+            }
+        } else {
+            None
+        }
+    };
+    // 기록 없이 열어 둔 곡(세션 복원 등)은 실제로 재생을 재개할 때 재생 기록 시작
+    if let Some(file_path) = current_file.filter(|file_path| !is_active_play(file_path)) {
+        record_play_start(&file_path);
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn stop_audio() -> Result<(), String> {
+    stop_playback().await?;
+    // 사용자가 멈춘 곡의 재생 기록 마무리 (재시작 / seek는 stop_playback만 사용해서 기록을 이어 씀)
+    finalize_active_play(None, false);
+    Ok(())
+}
+
+// 재생 중지 (재생 기록은 그대로 둠)
+async fn stop_playback() -> Result<(), String> {
+    // ✅ rt_state를 먼저 설정하여 콜백이 즉시 중지되도록 함
+    let (rt_state_opt, current_file) = {
+        let state_guard = PLAYER_STATE.lock().map_err(|e| format!("Lock error: {}", e))?;
+        if let Some(state) = state_guard.as_ref() {
+            let player_state = state.lock().map_err(|e| format!("Lock error: {}", e))?;
+            (player_state.rt_state.clone(), player_state.current_file.clone())
+        } else {
+            (None, None)
+        }
+    };
+    
+    // 위치 기억 곡이면 멈춘 위치 저장 (끝까지 재생된 곡은 재생 스레드가 지움)
+    if let (Some(rt_state), Some(file_path)) = (rt_state_opt.as_ref(), current_file) {
+        if !rt_state.should_stop.load(Ordering::Relaxed) && !rt_state.finished.load(Ordering::Relaxed) {
+            save_resume_position(&file_path, Some(rt_state.position_seconds()));
+        }
+    }
+    
+    if let Some(rt_state) = rt_state_opt {
+        // ✅ 먼저 페이드아웃 (클릭 방지), 무음이 된 뒤 정지
+        rt_state.stopping.store(true, Ordering::Relaxed);
+        wait_for_ramp_out(&rt_state).await;
+        rt_state.should_stop.store(true, Ordering::Relaxed);
+        rt_state.is_paused.store(false, Ordering::Relaxed);
+        rt_state.samples_played.store(0, Ordering::Relaxed);
+    }
+    
+    // ✅ 상태 업데이트 후 짧은 대기 (콜백이 should_stop을 확인할 시간)
+    // 주의: 이 sleep은 타이밍에 의존하는 안전망 역할
+    // 실제 동기화는 rt_state.should_stop + stream drop으로 보장됨
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    
+    let mut state_guard = PLAYER_STATE.lock().map_err(|e| format!("Lock error: {}", e))?;
+    if let Some(state) = state_guard.take() {
+        let mut player_state = state.lock().map_err(|e| format!("Lock error: {}", e))?;
+        player_state.is_playing = false;
+        player_state.is_paused = false;
+        player_state.samples_played = 0;
+        // rt_state.should_stop은 이미 위에서 설정됨
+    }
+    
+    Ok(())
+}
+
+// gapless로 이어서 재생할 다음 곡 등록 (None이면 등록 해제)
+// 디코딩 스레드가 현재 곡 EOF 전에 미리 열어서 같은 스트림에 이어 붙임
+#[tauri::command]
+pub async fn set_next_track(file_path: Option<String>) -> Result<(), String> {
+    let state_guard = PLAYER_STATE.lock().map_err(|e| format!("Lock error: {}", e))?;
+    if let Some(state) = state_guard.as_ref() {
+        let mut player_state = state.lock().map_err(|e| format!("Lock error: {}", e))?;
+        player_state.next_file = file_path;
+    }
+    Ok(())
+}
+
+// 대기열 변경 시 다음 곡 재등록 (재생 중인 곡이 current일 때만, 이미 넘어간 gapless 전환은 유지)
+// current가 None이면 대기열에 현재 곡이 없어졌으므로 (삭제 / 비우기) 등록된 다음 곡을 해제
+pub(crate) fn set_queue_next_file(current: Option<&str>, next: Option<String>) {
+    let Ok(state_guard) = PLAYER_STATE.lock() else {
+        return;
+    };
+    if let Some(state) = state_guard.as_ref() {
+        if let Ok(mut player_state) = state.lock() {
+            let matches_current = current.is_none_or(|current| player_state.current_file.as_deref() == Some(current));
+            if matches_current && player_state.pending_gapless_file.is_none() {
+                player_state.next_file = next;
+            }
+        }
+    }
+}
+
+// 현재 재생 볼륨 (재생 중이 아니면 저장된 볼륨)
+pub(crate) async fn current_playback_volume() -> f32 {
+    let playing_volume = PLAYER_STATE
+        .lock()
+        .ok()
+        .and_then(|guard| guard.as_ref().and_then(|state| state.lock().ok().map(|s| s.volume)));
+    match playing_volume {
+        Some(volume) => volume,
+        None => get_saved_volume().await.unwrap_or(1.0),
+    }
+}
+
+#[tauri::command]
+pub async fn seek_audio(app_handle: tauri::AppHandle, time: f64) -> Result<(), String> {
+    // ✅ 재생 스레드가 살아 있으면 디코딩 스레드에서 바로 seek (스트림/프리로딩 재생성 없음)
+    let rt_state_opt = {
+        let state_guard = PLAYER_STATE.lock().map_err(|e| format!("Lock error: {}", e))?;
+        match state_guard.as_ref() {
+            Some(state) => state.lock().map_err(|e| format!("Lock error: {}", e))?.rt_state.clone(),
+            None => None,
+        }
+    };
+    if let Some(rt_state) = rt_state_opt {
+        if !rt_state.should_stop.load(Ordering::Relaxed) && !rt_state.finished.load(Ordering::Relaxed) {
+            rt_state.request_seek(time);
+            // 디코딩 스레드가 요청을 처리할 때까지 잠깐 대기
+            for _ in 0..50 {
+                if !rt_state.seek_pending.load(Ordering::Acquire) {
+                    return Ok(());
+                }
+                thread::sleep(Duration::from_millis(10));
+            }
+            // 디코딩 스레드가 이미 종료된 경우: 재생을 다시 시작
+            rt_state.seek_pending.store(false, Ordering::Release);
+            eprintln!("Seek request was not handled by the decode thread, restarting playback");
+        }
+    }
+
+    restart_playback(app_handle, time).await
+}
+
+// 현재 곡을 새 스트림으로 다시 재생 (위치, 다음 곡, 일시정지 상태 유지)
+async fn restart_playback(app_handle: tauri::AppHandle, time: f64) -> Result<(), String> {
+    let file_path_volume_and_paused = {
+        let state_guard = PLAYER_STATE.lock().map_err(|e| format!("Lock error: {}", e))?;
+        if let Some(state) = state_guard.as_ref() {
+            let player_state = state.lock().map_err(|e| format!("Lock error: {}", e))?;
+            if let Some(file_path) = &player_state.current_file {
+                // 이미 디코딩이 넘어간 다음 곡도 다시 등록해야 gapless 전환이 유지됨
+                let next_file = player_state.pending_gapless_file.clone().or(player_state.next_file.clone());
+                Some((file_path.clone(), player_state.volume, player_state.is_paused, next_file, player_state.ab_loop))
+            } else {
+                None
+            }
+        } else {
+            None
+        }
+    };
+    
+    if let Some((file_path, volume, was_paused, next_file, ab_loop)) = file_path_volume_and_paused {
+        // 일시정지 상태를 유지하기 위해 재생 후에 다시 일시정지 (일시정지 중이면 재개할 때 기록)
+        start_playback(app_handle, file_path, volume, Some(time), !was_paused).await?;
+        if next_file.is_some() {
+            set_next_track(next_file).await?;
+        }
+        if ab_loop.is_some() {
+            update_ab_loop(ab_loop)?;
+        }
+        
+        // 일시정지 상태였으면 다시 일시정지
+        if was_paused {
+            pause_audio().await?;
+        }
+    }
+    Ok(())
+}
+
+// A-B 반복 구간 변경 (디코딩 스레드가 RtState.loop_version 변경을 보고 반영)
+fn update_ab_loop(ab_loop: Option<AbLoop>) -> Result<(), String> {
+    let state_guard = PLAYER_STATE.lock().map_err(|e| format!("Lock error: {}", e))?;
+    let state = state_guard.as_ref().ok_or_else(|| "No audio is playing".to_string())?;
+    let mut player_state = state.lock().map_err(|e| format!("Lock error: {}", e))?;
+    let rt_state = player_state.rt_state.clone().ok_or_else(|| "No audio is playing".to_string())?;
+    player_state.ab_loop = ab_loop;
+    rt_state.loop_version.fetch_add(1, Ordering::Release);
+    Ok(())
+}
+
+// 현재 곡의 A-B 반복 구간 설정 (현재 위치가 구간 밖이면 A부터 재생)
+#[tauri::command]
+pub async fn set_ab_loop(start: f64, end: f64) -> Result<AbLoop, String> {
+    if !start.is_finite() || !end.is_finite() || start < 0.0 || end - start < AB_LOOP_MIN_SECONDS {
+        return Err(format!("Invalid A-B loop range: {:.3}s - {:.3}s", start, end));
+    }
+    let ab_loop = AbLoop { start, end };
+    update_ab_loop(Some(ab_loop))?;
+    Ok(ab_loop)
+}
+
+#[tauri::command]
+pub async fn clear_ab_loop() -> Result<(), String> {
+    update_ab_loop(None).or(Ok(()))
+}
+
+#[tauri::command]
+pub async fn get_ab_loop() -> Result<Option<AbLoop>, String> {
+    let state_guard = PLAYER_STATE.lock().map_err(|e| format!("Lock error: {}", e))?;
+    match state_guard.as_ref() {
+        Some(state) => Ok(state.lock().map_err(|e| format!("Lock error: {}", e))?.ab_loop),
+        None => Ok(None),
+    }
+}
+
+// 재생 중인 곡의 현재 위치 (초, 재생 중이 아니면 None)
+fn current_position_seconds() -> Option<f64> {
+    let state_guard = PLAYER_STATE.lock().ok()?;
+    let state = state_guard.as_ref()?;
+    let rt_state = state.lock().ok()?.rt_state.clone()?;
+    if rt_state.should_stop.load(Ordering::Relaxed) || rt_state.finished.load(Ordering::Relaxed) {
+        return None;
+    }
+    if rt_state.output_sample_rate.load(Ordering::Relaxed) == 0 {
+        return None;
+    }
+    Some(rt_state.position_seconds())
+}
+
+// 현재 재생 상태 (웹뷰를 다시 불러왔을 때 오디오 스레드와 동기화용)
+#[tauri::command]
+pub async fn get_playback_status() -> Result<PlaybackStatus, String> {
+    let state_guard = PLAYER_STATE.lock().map_err(|e| format!("Lock error: {}", e))?;
+    let Some(state) = state_guard.as_ref() else {
+        return Ok(PlaybackStatus {
+            is_playing: false,
+            is_paused: false,
+            file_path: None,
+            next_file_path: None,
+            position: 0.0,
+            duration: None,
+            buffered: 0.0,
+            volume: 0.5,
+        });
+    };
+    let player_state = state.lock().map_err(|e| format!("Lock error: {}", e))?;
+    let active = player_state.rt_state.as_ref().filter(|rt_state| {
+        !rt_state.should_stop.load(Ordering::Relaxed) && !rt_state.finished.load(Ordering::Relaxed)
+    });
+    Ok(PlaybackStatus {
+        is_playing: active.is_some() && player_state.is_playing,
+        is_paused: active.map(|rt_state| rt_state.is_paused.load(Ordering::Relaxed)).unwrap_or(false),
+        file_path: player_state.current_file.clone(),
+        next_file_path: player_state.pending_gapless_file.clone().or(player_state.next_file.clone()),
+        position: active.map(|rt_state| rt_state.position_seconds()).unwrap_or(0.0),
+        duration: player_state.duration,
+        buffered: active.map(|rt_state| rt_state.buffered_seconds()).unwrap_or(0.0),
+        volume: player_state.volume,
+    })
+}
+
+fn sleep_timer_mode() -> Option<SleepTimerMode> {
+    SLEEP_TIMER.lock().ok().and_then(|timer| timer.as_ref().map(|timer| timer.mode))
+}
+
+// 현재 곡이 끝날 때까지 남은 실제 시간 (초, 재생 속도 반영)
+fn current_track_remaining_seconds() -> Option<f64> {
+    let state_guard = PLAYER_STATE.lock().ok()?;
+    let player_state = state_guard.as_ref()?.lock().ok()?;
+    let rt_state = player_state.rt_state.as_ref()?;
+    let duration = player_state.duration?;
+    let speed = f64::from_bits(rt_state.speed.load(Ordering::Relaxed)).max(0.01);
+    Some(((duration - rt_state.position_seconds()) / speed).max(0.0))
+}
+
+// 현재 곡 뒤에 이어질 곡이 등록되어 있는지 (gapless 다음 곡 또는 디코딩이 이미 넘어간 곡)
+fn has_upcoming_track() -> bool {
+    PLAYER_STATE
+        .lock()
+        .ok()
+        .and_then(|guard| {
+            let player_state = guard.as_ref()?.lock().ok()?;
+            Some(player_state.next_file.is_some() || player_state.pending_gapless_file.is_some())
+        })
+        .unwrap_or(false)
+}
+
+// 멈출 때까지 남은 시간 (곡 끝 기준 모드는 현재 곡이 마지막 곡일 때만 계산)
+fn sleep_timer_remaining(timer: &SleepTimer) -> Option<f64> {
+    match timer.mode {
+        SleepTimerMode::Minutes => timer
+            .deadline
+            .map(|deadline| deadline.saturating_duration_since(std::time::Instant::now()).as_secs_f64()),
+        SleepTimerMode::EndOfTrack => current_track_remaining_seconds(),
+        SleepTimerMode::EndOfQueue if !has_upcoming_track() => current_track_remaining_seconds(),
+        SleepTimerMode::EndOfQueue => None,
+    }
+}
+
+fn set_fade_gain(gain: f32) {
+    if let Ok(state_guard) = PLAYER_STATE.lock() {
+        if let Some(state) = state_guard.as_ref() {
+            if let Ok(player_state) = state.lock() {
+                if let Some(rt_state) = &player_state.rt_state {
+                    rt_state.fade_gain.store(gain.to_bits(), Ordering::Relaxed);
+                }
+            }
+        }
+    }
+}
+
+// 타이머 종료 처리 (타이머 해제 + 이벤트), 재생 중지는 호출하는 쪽에서
+fn fire_sleep_timer(app_handle: &tauri::AppHandle) {
+    let fired = SLEEP_TIMER.lock().ok().and_then(|mut timer| timer.take());
+    if let Some(timer) = fired {
+        SLEEP_TIMER_ID.fetch_add(1, Ordering::Relaxed);
+        let _ = app_handle.emit_all("sleep-timer-fired", SleepTimerFiredPayload { mode: timer.mode });
+    }
+}
+
+// 타이머 스레드: 남은 시간이 페이드 길이보다 짧아지면 볼륨을 줄이고, 시간이 다 되면 재생 중지
+// (곡 끝 기준 모드의 실제 중지는 재생 스레드가 곡이 끝나는 시점에 처리)
+fn run_sleep_timer(app_handle: tauri::AppHandle, id: u64) {
+    loop {
+        thread::sleep(SLEEP_TIMER_TICK);
+        let (remaining, mode, fade_seconds) = {
+            let Ok(timer) = SLEEP_TIMER.lock() else {
+                return;
+            };
+            match timer.as_ref() {
+                Some(timer) if timer.id == id => (sleep_timer_remaining(timer), timer.mode, timer.fade_seconds),
+                _ => return,
+            }
+        };
+
+        // 페이드 gain (진폭의 제곱 곡선이라 끝부분이 자연스럽게 작아짐)
+        let gain = match remaining {
+            Some(remaining) if fade_seconds > 0.0 && remaining < fade_seconds => {
+                let t = (remaining / fade_seconds) as f32;
+                t * t
+            }
+            _ => 1.0,
+        };
+        set_fade_gain(gain);
+
+        if mode == SleepTimerMode::Minutes && remaining.map(|r| r <= 0.0).unwrap_or(true) {
+            fire_sleep_timer(&app_handle);
+            if let Err(e) = tauri::async_runtime::block_on(stop_audio()) {
+                eprintln!("Sleep timer failed to stop playback: {}", e);
+            }
+            return;
+        }
+    }
+}
+
+fn load_sleep_fade_seconds() -> f64 {
+    get_connection()
+        .ok()
+        .and_then(|conn| read_setting(&conn, "sleep_timer_fade_seconds"))
+        .and_then(|value| value.parse::<f64>().ok())
+        .filter(|seconds| seconds.is_finite())
+        .unwrap_or(10.0)
+        .clamp(0.0, SLEEP_FADE_MAX_SECONDS)
+}
+
+// 슬립 타이머 설정 (기존 타이머는 교체)
+// minutes: Minutes 모드에서 필수, fade_seconds: 생략하면 마지막으로 쓴 값
+#[tauri::command]
+pub async fn set_sleep_timer(
+    app_handle: tauri::AppHandle,
+    mode: SleepTimerMode,
+    minutes: Option<f64>,
+    fade_seconds: Option<f64>,
+) -> Result<SleepTimerStatus, String> {
+    let deadline = match mode {
+        SleepTimerMode::Minutes => {
+            let minutes = minutes
+                .filter(|m| m.is_finite() && *m > 0.0)
+                .ok_or_else(|| "Sleep timer minutes must be greater than 0".to_string())?;
+            Some(std::time::Instant::now() + Duration::from_secs_f64(minutes * 60.0))
+        }
+        _ => None,
+    };
+    let fade_seconds = match fade_seconds {
+        Some(seconds) if seconds.is_finite() => {
+            let seconds = seconds.clamp(0.0, SLEEP_FADE_MAX_SECONDS);
+            let conn = get_connection()?;
+            write_setting(&conn, "sleep_timer_fade_seconds", &seconds.to_string())?;
+            seconds
+        }
+        _ => load_sleep_fade_seconds(),
+    };
+
+    let id = SLEEP_TIMER_ID.fetch_add(1, Ordering::Relaxed) + 1;
+    let timer = SleepTimer { id, mode, deadline, fade_seconds };
+    let status = SleepTimerStatus {
+        mode,
+        remaining_seconds: sleep_timer_remaining(&timer),
+        fade_seconds,
+    };
+    *SLEEP_TIMER.lock().map_err(|e| format!("Lock error: {}", e))? = Some(timer);
+    set_fade_gain(1.0);
+
+    // 곡 끝에서 멈추면 이미 등록된 gapless 다음 곡은 미리 열지 않음 (prepare_next_source가 확인)
+    thread::spawn(move || run_sleep_timer(app_handle, id));
+    Ok(status)
+}
+
+#[tauri::command]
+pub async fn get_sleep_timer() -> Result<Option<SleepTimerStatus>, String> {
+    let timer_guard = SLEEP_TIMER.lock().map_err(|e| format!("Lock error: {}", e))?;
+    Ok(timer_guard.as_ref().map(|timer| SleepTimerStatus {
+        mode: timer.mode,
+        remaining_seconds: sleep_timer_remaining(timer),
+        fade_seconds: timer.fade_seconds,
+    }))
 }
 
+// 타이머 취소 (페이드 중이었으면 볼륨 복원)
 #[tauri::command]
-pub async fn pause_audio() -> Result<(), String> {
-    let state_guard = PLAYER_STATE.lock().map_err(|e| format!("Lock error: {}", e))?;
-    if let Some(state) = state_guard.as_ref() {
-        let rt_state_opt = {
-            let mut player_state = state.lock().map_err(|e| format!("Lock error: {}", e))?;
-            player_state.is_paused = true;
-            player_state.rt_state.clone()
-        };
-        // rt_state도 업데이트
-        if let Some(rt_state) = rt_state_opt {
-            rt_state.is_paused.store(true, Ordering::Relaxed);
-        }
-    }
+pub async fn cancel_sleep_timer() -> Result<(), String> {
+    SLEEP_TIMER.lock().map_err(|e| format!("Lock error: {}", e))?.take();
+    SLEEP_TIMER_ID.fetch_add(1, Ordering::Relaxed);
+    set_fade_gain(1.0);
     Ok(())
 }
 
-#[tauri::command]
-pub async fn resume_audio() -> Result<(), String> {
-    let state_guard = PLAYER_STATE.lock().map_err(|e| format!("Lock error: {}", e))?;
-    if let Some(state) = state_guard.as_ref() {
-        let rt_state_opt = {
-            let mut player_state = state.lock().map_err(|e| format!("Lock error: {}", e))?;
-            player_state.is_paused = false;
-            player_state.is_playing = true;
-            player_state.rt_state.clone()
-        };
-        // rt_state도 업데이트
-        if let Some(rt_state) = rt_state_opt {
-            rt_state.is_paused.store(false, Ordering::Relaxed);
-        }
+// 재생 세션 저장 (settings 테이블)
+fn save_session(file_path: &str, position: f64) {
+    if let Ok(conn) = get_connection() {
+        let _ = write_setting(&conn, "session_file", file_path);
+        let _ = write_setting(&conn, "session_position", &format!("{:.3}", position.max(0.0)));
     }
-    Ok(())
 }
 
-#[tauri::command]
-pub async fn stop_audio() -> Result<(), String> {
-    // ✅ rt_state를 먼저 설정하여 콜백이 즉시 중지되도록 함
-    let rt_state_opt = {
-        let state_guard = PLAYER_STATE.lock().map_err(|e| format!("Lock error: {}", e))?;
-        if let Some(state) = state_guard.as_ref() {
-            state.lock().map_err(|e| format!("Lock error: {}", e))?.rt_state.clone()
-        } else {
-            None
+// 앱 종료 시 현재 재생 위치를 저장하고 재생 기록을 마무리 (main.rs의 RunEvent::Exit에서 호출)
+pub fn save_session_checkpoint() {
+    let session = PLAYER_STATE.lock().ok().and_then(|guard| {
+        let state = guard.as_ref()?;
+        let player_state = state.lock().ok()?;
+        let rt_state = player_state.rt_state.as_ref()?;
+        if rt_state.should_stop.load(Ordering::Relaxed) || rt_state.finished.load(Ordering::Relaxed) {
+            return None;
         }
-    };
-    
-    if let Some(rt_state) = rt_state_opt {
-        rt_state.should_stop.store(true, Ordering::Relaxed);
-        rt_state.is_paused.store(false, Ordering::Relaxed);
-        rt_state.samples_played.store(0, Ordering::Relaxed);
-    }
-    
-    // ✅ 상태 업데이트 후 짧은 대기 (콜백이 should_stop을 확인할 시간)
-    // 주의: 이 sleep은 타이밍에 의존하는 안전망 역할
-    // 실제 동기화는 rt_state.should_stop + stream drop으로 보장됨
-    thread::sleep(Duration::from_millis(50));
-    
-    let mut state_guard = PLAYER_STATE.lock().map_err(|e| format!("Lock error: {}", e))?;
-    if let Some(state) = state_guard.take() {
-        let mut player_state = state.lock().map_err(|e| format!("Lock error: {}", e))?;
-        player_state.is_playing = false;
-        player_state.is_paused = false;
-        player_state.samples_played = 0;
-        // rt_state.should_stop은 이미 위에서 설정됨
+        Some((player_state.current_file.clone()?, rt_state.position_seconds()))
+    });
+    if let Some((file_path, position)) = session {
+        save_session(&file_path, position);
+        save_resume_position(&file_path, Some(position));
     }
-    
-    Ok(())
+    // 종료 시 듣던 곡 기록은 중간에 넘긴 것으로 마무리
+    finalize_active_play(None, false);
 }
 
+// 마지막 재생 세션 복원: 같은 곡을 저장된 위치에서 일시정지 상태로 엶
+// (이미 재생 중이거나 저장된 곡 파일이 없으면 None)
 #[tauri::command]
-pub async fn seek_audio(app_handle: tauri::AppHandle, time: f64) -> Result<(), String> {
-    let file_path_volume_and_paused = {
-        let state_guard = PLAYER_STATE.lock().map_err(|e| format!("Lock error: {}", e))?;
-        if let Some(state) = state_guard.as_ref() {
-            let player_state = state.lock().map_err(|e| format!("Lock error: {}", e))?;
-            if let Some(file_path) = &player_state.current_file {
-                Some((file_path.clone(), player_state.volume, player_state.is_paused))
-            } else {
-                None
-            }
-        } else {
-            None
-        }
-    };
-    
-    if let Some((file_path, volume, was_paused)) = file_path_volume_and_paused {
-        // 일시정지 상태를 유지하기 위해 play_audio 후에 다시 일시정지
-        play_audio(app_handle, file_path, volume, Some(time)).await?;
-        
-        // 일시정지 상태였으면 다시 일시정지
-        if was_paused {
-            pause_audio().await?;
-        }
+pub async fn restore_session(app_handle: tauri::AppHandle) -> Result<Option<RestoredSession>, String> {
+    let already_playing = PLAYER_STATE
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?
+        .is_some();
+    if already_playing {
+        return Ok(None);
     }
-    Ok(())
+
+    let (file_path, position) = {
+        let conn = get_connection()?;
+        let file_path = read_setting(&conn, "session_file").filter(|path| !path.is_empty());
+        let position = read_setting(&conn, "session_position")
+            .and_then(|value| value.parse::<f64>().ok())
+            .filter(|position| position.is_finite())
+            .unwrap_or(0.0)
+            .max(0.0);
+        (file_path, position)
+    };
+    // CUE 가상 트랙은 원본 파일이 있는지 확인
+    let Some(file_path) = file_path.filter(|path| std::path::Path::new(&song_source_path(path)).exists()) else {
+        return Ok(None);
+    };
+
+    let queue = get_queue().await?;
+    let volume = get_saved_volume().await.unwrap_or(0.5);
+    // 복원은 재생 기록을 남기지 않음 (사용자가 재생을 재개하면 resume_audio에서 기록)
+    start_playback(app_handle, file_path.clone(), volume, Some(position), false).await?;
+    pause_audio().await?;
+    Ok(Some(RestoredSession { file_path, position, queue }))
 }
 
 // ✅ 볼륨 DB 저장용 debounce 타이머
@@ -1511,3 +3125,519 @@ pub async fn get_saved_volume() -> Result<f32, String> {
         Err(e) => Err(format!("Failed to get volume: {}", e)),
     }
 }
+
+// DB에 저장된 크로스페이드 설정 (없으면 크로스페이드 끔)
+fn load_crossfade_settings() -> CrossfadeSettings {
+    let Ok(conn) = get_connection() else {
+        return CrossfadeSettings::default();
+    };
+    let defaults = CrossfadeSettings::default();
+    let duration_secs = read_setting(&conn, "crossfade_duration")
+        .and_then(|value| value.parse::<f64>().ok())
+        .unwrap_or(defaults.duration_secs);
+    let curve = read_setting(&conn, "crossfade_curve")
+        .map(|value| CrossfadeCurve::from_setting(&value))
+        .unwrap_or(defaults.curve);
+    CrossfadeSettings { duration_secs, curve }.clamped()
+}
+
+#[tauri::command]
+pub async fn get_crossfade_settings() -> Result<CrossfadeSettings, String> {
+    Ok(load_crossfade_settings())
+}
+
+#[tauri::command]
+pub async fn set_crossfade_settings(duration_secs: f64, curve: String) -> Result<CrossfadeSettings, String> {
+    let settings = CrossfadeSettings {
+        duration_secs,
+        curve: CrossfadeCurve::from_setting(&curve),
+    }
+    .clamped();
+
+    let conn = get_connection()?;
+    write_setting(&conn, "crossfade_duration", &settings.duration_secs.to_string())?;
+    write_setting(&conn, "crossfade_curve", settings.curve.as_setting())?;
+
+    // ✅ 재생 중이면 디코딩 스레드에 즉시 반영 (다음 곡 전환부터 적용)
+    let state_guard = PLAYER_STATE.lock().map_err(|e| format!("Lock error: {}", e))?;
+    if let Some(state) = state_guard.as_ref() {
+        let mut player_state = state.lock().map_err(|e| format!("Lock error: {}", e))?;
+        player_state.crossfade = settings;
+    }
+
+    Ok(settings)
+}
+
+// DB에 저장된 재생 속도 / 음정 설정 (없으면 1배속, 음정 이동 없음)
+fn load_tempo_settings() -> TempoSettings {
+    let Ok(conn) = get_connection() else {
+        return TempoSettings::default();
+    };
+    let defaults = TempoSettings::default();
+    let speed = read_setting(&conn, "playback_speed")
+        .and_then(|value| value.parse::<f64>().ok())
+        .unwrap_or(defaults.speed);
+    let pitch_semitones = read_setting(&conn, "pitch_semitones")
+        .and_then(|value| value.parse::<f64>().ok())
+        .unwrap_or(defaults.pitch_semitones);
+    TempoSettings { speed, pitch_semitones }.clamped()
+}
+
+#[tauri::command]
+pub async fn get_tempo_settings() -> Result<TempoSettings, String> {
+    Ok(load_tempo_settings())
+}
+
+// 재생 속도(0.5~2.0배, 음정 유지)와 음정 이동(반음) 설정
+#[tauri::command]
+pub async fn set_tempo_settings(speed: f64, pitch_semitones: f64) -> Result<TempoSettings, String> {
+    let settings = TempoSettings { speed, pitch_semitones }.clamped();
+
+    let conn = get_connection()?;
+    write_setting(&conn, "playback_speed", &settings.speed.to_string())?;
+    write_setting(&conn, "pitch_semitones", &settings.pitch_semitones.to_string())?;
+
+    // ✅ 재생 중이면 디코딩 스레드에 즉시 반영
+    let state_guard = PLAYER_STATE.lock().map_err(|e| format!("Lock error: {}", e))?;
+    if let Some(state) = state_guard.as_ref() {
+        let mut player_state = state.lock().map_err(|e| format!("Lock error: {}", e))?;
+        player_state.tempo = settings;
+        if let Some(rt_state) = &player_state.rt_state {
+            rt_state.tempo_version.fetch_add(1, Ordering::Release);
+        }
+    }
+
+    Ok(settings)
+}
+
+// DB에 저장된 ReplayGain 설정 (없으면 끔)
+pub(crate) fn load_replaygain_settings() -> ReplayGainSettings {
+    let Ok(conn) = get_connection() else {
+        return ReplayGainSettings::default();
+    };
+    let defaults = ReplayGainSettings::default();
+    let mode = read_setting(&conn, "replaygain_mode")
+        .map(|value| ReplayGainMode::from_setting(&value))
+        .unwrap_or(defaults.mode);
+    let preamp_db = read_setting(&conn, "replaygain_preamp")
+        .and_then(|value| value.parse::<f64>().ok())
+        .unwrap_or(defaults.preamp_db);
+    let write_tags = read_setting(&conn, "replaygain_write_tags")
+        .map(|value| value == "true")
+        .unwrap_or(defaults.write_tags);
+    ReplayGainSettings { mode, preamp_db, write_tags }.clamped()
+}
+
+#[tauri::command]
+pub async fn get_replaygain_settings() -> Result<ReplayGainSettings, String> {
+    Ok(load_replaygain_settings())
+}
+
+#[tauri::command]
+pub async fn set_replaygain_settings(mode: String, preamp_db: f64, write_tags: bool) -> Result<ReplayGainSettings, String> {
+    let settings = ReplayGainSettings {
+        mode: ReplayGainMode::from_setting(&mode),
+        preamp_db,
+        write_tags,
+    }
+    .clamped();
+
+    let conn = get_connection()?;
+    write_setting(&conn, "replaygain_mode", settings.mode.as_setting())?;
+    write_setting(&conn, "replaygain_preamp", &settings.preamp_db.to_string())?;
+    write_setting(&conn, "replaygain_write_tags", if settings.write_tags { "true" } else { "false" })?;
+
+    // ✅ 재생 중이면 디코딩 스레드에 즉시 반영
+    let state_guard = PLAYER_STATE.lock().map_err(|e| format!("Lock error: {}", e))?;
+    if let Some(state) = state_guard.as_ref() {
+        let player_state = state.lock().map_err(|e| format!("Lock error: {}", e))?;
+        if let Some(rt_state) = &player_state.rt_state {
+            rt_state.set_replaygain(&settings);
+        }
+    }
+
+    Ok(settings)
+}
+
+// DB에 저장된 출력 장치 (빈 값이면 시스템 기본값)
+fn load_output_device_selection() -> OutputDeviceSelection {
+    let Ok(conn) = get_connection() else {
+        return OutputDeviceSelection::default();
+    };
+    let non_empty = |value: String| if value.is_empty() { None } else { Some(value) };
+    OutputDeviceSelection {
+        host: read_setting(&conn, "output_host").and_then(non_empty),
+        device: read_setting(&conn, "output_device").and_then(non_empty),
+    }
+}
+
+#[tauri::command]
+pub async fn list_output_devices() -> Result<Vec<OutputHostInfo>, String> {
+    Ok(list_output_hosts())
+}
+
+#[tauri::command]
+pub async fn get_output_device() -> Result<OutputDeviceSelection, String> {
+    Ok(load_output_device_selection())
+}
+
+// 출력 장치 선택 (host/device 모두 None이면 시스템 기본값)
+// 재생 중이면 현재 위치에서 새 장치로 스트림을 다시 엶
+#[tauri::command]
+pub async fn set_output_device(app_handle: tauri::AppHandle, host: Option<String>, device: Option<String>) -> Result<OutputDeviceSelection, String> {
+    let selection = OutputDeviceSelection { host, device };
+    if find_output_device(&selection).is_none() {
+        return Err(format!(
+            "Output device not found: {} / {}",
+            selection.host.as_deref().unwrap_or("default host"),
+            selection.device.as_deref().unwrap_or("default device")
+        ));
+    }
+
+    let conn = get_connection()?;
+    write_setting(&conn, "output_host", selection.host.as_deref().unwrap_or(""))?;
+    write_setting(&conn, "output_device", selection.device.as_deref().unwrap_or(""))?;
+    OUTPUT_FALLBACK_NOTIFIED.store(false, Ordering::Relaxed);
+
+    if let Some(position) = current_position_seconds() {
+        restart_playback(app_handle, position).await?;
+    }
+
+    Ok(selection)
+}
+
+// 출력 sink 설정 (settings 테이블에 JSON으로 저장, 기본값: cpal)
+fn load_audio_sink_settings() -> AudioSinkSettings {
+    get_connection()
+        .ok()
+        .and_then(|conn| read_setting(&conn, "audio_sink"))
+        .and_then(|value| serde_json::from_str::<AudioSinkSettings>(&value).ok())
+        .unwrap_or_default()
+        .clamped()
+}
+
+#[tauri::command]
+pub async fn get_audio_sink() -> Result<AudioSinkSettings, String> {
+    Ok(load_audio_sink_settings())
+}
+
+// 출력 sink 변경 (재생 중이면 현재 위치에서 새 출력으로 다시 엶)
+#[tauri::command]
+pub async fn set_audio_sink(
+    app_handle: tauri::AppHandle,
+    kind: AudioSinkKind,
+    wav_path: Option<String>,
+    sample_rate: Option<u32>,
+) -> Result<AudioSinkSettings, String> {
+    if kind == AudioSinkKind::Wav && wav_path.as_deref().map(str::trim).unwrap_or("").is_empty() {
+        return Err("WAV output path is required".to_string());
+    }
+    let settings = AudioSinkSettings {
+        kind,
+        wav_path,
+        sample_rate: sample_rate.unwrap_or(AudioSinkSettings::default().sample_rate),
+    }
+    .clamped();
+    let conn = get_connection()?;
+    let value = serde_json::to_string(&settings).map_err(|e| e.to_string())?;
+    write_setting(&conn, "audio_sink", &value)?;
+
+    if let Some(position) = current_position_seconds() {
+        restart_playback(app_handle, position).await?;
+    }
+    Ok(settings)
+}
+
+// 비트퍼펙트 모드 설정 (기본값: 끔)
+fn load_bit_perfect_setting() -> bool {
+    get_connection()
+        .ok()
+        .and_then(|conn| read_setting(&conn, "bit_perfect"))
+        .map(|value| value == "true")
+        .unwrap_or(false)
+}
+
+#[tauri::command]
+pub async fn get_bit_perfect_mode() -> Result<bool, String> {
+    Ok(load_bit_perfect_setting())
+}
+
+// 비트퍼펙트 모드 변경 (재생 중이면 현재 위치에서 스트림을 다시 엶)
+#[tauri::command]
+pub async fn set_bit_perfect_mode(app_handle: tauri::AppHandle, enabled: bool) -> Result<bool, String> {
+    let conn = get_connection()?;
+    write_setting(&conn, "bit_perfect", if enabled { "true" } else { "false" })?;
+
+    if let Some(position) = current_position_seconds() {
+        restart_playback(app_handle, position).await?;
+    }
+    Ok(enabled)
+}
+
+// 비주얼라이저 스펙트럼 피드 켜기 / 끄기 (켜져 있는 동안 audio-spectrum 이벤트 전송)
+#[tauri::command]
+pub async fn set_spectrum_feed(enabled: bool) -> Result<bool, String> {
+    SPECTRUM_FEED_ENABLED.store(enabled, Ordering::Relaxed);
+    if !enabled {
+        *SPECTRUM_SNAPSHOT.lock().map_err(|e| format!("Lock error: {}", e))? = None;
+    }
+    Ok(enabled)
+}
+
+// 마지막 분석 결과 (이벤트 대신 폴링할 때, 피드가 꺼져 있으면 None)
+#[tauri::command]
+pub async fn get_spectrum_snapshot() -> Result<Option<SpectrumFrame>, String> {
+    Ok(SPECTRUM_SNAPSHOT.lock().map_err(|e| format!("Lock error: {}", e))?.clone())
+}
+
+// 일시정지 / 재개 / 정지 / 곡 변경 램프 길이 (ms, 0이면 램프 없음)
+fn load_transition_fade_ms() -> u32 {
+    get_connection()
+        .ok()
+        .and_then(|conn| read_setting(&conn, "transition_fade_ms"))
+        .and_then(|value| value.parse::<u32>().ok())
+        .unwrap_or(TRANSITION_FADE_DEFAULT_MS)
+        .min(TRANSITION_FADE_MAX_MS)
+}
+
+#[tauri::command]
+pub async fn get_transition_fade_ms() -> Result<u32, String> {
+    Ok(load_transition_fade_ms())
+}
+
+// 램프 길이 변경 (재생 중이면 즉시 반영)
+#[tauri::command]
+pub async fn set_transition_fade_ms(ms: u32) -> Result<u32, String> {
+    let ms = ms.min(TRANSITION_FADE_MAX_MS);
+    let conn = get_connection()?;
+    write_setting(&conn, "transition_fade_ms", &ms.to_string())?;
+
+    let state_guard = PLAYER_STATE.lock().map_err(|e| format!("Lock error: {}", e))?;
+    if let Some(state) = state_guard.as_ref() {
+        let player_state = state.lock().map_err(|e| format!("Lock error: {}", e))?;
+        if let Some(rt_state) = &player_state.rt_state {
+            rt_state.transition_fade_ms.store(ms, Ordering::Relaxed);
+        }
+    }
+    Ok(ms)
+}
+
+// 재생할 수 없는 파일 건너뛰기 설정 (기본값: 켬)
+fn load_skip_unplayable_setting() -> bool {
+    get_connection()
+        .ok()
+        .and_then(|conn| read_setting(&conn, "skip_unplayable_files"))
+        .map(|value| value == "true")
+        .unwrap_or(true)
+}
+
+#[tauri::command]
+pub async fn get_skip_unplayable_files() -> Result<bool, String> {
+    Ok(load_skip_unplayable_setting())
+}
+
+#[tauri::command]
+pub async fn set_skip_unplayable_files(enabled: bool) -> Result<bool, String> {
+    let conn = get_connection()?;
+    write_setting(&conn, "skip_unplayable_files", if enabled { "true" } else { "false" })?;
+    Ok(enabled)
+}
+
+// 서라운드 upmix 설정 (기본값: 끔, 3채널 이상 장치에서만 의미 있음)
+fn load_surround_upmix_setting() -> bool {
+    get_connection()
+        .ok()
+        .and_then(|conn| read_setting(&conn, "surround_upmix"))
+        .map(|value| value == "true")
+        .unwrap_or(false)
+}
+
+#[tauri::command]
+pub async fn get_surround_upmix() -> Result<bool, String> {
+    Ok(load_surround_upmix_setting())
+}
+
+// 서라운드 upmix 변경 (재생 중이면 콜백에 즉시 반영)
+#[tauri::command]
+pub async fn set_surround_upmix(enabled: bool) -> Result<bool, String> {
+    let conn = get_connection()?;
+    write_setting(&conn, "surround_upmix", if enabled { "true" } else { "false" })?;
+
+    let state_guard = PLAYER_STATE.lock().map_err(|e| format!("Lock error: {}", e))?;
+    if let Some(state) = state_guard.as_ref() {
+        let player_state = state.lock().map_err(|e| format!("Lock error: {}", e))?;
+        if let Some(rt_state) = &player_state.rt_state {
+            rt_state.surround_upmix.store(enabled, Ordering::Relaxed);
+        }
+    }
+    Ok(enabled)
+}
+
+fn load_channel_options() -> ChannelOptions {
+    get_connection()
+        .ok()
+        .and_then(|conn| read_setting(&conn, "channel_options"))
+        .and_then(|value| serde_json::from_str::<ChannelOptions>(&value).ok())
+        .unwrap_or_default()
+        .clamped()
+}
+
+#[tauri::command]
+pub async fn get_channel_options() -> Result<ChannelOptions, String> {
+    Ok(load_channel_options())
+}
+
+// 밸런스 / 모노 / 좌우 바꾸기 변경 (재생 중이면 콜백에 즉시 반영)
+#[tauri::command]
+pub async fn set_channel_options(balance: f32, mono: bool, swap_channels: bool) -> Result<ChannelOptions, String> {
+    let options = ChannelOptions { balance, mono, swap_channels }.clamped();
+    let conn = get_connection()?;
+    let value = serde_json::to_string(&options).map_err(|e| e.to_string())?;
+    write_setting(&conn, "channel_options", &value)?;
+
+    let state_guard = PLAYER_STATE.lock().map_err(|e| format!("Lock error: {}", e))?;
+    if let Some(state) = state_guard.as_ref() {
+        let player_state = state.lock().map_err(|e| format!("Lock error: {}", e))?;
+        if let Some(rt_state) = &player_state.rt_state {
+            rt_state.set_channel_options(&options);
+        }
+    }
+    Ok(options)
+}
+
+// 재생 중이면 디코딩 스레드에 새 컴프레서/리미터 설정을 즉시 반영
+pub(crate) fn apply_live_dynamics_settings(settings: &DynamicsSettings) -> Result<(), String> {
+    let state_guard = PLAYER_STATE.lock().map_err(|e| format!("Lock error: {}", e))?;
+    if let Some(state) = state_guard.as_ref() {
+        let mut player_state = state.lock().map_err(|e| format!("Lock error: {}", e))?;
+        player_state.dynamics = *settings;
+        if let Some(rt_state) = &player_state.rt_state {
+            rt_state.dynamics_version.fetch_add(1, Ordering::Release);
+        }
+    }
+    Ok(())
+}
+
+// 재생 중이면 디코딩 스레드에 새 EQ 설정을 즉시 반영
+pub(crate) fn apply_live_eq_settings(settings: &EqSettings) -> Result<(), String> {
+    let state_guard = PLAYER_STATE.lock().map_err(|e| format!("Lock error: {}", e))?;
+    if let Some(state) = state_guard.as_ref() {
+        let mut player_state = state.lock().map_err(|e| format!("Lock error: {}", e))?;
+        player_state.eq = settings.clone();
+        if let Some(rt_state) = &player_state.rt_state {
+            rt_state.eq_version.fetch_add(1, Ordering::Release);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE_RATE: u32 = 44100;
+    const OUTPUT_RATE: u32 = 48000;
+    const SOURCE_FRAMES: usize = 22050; // 0.5초
+    const TONE_HZ: f64 = 1000.0;
+    const AMPLITUDE: f64 = 0.5;
+    const VOLUME: f32 = 0.5;
+
+    // 양쪽 채널에 같은 사인파가 들어 있는 16비트 PCM 스테레오 WAV
+    fn write_tone_wav(path: &std::path::Path) {
+        let data_len = (SOURCE_FRAMES * 2 * 2) as u32;
+        let mut bytes = Vec::with_capacity(44 + data_len as usize);
+        bytes.extend_from_slice(b"RIFF");
+        bytes.extend_from_slice(&(36 + data_len).to_le_bytes());
+        bytes.extend_from_slice(b"WAVEfmt ");
+        bytes.extend_from_slice(&16u32.to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes()); // PCM
+        bytes.extend_from_slice(&2u16.to_le_bytes());
+        bytes.extend_from_slice(&SOURCE_RATE.to_le_bytes());
+        bytes.extend_from_slice(&(SOURCE_RATE * 4).to_le_bytes());
+        bytes.extend_from_slice(&4u16.to_le_bytes());
+        bytes.extend_from_slice(&16u16.to_le_bytes());
+        bytes.extend_from_slice(b"data");
+        bytes.extend_from_slice(&data_len.to_le_bytes());
+        for frame in 0..SOURCE_FRAMES {
+            let phase = 2.0 * std::f64::consts::PI * TONE_HZ * frame as f64 / SOURCE_RATE as f64;
+            let sample = (AMPLITUDE * phase.sin() * i16::MAX as f64).round() as i16;
+            bytes.extend_from_slice(&sample.to_le_bytes());
+            bytes.extend_from_slice(&sample.to_le_bytes());
+        }
+        std::fs::write(path, bytes).unwrap();
+    }
+
+    // 디코딩 -> 리샘플링 -> 볼륨 파이프라인을 WAV 파일로 렌더링해서 길이 / 음량 / 주파수 비교
+    #[test]
+    fn pipeline_renders_resampled_tone_to_wav() {
+        let dir = std::env::temp_dir();
+        let input = dir.join(format!("lcmp-pipeline-input-{}.wav", std::process::id()));
+        let output = dir.join(format!("lcmp-pipeline-output-{}.wav", std::process::id()));
+        write_tone_wav(&input);
+
+        let source = DecodeSource::open(input.to_str().unwrap()).unwrap();
+        let layout = source.layout().to_vec();
+        assert_eq!(source.sample_rate, SOURCE_RATE);
+        let rt_state = Arc::new(RtState::new(VOLUME));
+        rt_state.output_sample_rate.store(OUTPUT_RATE, Ordering::Relaxed);
+        let state = Arc::new(Mutex::new(PlayerState {
+            is_playing: true,
+            volume: VOLUME,
+            rt_state: Some(rt_state.clone()),
+            // 리미터 look-ahead 지연(출력 앞에 붙는 무음)을 빼고 길이를 정확히 비교
+            dynamics: DynamicsSettings {
+                limiter_enabled: false,
+                ..DynamicsSettings::default()
+            },
+            ..PlayerState::default()
+        }));
+
+        let (tx, rx) = mpsc::sync_channel::<Vec<f32>>(256);
+        let decoder = {
+            let state = state.clone();
+            let rt_state = rt_state.clone();
+            thread::spawn(move || decode_thread(source, state, rt_state, tx, OUTPUT_RATE))
+        };
+        let mut sink = WavFileSink::create(output.to_str().unwrap(), OUTPUT_RATE, 2).unwrap();
+        let render = build_renderer(rx, rt_state.clone(), OUTPUT_RATE, sink.channels(), layout);
+        sink.start(render, Box::new(|| {})).unwrap();
+        let started = std::time::Instant::now();
+        while !rt_state.finished.load(Ordering::Relaxed) {
+            assert!(started.elapsed() < Duration::from_secs(10), "pipeline did not finish");
+            thread::sleep(Duration::from_millis(5));
+        }
+        // drop 시 헤더 길이 갱신
+        drop(sink);
+        rt_state.should_stop.store(true, Ordering::Relaxed);
+        decoder.join().unwrap();
+
+        let bytes = std::fs::read(&output).unwrap();
+        std::fs::remove_file(&input).ok();
+        std::fs::remove_file(&output).ok();
+        let samples: Vec<f32> = bytes[44..]
+            .chunks_exact(4)
+            .map(|chunk| f32::from_le_bytes(chunk.try_into().unwrap()))
+            .collect();
+
+        // 길이: 원본 0.5초가 출력 샘플레이트에서도 정확히 0.5초
+        let frames = samples.len() / 2;
+        assert_eq!(frames, SOURCE_FRAMES * OUTPUT_RATE as usize / SOURCE_RATE as usize);
+
+        // 시작 램프 / 리샘플러 가장자리를 뺀 가운데 구간 (0.1초 ~ 0.4초)
+        let steady = &samples[(OUTPUT_RATE as usize / 10) * 2..(OUTPUT_RATE as usize * 4 / 10) * 2];
+        let left: Vec<f32> = steady.iter().step_by(2).copied().collect();
+        for frame in steady.chunks_exact(2) {
+            assert!((frame[0] - frame[1]).abs() < 1e-6);
+        }
+
+        // 음량: 원본 진폭 * 볼륨
+        let peak = left.iter().fold(0.0f32, |peak, sample| peak.max(sample.abs()));
+        let expected_peak = AMPLITUDE as f32 * VOLUME;
+        assert!((peak - expected_peak).abs() < 0.005, "peak {} != {}", peak, expected_peak);
+
+        // 주파수: 상승 영점 교차 수 / 구간 길이 (리샘플링이 빠지면 1088Hz로 들림)
+        let crossings = left.windows(2).filter(|pair| pair[0] < 0.0 && pair[1] >= 0.0).count();
+        let frequency = crossings as f64 / (left.len() as f64 / OUTPUT_RATE as f64);
+        assert!((frequency - TONE_HZ).abs() < 10.0, "tone {}Hz != {}Hz", frequency, TONE_HZ);
+    }
+}
@@ -0,0 +1,502 @@
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use tauri::Manager;
+
+use crate::commands::player::{current_playback_volume, play_audio, set_queue_next_file};
+use crate::commands::settings::{read_setting, write_setting};
+use crate::database::get_connection;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RepeatMode {
+    Off,
+    All,
+    One,
+}
+
+impl RepeatMode {
+    pub fn from_setting(value: &str) -> Self {
+        match value {
+            "all" => RepeatMode::All,
+            "one" => RepeatMode::One,
+            _ => RepeatMode::Off,
+        }
+    }
+
+    pub fn as_setting(&self) -> &'static str {
+        match self {
+            RepeatMode::Off => "off",
+            RepeatMode::All => "all",
+            RepeatMode::One => "one",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueItem {
+    pub entry_id: i64, // 같은 곡이 여러 번 들어갈 수 있으므로 항목마다 고유 id
+    pub song_id: i64,
+    pub file_path: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueSnapshot {
+    pub items: Vec<QueueItem>,
+    pub current_index: Option<usize>,
+    pub shuffle: bool,
+    pub repeat: RepeatMode,
+}
+
+#[derive(Clone, Serialize)]
+struct QueueCurrentChangedPayload {
+    current_index: Option<usize>,
+    song_id: Option<i64>,
+    file_path: Option<String>,
+}
+
+// 재생 대기열 (items는 재생 순서, original은 셔플 전 순서의 entry_id 목록)
+struct PlayQueue {
+    items: Vec<QueueItem>,
+    original: Vec<i64>,
+    current_index: Option<usize>,
+    shuffle: bool,
+    repeat: RepeatMode,
+    next_entry_id: i64,
+}
+
+// 처음 접근할 때 DB에서 불러옴
+static PLAY_QUEUE: Mutex<Option<PlayQueue>> = Mutex::new(None);
+
+impl PlayQueue {
+    fn load() -> Self {
+        let mut queue = PlayQueue {
+            items: Vec::new(),
+            original: Vec::new(),
+            current_index: None,
+            shuffle: false,
+            repeat: RepeatMode::Off,
+            next_entry_id: 1,
+        };
+        let Ok(conn) = get_connection() else {
+            return queue;
+        };
+
+        // 라이브러리에서 삭제된 곡은 JOIN으로 자연스럽게 빠짐
+        let rows: Vec<(QueueItem, i64)> = conn
+            .prepare(
+                "SELECT q.entry_id, q.song_id, s.file_path, q.original_position
+                 FROM play_queue q JOIN songs s ON s.id = q.song_id
+                 ORDER BY q.position ASC",
+            )
+            .and_then(|mut stmt| {
+                stmt.query_map([], |row| {
+                    Ok((
+                        QueueItem {
+                            entry_id: row.get(0)?,
+                            song_id: row.get(1)?,
+                            file_path: row.get(2)?,
+                        },
+                        row.get(3)?,
+                    ))
+                })?
+                .collect()
+            })
+            .unwrap_or_default();
+
+        let mut original: Vec<(i64, i64)> = rows.iter().map(|(item, pos)| (*pos, item.entry_id)).collect();
+        original.sort();
+        queue.original = original.into_iter().map(|(_, entry_id)| entry_id).collect();
+        queue.items = rows.into_iter().map(|(item, _)| item).collect();
+        queue.next_entry_id = queue.items.iter().map(|item| item.entry_id).max().unwrap_or(0) + 1;
+        queue.current_index = read_setting(&conn, "queue_current_index")
+            .and_then(|value| value.parse::<usize>().ok())
+            .filter(|&index| index < queue.items.len());
+        queue.shuffle = read_setting(&conn, "queue_shuffle").map(|value| value == "true").unwrap_or(false);
+        queue.repeat = read_setting(&conn, "queue_repeat")
+            .map(|value| RepeatMode::from_setting(&value))
+            .unwrap_or(RepeatMode::Off);
+        queue
+    }
+
+    fn save(&self) -> Result<(), String> {
+        let mut conn = get_connection()?;
+        let tx = conn.transaction().map_err(|e| e.to_string())?;
+        tx.execute("DELETE FROM play_queue", []).map_err(|e| e.to_string())?;
+        {
+            let mut stmt = tx
+                .prepare("INSERT INTO play_queue (entry_id, song_id, position, original_position) VALUES (?1, ?2, ?3, ?4)")
+                .map_err(|e| e.to_string())?;
+            for (position, item) in self.items.iter().enumerate() {
+                let original_position = self
+                    .original
+                    .iter()
+                    .position(|&entry_id| entry_id == item.entry_id)
+                    .unwrap_or(position);
+                stmt.execute(params![item.entry_id, item.song_id, position as i64, original_position as i64])
+                    .map_err(|e| e.to_string())?;
+            }
+        }
+        let current_index = self.current_index.map(|index| index.to_string()).unwrap_or_default();
+        write_setting(&tx, "queue_current_index", &current_index)?;
+        write_setting(&tx, "queue_shuffle", if self.shuffle { "true" } else { "false" })?;
+        write_setting(&tx, "queue_repeat", self.repeat.as_setting())?;
+        tx.commit().map_err(|e| e.to_string())
+    }
+
+    fn snapshot(&self) -> QueueSnapshot {
+        QueueSnapshot {
+            items: self.items.clone(),
+            current_index: self.current_index,
+            shuffle: self.shuffle,
+            repeat: self.repeat,
+        }
+    }
+
+    fn current(&self) -> Option<&QueueItem> {
+        self.current_index.and_then(|index| self.items.get(index))
+    }
+
+    // 현재 곡이 끝나면 이어서 재생할 위치 (1곡 반복이면 같은 곡)
+    fn upcoming_index(&self) -> Option<usize> {
+        let current = self.current_index?;
+        match self.repeat {
+            RepeatMode::One => Some(current),
+            _ if current + 1 < self.items.len() => Some(current + 1),
+            RepeatMode::All if !self.items.is_empty() => Some(0),
+            _ => None,
+        }
+    }
+
+    fn new_items(&mut self, songs: Vec<(i64, String)>) -> Vec<QueueItem> {
+        songs
+            .into_iter()
+            .map(|(song_id, file_path)| {
+                let entry_id = self.next_entry_id;
+                self.next_entry_id += 1;
+                QueueItem { entry_id, song_id, file_path }
+            })
+            .collect()
+    }
+
+    // 셔플이 꺼져 있으면 셔플 전 순서 = 재생 순서
+    fn sync_original(&mut self) {
+        if !self.shuffle {
+            self.original = self.items.iter().map(|item| item.entry_id).collect();
+        }
+    }
+
+    fn remove_at(&mut self, index: usize) {
+        let removed = self.items.remove(index);
+        self.original.retain(|&entry_id| entry_id != removed.entry_id);
+        self.current_index = match self.current_index {
+            Some(current) if index < current => Some(current - 1),
+            Some(current) if index == current => None,
+            other => other,
+        };
+    }
+
+    // 현재 곡은 맨 앞에 두고 나머지를 섞음 (Fisher-Yates)
+    fn shuffle_items(&mut self) {
+        let current = self.current_index.map(|index| self.items.remove(index));
+        let mut rng = seed_rng();
+        for i in (1..self.items.len()).rev() {
+            let j = (next_random(&mut rng) % (i as u64 + 1)) as usize;
+            self.items.swap(i, j);
+        }
+        if let Some(current) = current {
+            self.items.insert(0, current);
+            self.current_index = Some(0);
+        }
+    }
+
+    // 셔플 전 순서로 복원 (현재 곡 위치는 따라감)
+    fn unshuffle_items(&mut self) {
+        let current_entry = self.current().map(|item| item.entry_id);
+        let original = &self.original;
+        self.items.sort_by_key(|item| original.iter().position(|&entry_id| entry_id == item.entry_id).unwrap_or(usize::MAX));
+        self.current_index = current_entry.and_then(|entry_id| self.items.iter().position(|item| item.entry_id == entry_id));
+    }
+}
+
+// 셔플용 xorshift 난수 (외부 crate 없이 현재 시각으로 시드)
+fn seed_rng() -> u64 {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0x9E37_79B9_7F4A_7C15);
+    nanos | 1
+}
+
+fn next_random(state: &mut u64) -> u64 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    *state
+}
+
+// 대기열 잠금 + 최초 로드 후 작업 실행
+fn with_queue<T>(f: impl FnOnce(&mut PlayQueue) -> T) -> Result<T, String> {
+    let mut guard = PLAY_QUEUE.lock().map_err(|e| format!("Lock error: {}", e))?;
+    let queue = guard.get_or_insert_with(PlayQueue::load);
+    Ok(f(queue))
+}
+
+// 대기열 변경 후 저장 + 재생 중인 곡이 대기열의 현재 곡이면 gapless 다음 곡을 다시 등록
+fn commit_queue(queue: &PlayQueue) -> Result<QueueSnapshot, String> {
+    queue.save()?;
+    if let Some(current) = queue.current() {
+        let upcoming = queue.upcoming_index().map(|index| queue.items[index].file_path.clone());
+        set_queue_next_file(Some(&current.file_path), upcoming);
+    }
+    Ok(queue.snapshot())
+}
+
+fn load_songs(song_ids: &[i64]) -> Result<Vec<(i64, String)>, String> {
+    let conn = get_connection()?;
+    let mut stmt = conn
+        .prepare("SELECT file_path FROM songs WHERE id = ?1")
+        .map_err(|e| e.to_string())?;
+    let mut songs = Vec::new();
+    for &song_id in song_ids {
+        match stmt.query_row([song_id], |row| row.get::<_, String>(0)) {
+            Ok(file_path) => songs.push((song_id, file_path)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => {}
+            Err(e) => return Err(e.to_string()),
+        }
+    }
+    Ok(songs)
+}
+
+fn emit_current_changed(app_handle: &tauri::AppHandle, queue: &PlayQueue) {
+    let current = queue.current();
+    let _ = app_handle.emit_all(
+        "queue-current-changed",
+        QueueCurrentChangedPayload {
+            current_index: queue.current_index,
+            song_id: current.map(|item| item.song_id),
+            file_path: current.map(|item| item.file_path.clone()),
+        },
+    );
+}
+
+// 재생을 시작한 곡이 대기열의 현재 곡이면 gapless로 이어질 다음 곡 (play_audio에서 호출)
+pub(crate) fn queue_upcoming_file(playing_file: &str) -> Option<String> {
+    with_queue(|queue| {
+        if queue.current()?.file_path != playing_file {
+            return None;
+        }
+        queue.upcoming_index().map(|index| queue.items[index].file_path.clone())
+    })
+    .ok()
+    .flatten()
+}
+
+// gapless 곡 전환이 실제로 출력되면 현재 위치를 옮기고 그다음 곡을 돌려줌
+pub(crate) fn queue_advance_on_switch(app_handle: &tauri::AppHandle, from_file: &str, to_file: &str) -> Option<String> {
+    with_queue(|queue| {
+        let upcoming = queue.upcoming_index()?;
+        if queue.current()?.file_path != from_file || queue.items[upcoming].file_path != to_file {
+            return None;
+        }
+        queue.current_index = Some(upcoming);
+        if let Err(e) = queue.save() {
+            eprintln!("Failed to save play queue: {}", e);
+        }
+        emit_current_changed(app_handle, queue);
+        queue.upcoming_index().map(|index| queue.items[index].file_path.clone())
+    })
+    .ok()
+    .flatten()
+}
+
+// gapless로 이어지지 못하고 곡이 끝났을 때 대기열의 다음 곡 (없으면 None)
+pub(crate) fn queue_advance_on_finish(app_handle: &tauri::AppHandle, finished_file: &str) -> Option<String> {
+    with_queue(|queue| {
+        if queue.current()?.file_path != finished_file {
+            return None;
+        }
+        let upcoming = queue.upcoming_index()?;
+        queue.current_index = Some(upcoming);
+        if let Err(e) = queue.save() {
+            eprintln!("Failed to save play queue: {}", e);
+        }
+        emit_current_changed(app_handle, queue);
+        Some(queue.items[upcoming].file_path.clone())
+    })
+    .ok()
+    .flatten()
+}
+
+async fn play_queue_item(app_handle: tauri::AppHandle, index: usize) -> Result<QueueSnapshot, String> {
+    let (file_path, snapshot) = with_queue(|queue| {
+        let item = queue.items.get(index).cloned().ok_or_else(|| format!("Queue index out of range: {}", index))?;
+        queue.current_index = Some(index);
+        queue.save()?;
+        emit_current_changed(&app_handle, queue);
+        Ok::<_, String>((item.file_path, queue.snapshot()))
+    })??;
+    let volume = current_playback_volume().await;
+    play_audio(app_handle, file_path, volume, None).await?;
+    Ok(snapshot)
+}
+
+#[tauri::command]
+pub async fn get_queue() -> Result<QueueSnapshot, String> {
+    with_queue(|queue| queue.snapshot())
+}
+
+// 대기열 끝에 추가
+#[tauri::command]
+pub async fn queue_add(song_ids: Vec<i64>) -> Result<QueueSnapshot, String> {
+    let songs = load_songs(&song_ids)?;
+    with_queue(|queue| {
+        let items = queue.new_items(songs);
+        queue.original.extend(items.iter().map(|item| item.entry_id));
+        queue.items.extend(items);
+        commit_queue(queue)
+    })?
+}
+
+// 현재 곡 바로 다음에 추가 (셔플 전 순서에서도 현재 곡 다음)
+#[tauri::command]
+pub async fn queue_play_next(song_ids: Vec<i64>) -> Result<QueueSnapshot, String> {
+    let songs = load_songs(&song_ids)?;
+    with_queue(|queue| {
+        let items = queue.new_items(songs);
+        let insert_at = queue.current_index.map(|index| index + 1).unwrap_or(0);
+        let original_at = queue
+            .current()
+            .and_then(|current| queue.original.iter().position(|&entry_id| entry_id == current.entry_id))
+            .map(|position| position + 1)
+            .unwrap_or(0);
+        for (offset, item) in items.into_iter().enumerate() {
+            queue.original.insert(original_at + offset, item.entry_id);
+            queue.items.insert(insert_at + offset, item);
+        }
+        commit_queue(queue)
+    })?
+}
+
+#[tauri::command]
+pub async fn queue_remove(index: usize) -> Result<QueueSnapshot, String> {
+    with_queue(|queue| {
+        if index >= queue.items.len() {
+            return Err(format!("Queue index out of range: {}", index));
+        }
+        let removed_current = queue.current_index == Some(index);
+        queue.remove_at(index);
+        // 재생 중인 곡을 지웠으면 대기열에서 등록해 둔 다음 곡도 해제
+        if removed_current {
+            set_queue_next_file(None, None);
+        }
+        commit_queue(queue)
+    })?
+}
+
+#[tauri::command]
+pub async fn queue_move(from: usize, to: usize) -> Result<QueueSnapshot, String> {
+    with_queue(|queue| {
+        if from >= queue.items.len() || to >= queue.items.len() {
+            return Err(format!("Queue index out of range: {} -> {}", from, to));
+        }
+        let item = queue.items.remove(from);
+        queue.items.insert(to, item);
+        queue.current_index = queue.current_index.map(|current| {
+            if current == from {
+                to
+            } else if from < current && to >= current {
+                current - 1
+            } else if from > current && to <= current {
+                current + 1
+            } else {
+                current
+            }
+        });
+        queue.sync_original();
+        commit_queue(queue)
+    })?
+}
+
+#[tauri::command]
+pub async fn queue_clear() -> Result<QueueSnapshot, String> {
+    with_queue(|queue| {
+        queue.items.clear();
+        queue.original.clear();
+        queue.current_index = None;
+        queue.save()?;
+        set_queue_next_file(None, None);
+        Ok(queue.snapshot())
+    })?
+}
+
+#[tauri::command]
+pub async fn set_queue_shuffle(enabled: bool) -> Result<QueueSnapshot, String> {
+    with_queue(|queue| {
+        if enabled != queue.shuffle {
+            if enabled {
+                queue.original = queue.items.iter().map(|item| item.entry_id).collect();
+                queue.shuffle_items();
+            } else {
+                queue.unshuffle_items();
+            }
+            queue.shuffle = enabled;
+        }
+        commit_queue(queue)
+    })?
+}
+
+// 반복 모드 ("off" / "all" / "one")
+#[tauri::command]
+pub async fn set_queue_repeat(mode: String) -> Result<QueueSnapshot, String> {
+    with_queue(|queue| {
+        queue.repeat = RepeatMode::from_setting(&mode);
+        commit_queue(queue)
+    })?
+}
+
+#[tauri::command]
+pub async fn play_queue_index(app_handle: tauri::AppHandle, index: usize) -> Result<QueueSnapshot, String> {
+    play_queue_item(app_handle, index).await
+}
+
+// 다음 곡 (1곡 반복이어도 다음 곡으로, 전체 반복이면 처음으로)
+#[tauri::command]
+pub async fn queue_next(app_handle: tauri::AppHandle) -> Result<QueueSnapshot, String> {
+    let target = with_queue(|queue| {
+        let current = queue.current_index?;
+        if current + 1 < queue.items.len() {
+            Some(current + 1)
+        } else if queue.repeat == RepeatMode::All && !queue.items.is_empty() {
+            Some(0)
+        } else {
+            None
+        }
+    })?;
+    match target {
+        Some(index) => play_queue_item(app_handle, index).await,
+        None => get_queue().await,
+    }
+}
+
+// 이전 곡 (전체 반복이면 처음에서 마지막으로)
+#[tauri::command]
+pub async fn queue_previous(app_handle: tauri::AppHandle) -> Result<QueueSnapshot, String> {
+    let target = with_queue(|queue| {
+        let current = queue.current_index?;
+        if current > 0 {
+            Some(current - 1)
+        } else if queue.repeat == RepeatMode::All && !queue.items.is_empty() {
+            Some(queue.items.len() - 1)
+        } else {
+            None
+        }
+    })?;
+    match target {
+        Some(index) => play_queue_item(app_handle, index).await,
+        None => get_queue().await,
+    }
+}
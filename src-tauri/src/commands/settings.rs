@@ -1,4 +1,21 @@
 use crate::database::get_connection;
+use rusqlite::Connection;
+
+// settings 테이블 단일 값 조회 (없거나 실패하면 None)
+pub(crate) fn read_setting(conn: &Connection, key: &str) -> Option<String> {
+    conn.query_row("SELECT value FROM settings WHERE key = ?1", [key], |row| row.get(0))
+        .ok()
+}
+
+// settings 테이블 단일 값 저장
+pub(crate) fn write_setting(conn: &Connection, key: &str, value: &str) -> Result<(), String> {
+    conn.execute(
+        "INSERT OR REPLACE INTO settings (key, value, updated_at) VALUES (?1, ?2, CURRENT_TIMESTAMP)",
+        [key, value],
+    )
+    .map_err(|e| format!("Failed to save setting {}: {}", key, e))?;
+    Ok(())
+}
 
 #[tauri::command]
 pub async fn get_table_columns() -> Result<Vec<String>, String> {
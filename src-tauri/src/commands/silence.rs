@@ -0,0 +1,187 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::path::Path;
+use std::thread;
+use rusqlite::{params, Connection};
+use serde::Serialize;
+
+use crate::audio::{detect_silence, DecodeSource, SilenceTrim, SkipSilenceSettings};
+use crate::commands::player::{extract_waveform, open_song_source, song_source_path};
+use crate::commands::settings::{read_setting, write_setting};
+use crate::database::get_connection;
+
+// 백그라운드 무음 검사가 돌고 있는지 (한 번에 하나만)
+static SILENCE_SCAN_RUNNING: AtomicBool = AtomicBool::new(false);
+
+// 파형 + 무음 건너뛰기 구간 (파형에서 건너뛰는 부분을 흐리게 표시하기 위함)
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SilenceWaveform {
+    pub waveform: Vec<f32>,
+    pub trim: SilenceTrim,
+    pub duration: Option<f64>,
+    pub leading_bins: usize,  // 앞에서부터 건너뛰는 파형 칸 수
+    pub trailing_bins: usize, // 뒤에서부터 건너뛰는 파형 칸 수
+}
+
+// DB에 저장된 무음 건너뛰기 설정 (settings 테이블에 JSON으로 저장, 없으면 끔)
+pub(crate) fn load_skip_silence_settings() -> SkipSilenceSettings {
+    get_connection()
+        .ok()
+        .and_then(|conn| read_setting(&conn, "skip_silence"))
+        .and_then(|value| serde_json::from_str::<SkipSilenceSettings>(&value).ok())
+        .unwrap_or_default()
+        .clamped()
+}
+
+#[tauri::command]
+pub async fn get_skip_silence_settings() -> Result<SkipSilenceSettings, String> {
+    Ok(load_skip_silence_settings())
+}
+
+// 무음 건너뛰기 설정 변경 (다음에 여는 곡부터 적용)
+#[tauri::command]
+pub async fn set_skip_silence_settings(enabled: bool, threshold_db: f64) -> Result<SkipSilenceSettings, String> {
+    let settings = SkipSilenceSettings { enabled, threshold_db }.clamped();
+    let conn = get_connection()?;
+    let value = serde_json::to_string(&settings).map_err(|e| e.to_string())?;
+    write_setting(&conn, "skip_silence", &value)?;
+    // 켜면 아직 검사하지 않은 곡을 미리 검사 (재생 시작 시 디코딩을 기다리지 않도록)
+    start_silence_scan(None);
+    Ok(settings)
+}
+
+// 같은 threshold로 검사해서 DB에 저장해 둔 무음 구간
+fn cached_silence_trim(conn: &Connection, file_path: &str, threshold_db: f64) -> Option<SilenceTrim> {
+    conn.query_row(
+        "SELECT silence_start, silence_end FROM songs
+         WHERE file_path = ?1 AND silence_start IS NOT NULL AND silence_threshold_db = ?2",
+        params![file_path, threshold_db],
+        |row| Ok(SilenceTrim { start: row.get(0)?, end: row.get(1)? }),
+    )
+    .ok()
+}
+
+// 곡의 무음 구간 (같은 threshold로 검사한 결과가 DB에 있으면 사용, 없으면 검사 후 저장)
+pub(crate) fn song_silence_trim(file_path: &str, threshold_db: f64) -> Result<SilenceTrim, String> {
+    let conn = get_connection()?;
+    if let Some(trim) = cached_silence_trim(&conn, file_path, threshold_db) {
+        return Ok(trim);
+    }
+
+    let mut source = open_song_source(file_path)?;
+    let trim = detect_silence(&mut source, threshold_db)?;
+    save_silence_trim(file_path, trim, threshold_db)?;
+    Ok(trim)
+}
+
+fn save_silence_trim(file_path: &str, trim: SilenceTrim, threshold_db: f64) -> Result<(), String> {
+    let conn = get_connection()?;
+    conn.execute(
+        "UPDATE songs SET silence_start = ?1, silence_end = ?2, silence_threshold_db = ?3 WHERE file_path = ?4",
+        params![trim.start, trim.end, threshold_db, file_path],
+    )
+    .map_err(|e| format!("Failed to save silence offsets: {}", e))?;
+    Ok(())
+}
+
+// 무음 건너뛰기가 켜져 있으면 재생용으로 연 곡의 앞/뒤 무음을 잘라냄
+// (재생 시작 경로에서는 검사하지 않음: 저장된 구간이 없으면 그대로 재생하고 백그라운드 검사 시작)
+pub(crate) fn apply_silence_trim(source: &mut DecodeSource) {
+    let settings = load_skip_silence_settings();
+    if !settings.enabled {
+        return;
+    }
+    let cached = get_connection()
+        .ok()
+        .and_then(|conn| cached_silence_trim(&conn, &source.file_path, settings.threshold_db));
+    let Some(trim) = cached else {
+        start_silence_scan(Some(&source.file_path));
+        return;
+    };
+    if let Err(e) = source.trim_silence(trim.start, trim.end) {
+        eprintln!("Failed to skip silence in {}: {}", source.file_path, e);
+    }
+}
+
+// 무음 구간이 없거나 다른 threshold로 검사된 라이브러리 곡을 백그라운드에서 검사 (설정이 켜져 있을 때만)
+// priority가 있으면 그 곡을 먼저 검사 (재생하다 구간이 없던 곡)
+pub(crate) fn start_silence_scan(priority: Option<&str>) {
+    let settings = load_skip_silence_settings();
+    if !settings.enabled || SILENCE_SCAN_RUNNING.swap(true, Ordering::AcqRel) {
+        return;
+    }
+    let priority = priority.unwrap_or_default().to_string();
+    thread::spawn(move || {
+        let threshold_db = settings.threshold_db;
+        // 시작할 때 대상 목록을 정해 두고 한 번씩만 검사 (열 수 없는 파일을 계속 다시 검사하지 않도록)
+        let file_paths = get_connection().and_then(|conn| songs_without_silence_trim(&conn, threshold_db, &priority));
+        match file_paths {
+            Ok(file_paths) => {
+                for file_path in file_paths {
+                    // 도중에 설정이 바뀌면 중단 (아래에서 새 설정으로 다시 시작)
+                    let current = load_skip_silence_settings();
+                    if !current.enabled || current.threshold_db != threshold_db {
+                        break;
+                    }
+                    // 없는 파일은 건너뜀 (나중에 다시 생기면 그때 검사)
+                    if !Path::new(&song_source_path(&file_path)).exists() {
+                        continue;
+                    }
+                    if let Err(e) = song_silence_trim(&file_path, threshold_db) {
+                        eprintln!("Failed to detect silence in {}: {}", file_path, e);
+                        // 디코딩할 수 없는 곡은 건너뛸 구간 없음으로 저장해 다시 검사하지 않음
+                        save_silence_trim(&file_path, SilenceTrim::NONE, threshold_db).ok();
+                    }
+                }
+            }
+            Err(e) => eprintln!("Failed to list songs for silence detection: {}", e),
+        }
+        SILENCE_SCAN_RUNNING.store(false, Ordering::Release);
+
+        let current = load_skip_silence_settings();
+        if current.enabled && current.threshold_db != threshold_db {
+            start_silence_scan(None);
+        }
+    });
+}
+
+fn songs_without_silence_trim(conn: &Connection, threshold_db: f64, priority: &str) -> Result<Vec<String>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT file_path FROM songs
+             WHERE silence_start IS NULL OR silence_threshold_db IS NULL OR silence_threshold_db != ?1
+             ORDER BY file_path = ?2 DESC, title ASC",
+        )
+        .map_err(|e| format!("Failed to prepare silence query: {}", e))?;
+    let rows = stmt
+        .query_map(params![threshold_db, priority], |row| row.get::<_, String>(0))
+        .map_err(|e| format!("Failed to query silence targets: {}", e))?;
+    Ok(rows.filter_map(|row| row.ok()).collect())
+}
+
+// extract_waveform 결과에 무음 건너뛰기 구간을 함께 반환 (설정이 꺼져 있어도 현재 threshold 기준 구간)
+#[tauri::command]
+pub async fn extract_waveform_with_silence(file_path: String, samples: usize) -> Result<SilenceWaveform, String> {
+    let waveform = extract_waveform(file_path.clone(), samples).await?;
+    let settings = load_skip_silence_settings();
+    let trim = song_silence_trim(&file_path, settings.threshold_db)?;
+    let duration = open_song_source(&file_path)?.duration;
+
+    let bins = waveform.len();
+    let to_bins = |seconds: f64| match duration {
+        Some(duration) if duration > 0.0 => ((seconds / duration) * bins as f64).floor().clamp(0.0, bins as f64) as usize,
+        _ => 0,
+    };
+    let leading_bins = to_bins(trim.start);
+    let trailing_bins = match (trim.end, duration) {
+        (Some(end), Some(duration)) => to_bins(duration - end).min(bins - leading_bins),
+        _ => 0,
+    };
+    Ok(SilenceWaveform {
+        waveform,
+        trim,
+        duration,
+        leading_bins,
+        trailing_bins,
+    })
+}
@@ -1,7 +1,8 @@
 ﻿use crate::database::get_connection;
 use crate::models::Song;
 use crate::commands::folder::scan_folder_for_songs;
-use crate::commands::player::{extract_metadata, extract_waveform};
+use crate::commands::player::{extract_metadata, extract_waveform, resolve_cue_track, song_source_path};
+use crate::audio::{ReplayGainInfo, REPLAYGAIN_REFERENCE_LUFS};
 use rusqlite::{Result, params};
 use serde::{Deserialize, Serialize};
 use id3::TagLike;
@@ -30,6 +31,9 @@ pub struct CachePruneResult {
     pub remaining_bytes: u64,
 }
 
+// get_song_metadata_details의 DB 조회 결과 (file_path, title, artist, album, year, genre)
+type SongDetailsRow = (String, Option<String>, Option<String>, Option<String>, Option<i32>, Option<String>);
+
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SongMetadataDetails {
@@ -262,27 +266,30 @@ fn get_file_mtime_seconds(file_path: &str) -> Option<u64> {
 }
 
 fn merge_file_metadata(song: &mut Song) -> Vec<String> {
-    if !Path::new(&song.file_path).exists() {
+    // CUE 가상 트랙은 원본 파일을 읽되, 곡마다 다른 제목/아티스트/길이는 DB(CUE 시트) 값을 유지
+    let is_cue_track = resolve_cue_track(&song.file_path).is_some();
+    let source_path = song_source_path(&song.file_path);
+    if !Path::new(&source_path).exists() {
         return Vec::new();
     }
-    let mtime = match get_file_mtime_seconds(&song.file_path) {
+    let mtime = match get_file_mtime_seconds(&source_path) {
         Some(value) => value,
         None => return Vec::new(),
     };
 
     let cached = {
         let cache = metadata_cache().lock().ok();
-        cache.and_then(|map| map.get(&song.file_path).cloned())
+        cache.and_then(|map| map.get(&source_path).cloned())
     };
 
     let (title, artist, album, year, genre, duration, tags) = if let Some((cached_mtime, cached_meta)) = cached {
         if cached_mtime == mtime {
             cached_meta
         } else {
-            let extracted = extract_metadata(&song.file_path).ok();
+            let extracted = extract_metadata(&source_path).ok();
             if let Some(meta) = extracted {
                 if let Ok(mut map) = metadata_cache().lock() {
-                    map.insert(song.file_path.clone(), (mtime, meta.clone()));
+                    map.insert(source_path.clone(), (mtime, meta.clone()));
                 }
                 meta
             } else {
@@ -290,10 +297,10 @@ fn merge_file_metadata(song: &mut Song) -> Vec<String> {
             }
         }
     } else {
-        let extracted = extract_metadata(&song.file_path).ok();
+        let extracted = extract_metadata(&source_path).ok();
         if let Some(meta) = extracted {
             if let Ok(mut map) = metadata_cache().lock() {
-                map.insert(song.file_path.clone(), (mtime, meta.clone()));
+                map.insert(source_path.clone(), (mtime, meta.clone()));
             }
             meta
         } else {
@@ -301,6 +308,19 @@ fn merge_file_metadata(song: &mut Song) -> Vec<String> {
         }
     };
 
+    if is_cue_track {
+        // 앨범 단위 값은 CUE 시트에 없을 때만 파일 값으로 채움
+        if song.album.is_none() {
+            song.album = album;
+        }
+        if song.year.is_none() {
+            song.year = year;
+        }
+        if song.genre.is_none() {
+            song.genre = genre;
+        }
+        return tags;
+    }
     if title.is_some() {
         song.title = title;
     }
@@ -715,6 +735,84 @@ fn update_flac_tags_only(file_path: &str, tags: &[String]) -> Result<(), String>
     Ok(())
 }
 
+// REPLAYGAIN_* 태그 값 (키, 값) 목록
+fn replaygain_tag_values(info: &ReplayGainInfo) -> Vec<(&'static str, String)> {
+    let mut values = vec![
+        ("REPLAYGAIN_TRACK_GAIN", format!("{:.2} dB", info.track_gain_db())),
+        ("REPLAYGAIN_TRACK_PEAK", format!("{:.6}", info.track_peak)),
+    ];
+    if let (Some(gain), Some(peak)) = (info.album_gain_db(), info.album_peak) {
+        values.push(("REPLAYGAIN_ALBUM_GAIN", format!("{:.2} dB", gain)));
+        values.push(("REPLAYGAIN_ALBUM_PEAK", format!("{:.6}", peak)));
+    }
+    values.push(("REPLAYGAIN_REFERENCE_LOUDNESS", format!("{:.2} LUFS", REPLAYGAIN_REFERENCE_LUFS)));
+    values
+}
+
+fn update_mp3_replaygain(file_path: &str, info: &ReplayGainInfo) -> Result<(), String> {
+    use id3::frame::{Content, ExtendedText, Frame};
+    use id3::Tag;
+
+    let mut tag = Tag::read_from_path(file_path).unwrap_or_else(|_| Tag::new());
+
+    // 기존 REPLAYGAIN_* TXXX만 교체 (대소문자 무관), 나머지 TXXX는 유지
+    let existing_ext: Vec<(String, String)> = tag
+        .extended_texts()
+        .filter(|text| !text.description.to_uppercase().starts_with("REPLAYGAIN_"))
+        .map(|text| (text.description.to_string(), text.value.to_string()))
+        .collect();
+    tag.remove("TXXX");
+    for (description, value) in existing_ext {
+        tag.add_frame(Frame::with_content(
+            "TXXX",
+            Content::ExtendedText(ExtendedText { description, value }),
+        ));
+    }
+    for (description, value) in replaygain_tag_values(info) {
+        tag.add_frame(Frame::with_content(
+            "TXXX",
+            Content::ExtendedText(ExtendedText {
+                description: description.to_string(),
+                value,
+            }),
+        ));
+    }
+
+    tag.write_to_path(file_path, id3::Version::Id3v24)
+        .map_err(|e| format!("Failed to write ID3 tag: {}", e))?;
+
+    Ok(())
+}
+
+fn update_flac_replaygain(file_path: &str, info: &ReplayGainInfo) -> Result<(), String> {
+    let mut tag = metaflac::Tag::read_from_path(file_path)
+        .map_err(|e| format!("Failed to read FLAC tag: {}", e))?;
+    {
+        let vorbis = tag.vorbis_comments_mut();
+        vorbis.comments.retain(|key, _| !key.to_uppercase().starts_with("REPLAYGAIN_"));
+        for (key, value) in replaygain_tag_values(info) {
+            vorbis.comments.insert(key.to_string(), vec![value]);
+        }
+    }
+    tag.write_to_path(file_path)
+        .map_err(|e| format!("Failed to write FLAC tag: {}", e))?;
+    Ok(())
+}
+
+// 라우드니스 분석 결과를 파일 태그(REPLAYGAIN_*)로 기록 (mp3/flac만 지원)
+pub(crate) fn write_replaygain_tags(file_path: &str, info: &ReplayGainInfo) -> Result<(), String> {
+    let extension = Path::new(file_path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|s| s.to_lowercase());
+
+    match extension.as_deref() {
+        Some("mp3") => update_mp3_replaygain(file_path, info),
+        Some("flac") => update_flac_replaygain(file_path, info),
+        _ => Ok(()),
+    }
+}
+
 fn pick_album_art_path(cache_root: &Path, cache_key: &str) -> Option<PathBuf> {
     let extensions = ["jpg", "jpeg", "png", "webp", "bmp"];
     for ext in extensions {
@@ -781,6 +879,9 @@ fn get_cache_root() -> Option<PathBuf> {
 }
 
 fn resolve_album_art_cache_path(file_path: &str) -> Result<Option<String>, String> {
+    // CUE 가상 트랙은 원본 파일의 앨범아트 공유
+    let source_path = song_source_path(file_path);
+    let file_path = source_path.as_str();
     if !Path::new(file_path).exists() {
         return Ok(None);
     }
@@ -832,6 +933,7 @@ pub async fn open_song_location(file_path: String) -> Result<(), String> {
     if file_path.trim().is_empty() {
         return Err("File path is empty".to_string());
     }
+    let file_path = song_source_path(&file_path);
     if cfg!(target_os = "windows") {
         let normalized = file_path.replace("/", "\\");
         Command::new("explorer")
@@ -1048,8 +1150,8 @@ pub(crate) fn generate_waveforms_for_songs_without_waveform(conn: &rusqlite::Con
         *GENERATING_WAVEFORM_SONG_ID.lock().unwrap() = Some(song_id);
         
         thread::spawn(move || {
-            // 파일 존재 확인
-            if Path::new(&file_path).exists() {
+            // 파일 존재 확인 (CUE 가상 트랙은 원본 파일)
+            if Path::new(&song_source_path(&file_path)).exists() {
                 // ?⑥씠??異붿텧
                 match tokio::runtime::Runtime::new() {
                     Ok(rt) => {
@@ -1306,7 +1408,8 @@ pub async fn get_file_sizes(file_paths: Vec<String>) -> Result<Vec<(String, u64)
     let mut results = Vec::new();
     
     for file_path in file_paths {
-        match fs::metadata(&file_path) {
+        // CUE 가상 트랙은 원본 파일 크기
+        match fs::metadata(song_source_path(&file_path)) {
             Ok(metadata) => {
                 results.push((file_path, metadata.len()));
             }
@@ -1476,13 +1579,16 @@ fn read_flac_metadata_details(file_path: &str) -> SongMetadataDetails {
 #[tauri::command]
 pub async fn get_song_metadata_details(song_id: i64) -> Result<SongMetadataDetails, String> {
     let conn = get_connection().map_err(|e| e.to_string())?;
-    let file_path: String = conn
+    let (song_path, db_title, db_artist, db_album, db_year, db_genre): SongDetailsRow = conn
         .query_row(
-            "SELECT file_path FROM songs WHERE id = ?1",
+            "SELECT file_path, title, artist, album, year, genre FROM songs WHERE id = ?1",
             [song_id],
-            |row| row.get(0),
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?)),
         )
         .map_err(|e| format!("Failed to load song path: {}", e))?;
+    // CUE 가상 트랙은 원본 파일에서 읽고 곡 단위 값은 DB(CUE 시트) 값으로 덮어씀
+    let is_cue_track = resolve_cue_track(&song_path).is_some();
+    let file_path = song_source_path(&song_path);
 
     if !Path::new(&file_path).exists() {
         return Ok(SongMetadataDetails {
@@ -1514,7 +1620,7 @@ pub async fn get_song_metadata_details(song_id: i64) -> Result<SongMetadataDetai
         .map(|ext| ext.to_lowercase())
         .unwrap_or_default();
 
-    let details = match extension.as_str() {
+    let mut details = match extension.as_str() {
         "mp3" => read_mp3_metadata_details(&file_path),
         "flac" => read_flac_metadata_details(&file_path),
         _ => SongMetadataDetails {
@@ -1540,6 +1646,15 @@ pub async fn get_song_metadata_details(song_id: i64) -> Result<SongMetadataDetai
         },
     };
 
+    if is_cue_track {
+        details.title = db_title;
+        details.artist = db_artist.or(details.artist);
+        details.album = db_album.or(details.album);
+        details.year = db_year.or(details.year);
+        details.genre = db_genre.or(details.genre);
+        details.track_number = song_path.rsplit_once("#track").and_then(|(_, number)| number.parse().ok());
+    }
+
     Ok(details)
 }
 
@@ -1555,7 +1670,9 @@ pub async fn update_song_metadata(payload: UpdateSongMetadataPayload) -> Result<
         )
         .map_err(|e| format!("Failed to load song path: {}", e))?;
     
-    if !Path::new(&file_path).exists() {
+    // CUE 가상 트랙은 원본 파일을 여러 곡이 공유하므로 파일에 쓰지 않고 DB에만 저장
+    let is_cue_track = resolve_cue_track(&file_path).is_some();
+    if !is_cue_track && !Path::new(&file_path).exists() {
         return Err("파일이 존재하지 않습니다.".to_string());
     }
     
@@ -1582,9 +1699,9 @@ pub async fn update_song_metadata(payload: UpdateSongMetadataPayload) -> Result<
         .and_then(|ext| ext.to_str())
         .map(|s| s.to_lowercase());
 
-    let ext = extension.as_deref().unwrap_or("");
+    let ext = if is_cue_track { "" } else { extension.as_deref().unwrap_or("") };
     let supports_file_metadata = matches!(ext, "mp3" | "flac");
-    if !supports_file_metadata {
+    if !is_cue_track && !supports_file_metadata {
         return Err(format!(
             "지원하지 않는 파일 확장자입니다. 파일 메타데이터는 mp3/flac만 지원합니다. (현재: {})",
             if ext.is_empty() { "확장자 없음" } else { ext }
@@ -1705,7 +1822,9 @@ pub async fn update_song_tags(payload: UpdateSongTagsPayload) -> Result<Song, St
         )
         .map_err(|e| format!("Failed to load song path: {}", e))?;
 
-    if !Path::new(&file_path).exists() {
+    // CUE 가상 트랙은 원본 파일을 여러 곡이 공유하므로 파일에 쓰지 않고 DB에만 저장
+    let is_cue_track = resolve_cue_track(&file_path).is_some();
+    if !is_cue_track && !Path::new(&file_path).exists() {
         return Err("파일이 존재하지 않습니다.".to_string());
     }
 
@@ -1717,6 +1836,7 @@ pub async fn update_song_tags(payload: UpdateSongTagsPayload) -> Result<Song, St
         .map(|s| s.to_lowercase());
     let ext = extension.as_deref().unwrap_or("");
     match ext {
+        _ if is_cue_track => {}
         "mp3" => {
             update_mp3_tags_only(&file_path, &normalized)?;
         }
@@ -78,6 +78,58 @@ pub fn run_migrations(conn: &Connection) -> Result<()> {
         [],
     ).ok(); // 이미 존재하면 무시
 
+    // songs 테이블에 라우드니스(ReplayGain) 분석 결과 컬럼 추가 (마이그레이션)
+    for column in [
+        "loudness_track_lufs REAL",
+        "loudness_track_peak REAL",
+        "loudness_album_lufs REAL",
+        "loudness_album_peak REAL",
+    ] {
+        conn.execute(
+            &format!("ALTER TABLE songs ADD COLUMN {}", column),
+            [],
+        ).ok(); // 이미 존재하면 무시
+    }
+
+    // songs 테이블에 CUE 가상 트랙 컬럼 추가 (마이그레이션)
+    // 가상 트랙은 cue_source_path 파일의 [cue_start, cue_end) 구간 (cue_end가 NULL이면 파일 끝까지)
+    for column in [
+        "cue_source_path TEXT",
+        "cue_start REAL",
+        "cue_end REAL",
+    ] {
+        conn.execute(
+            &format!("ALTER TABLE songs ADD COLUMN {}", column),
+            [],
+        ).ok(); // 이미 존재하면 무시
+    }
+
+    // songs 테이블에 챕터 / 이어듣기 컬럼 추가 (마이그레이션)
+    // chapters_scanned: 챕터를 한 번 읽었으면 1, remember_position: 정지 시 위치 기억 여부
+    for column in [
+        "chapters_scanned INTEGER DEFAULT 0",
+        "remember_position INTEGER DEFAULT 0",
+        "resume_position REAL",
+    ] {
+        conn.execute(
+            &format!("ALTER TABLE songs ADD COLUMN {}", column),
+            [],
+        ).ok(); // 이미 존재하면 무시
+    }
+
+    // songs 테이블에 무음 건너뛰기 구간 컬럼 추가 (마이그레이션)
+    // silence_threshold_db로 검사한 앞/뒤 무음 위치 (초), threshold가 바뀌면 다시 검사
+    for column in [
+        "silence_start REAL",
+        "silence_end REAL",
+        "silence_threshold_db REAL",
+    ] {
+        conn.execute(
+            &format!("ALTER TABLE songs ADD COLUMN {}", column),
+            [],
+        ).ok(); // 이미 존재하면 무시
+    }
+
     // playlists 테이블
     conn.execute(
         "CREATE TABLE IF NOT EXISTS playlists (
@@ -125,6 +177,21 @@ pub fn run_migrations(conn: &Connection) -> Result<()> {
         [],
     )?;
 
+    // play_history에 완료 여부 컬럼 추가 (마이그레이션)
+    // play_duration은 실제로 들은 시간(초), completed는 끝까지 들었으면 1 / 중간에 넘겼으면 0
+    // (둘 다 NULL이면 아직 마무리되지 않았거나 비정상 종료로 남은 기록)
+    let completed_added = conn
+        .execute("ALTER TABLE play_history ADD COLUMN completed INTEGER", [])
+        .is_ok(); // 이미 존재하면 무시
+    if completed_added {
+        // 컬럼이 생기기 전 기록은 들은 시간을 남기지 않았으므로 모두 재생 횟수로 인정되던 기록
+        // 많이 들은 곡이 비지 않도록 컬럼을 추가할 때 한 번만 완료로 채움 (이후 생기는 미완료 기록은 건드리지 않음)
+        conn.execute(
+            "UPDATE play_history SET completed = 1 WHERE completed IS NULL AND play_duration IS NULL",
+            [],
+        )?;
+    }
+
     // queue_events 테이블 (대기열 추가 히스토리)
     conn.execute(
         "CREATE TABLE IF NOT EXISTS queue_events (
@@ -153,6 +220,68 @@ pub fn run_migrations(conn: &Connection) -> Result<()> {
         [],
     )?;
 
+    // song_markers 테이블 (곡별 이름 붙인 시간 위치)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS song_markers (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            song_id INTEGER NOT NULL,
+            name TEXT NOT NULL,
+            position REAL NOT NULL,
+            created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (song_id) REFERENCES songs(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_song_markers_song_id ON song_markers(song_id)",
+        [],
+    )?;
+
+    // song_chapters 테이블 (파일에 들어 있는 챕터, 스캔 시 추출)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS song_chapters (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            song_id INTEGER NOT NULL,
+            position INTEGER NOT NULL,
+            title TEXT NOT NULL,
+            start_time REAL NOT NULL,
+            end_time REAL,
+            FOREIGN KEY (song_id) REFERENCES songs(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_song_chapters_song_id ON song_chapters(song_id)",
+        [],
+    )?;
+
+    // eq_presets 테이블 (이퀄라이저 프리셋, bands는 JSON 배열)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS eq_presets (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE,
+            preamp_db REAL NOT NULL DEFAULT 0,
+            bands TEXT NOT NULL,
+            created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+            updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+
+    // play_queue 테이블 (재생 대기열, position은 재생 순서 / original_position은 셔플 전 순서)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS play_queue (
+            entry_id INTEGER PRIMARY KEY,
+            song_id INTEGER NOT NULL,
+            position INTEGER NOT NULL,
+            original_position INTEGER NOT NULL,
+            FOREIGN KEY (song_id) REFERENCES songs(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
     // settings 테이블 (앱 설정)
     conn.execute(
         "CREATE TABLE IF NOT EXISTS settings (
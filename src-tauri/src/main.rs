@@ -4,6 +4,7 @@
 mod commands;
 mod database;
 mod models;
+mod audio;
 
 use database::{get_connection, run_migrations};
 use commands::{
@@ -13,12 +14,34 @@ use commands::{
     get_songs_by_folder, get_songs_by_playlist, get_all_songs, get_song_by_id, get_song_metadata_details, update_song_metadata, update_song_tags, get_all_tags, get_album_art_cache_path, clear_album_art_cache, prune_album_art_cache, open_song_location,
     get_video_sync, set_video_sync, update_video_sync_delay, clear_video_sync,
     get_audio_duration, get_file_sizes, get_current_generating_waveform_song_id,
-    play_audio, pause_audio, resume_audio, stop_audio, seek_audio, set_volume,
+    play_audio, pause_audio, resume_audio, stop_audio, seek_audio, set_next_track, set_volume,
+    get_playback_status, restore_session, save_session_checkpoint,
+    set_sleep_timer, get_sleep_timer, cancel_sleep_timer,
     get_saved_volume, extract_waveform,
+    get_crossfade_settings, set_crossfade_settings,
+    get_tempo_settings, set_tempo_settings,
+    set_ab_loop, clear_ab_loop, get_ab_loop,
+    get_song_markers, add_song_marker, update_song_marker, delete_song_marker,
+    get_song_chapters, get_song_remember_position, set_song_remember_position,
+    get_queue, queue_add, queue_play_next, queue_remove, queue_move, queue_clear,
+    set_queue_shuffle, set_queue_repeat, play_queue_index, queue_next, queue_previous,
+    get_replaygain_settings, set_replaygain_settings,
+    list_output_devices, get_output_device, set_output_device,
+    get_bit_perfect_mode, set_bit_perfect_mode,
+    get_surround_upmix, set_surround_upmix,
+    get_channel_options, set_channel_options,
+    get_skip_unplayable_files, set_skip_unplayable_files,
+    get_skip_silence_settings, set_skip_silence_settings, extract_waveform_with_silence,
+    get_audio_sink, set_audio_sink,
+    get_transition_fade_ms, set_transition_fade_ms,
+    set_spectrum_feed, get_spectrum_snapshot,
+    start_loudness_scan, get_loudness_scan_status, cancel_loudness_scan,
+    get_eq_settings, set_eq_settings, get_eq_presets, save_eq_preset, delete_eq_preset, apply_eq_preset,
+    get_dynamics_settings, set_dynamics_settings, apply_dynamics_preset,
     get_table_columns, set_table_columns,
     get_table_column_widths, set_table_column_widths,
     get_audio_format_info,
-    get_dashboard_stats, record_queue_event,
+    get_dashboard_stats, record_queue_event, get_play_count_threshold, set_play_count_threshold,
 };
 
 fn main() {
@@ -72,8 +95,73 @@ fn main() {
             resume_audio,
             stop_audio,
             seek_audio,
+            get_playback_status,
+            restore_session,
+            set_sleep_timer,
+            get_sleep_timer,
+            cancel_sleep_timer,
+            set_next_track,
             set_volume,
             get_saved_volume,
+            get_crossfade_settings,
+            set_crossfade_settings,
+            get_tempo_settings,
+            set_tempo_settings,
+            set_ab_loop,
+            clear_ab_loop,
+            get_ab_loop,
+            get_song_markers,
+            add_song_marker,
+            update_song_marker,
+            delete_song_marker,
+            get_song_chapters,
+            get_song_remember_position,
+            set_song_remember_position,
+            get_queue,
+            queue_add,
+            queue_play_next,
+            queue_remove,
+            queue_move,
+            queue_clear,
+            set_queue_shuffle,
+            set_queue_repeat,
+            play_queue_index,
+            queue_next,
+            queue_previous,
+            get_replaygain_settings,
+            set_replaygain_settings,
+            list_output_devices,
+            get_output_device,
+            set_output_device,
+            get_bit_perfect_mode,
+            set_bit_perfect_mode,
+            get_surround_upmix,
+            set_surround_upmix,
+            get_channel_options,
+            set_channel_options,
+            get_skip_unplayable_files,
+            set_skip_unplayable_files,
+            get_skip_silence_settings,
+            set_skip_silence_settings,
+            extract_waveform_with_silence,
+            get_audio_sink,
+            set_audio_sink,
+            get_transition_fade_ms,
+            set_transition_fade_ms,
+            set_spectrum_feed,
+            get_spectrum_snapshot,
+            start_loudness_scan,
+            get_loudness_scan_status,
+            cancel_loudness_scan,
+            get_eq_settings,
+            set_eq_settings,
+            get_eq_presets,
+            save_eq_preset,
+            delete_eq_preset,
+            apply_eq_preset,
+            get_dynamics_settings,
+            set_dynamics_settings,
+            apply_dynamics_preset,
             extract_waveform,
             get_table_columns,
             set_table_columns,
@@ -82,7 +170,15 @@ fn main() {
             get_audio_format_info,
             get_dashboard_stats,
             record_queue_event,
+            get_play_count_threshold,
+            set_play_count_threshold,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application")
+        .run(|_app_handle, event| {
+            // 종료 직전 재생 세션 저장
+            if let tauri::RunEvent::Exit = event {
+                save_session_checkpoint();
+            }
+        });
 }